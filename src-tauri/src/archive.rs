@@ -0,0 +1,552 @@
+use crate::clipboard::{self, ClipboardKind};
+use crate::db::now_timestamp;
+use crate::encryption;
+use crate::models::CodeSnippet;
+use crate::secrets::SecretAction;
+use crate::snippets::{
+    apply_patch, content_hash, find_by_hash, insert_snippet_at, screen_code, validate_new,
+    NewSnippet, SnippetPatch,
+};
+use crate::validation::SnippetLimits;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqliteConnection, SqlitePool};
+
+/// 归档文件格式标识
+pub const ARCHIVE_FORMAT: &str = "seekcode-archive";
+/// 当前归档格式版本，格式发生不兼容变化时递增
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// 与本机环境相关、不随归档迁移的设置项
+const NON_PORTABLE_SETTINGS: &[&str] = &[
+    "autostart_enabled",
+    "encryption_enabled",
+    "encryption_salt",
+    "encryption_verifier",
+    "encryption_key_source",
+    "clipboard_capture_paused_until",
+    "sync_folder_path",
+    "sync_folder_enabled",
+    "git_sync_path",
+    "git_sync_remote",
+    "auto_backup_dir",
+];
+
+/// 导出归档
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Archive {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    #[serde(default)]
+    pub app_version: String,
+    pub snippets: Vec<ArchiveSnippet>,
+    #[serde(default)]
+    pub settings: Vec<ArchiveSetting>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clipboard: Option<Vec<ArchiveClipboardItem>>,
+}
+
+/// 归档中的代码片段（不包含 id，避免导入时冲突）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveSnippet {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub language: String,
+    #[serde(default)]
+    pub code: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+/// 归档中的用户设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveSetting {
+    pub key: String,
+    pub value: String,
+}
+
+/// 归档中的剪贴板记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveClipboardItem {
+    pub content: String,
+    pub created_at: String,
+    pub updated_at: String,
+    /// 内容类型，旧版归档中没有该字段，视为 text
+    #[serde(default = "default_clipboard_kind")]
+    pub kind: String,
+    /// HTML 原文或 PNG 图片（base64）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+}
+
+fn default_clipboard_kind() -> String {
+    ClipboardKind::Text.as_str().to_string()
+}
+
+/// 导出选项
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportOptions {
+    #[serde(default = "default_true")]
+    pub include_settings: bool,
+    #[serde(default)]
+    pub include_clipboard: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            include_settings: true,
+            include_clipboard: false,
+        }
+    }
+}
+
+/// 导出结果摘要
+#[derive(Debug, Serialize)]
+pub struct ExportSummary {
+    pub path: String,
+    pub version: u32,
+    pub snippets: usize,
+    pub settings: usize,
+    pub clipboard_items: usize,
+}
+
+/// 导入模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// 合并：新内容直接写入，内容重复的代码片段合并标签
+    Merge,
+    /// 跳过重复：内容重复的代码片段直接跳过
+    SkipDuplicates,
+    /// 覆盖：先清空现有代码片段，再写入归档内容
+    Overwrite,
+}
+
+/// 导入报告（dry run 时为预计结果）
+#[derive(Debug, Default, Serialize)]
+pub struct ImportReport {
+    pub archive_version: u32,
+    pub dry_run: bool,
+    pub snippets_total: usize,
+    pub snippets_created: usize,
+    pub snippets_merged: usize,
+    pub snippets_skipped: usize,
+    pub snippets_removed: u64,
+    pub settings_imported: u64,
+    pub clipboard_imported: u64,
+    pub errors: Vec<String>,
+}
+
+/// 外部来源导入预览：解析出的代码片段及 dry run 报告
+#[derive(Debug, Serialize)]
+pub struct ExternalImportPreview {
+    pub snippets: Vec<ArchiveSnippet>,
+    pub report: ImportReport,
+}
+
+// ============================================================================
+// 导出
+// ============================================================================
+
+/// 从数据库构建归档
+pub async fn build_archive(
+    pool: &SqlitePool,
+    options: &ExportOptions,
+    app_version: &str,
+) -> Result<Archive, String> {
+    let rows = sqlx::query_as::<_, CodeSnippet>(
+        "SELECT id, title, language, code, tags, created_at, updated_at
+         FROM code_snippets ORDER BY created_at",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to read snippets: {}", e))?;
+
+    let snippets: Vec<ArchiveSnippet> = rows
+        .into_iter()
+        .map(|row| {
+            Ok(ArchiveSnippet {
+                title: row.title,
+                language: row.language,
+                code: encryption::open(&row.code)?,
+                tags: serde_json::from_str(&row.tags).unwrap_or_default(),
+                created_at: Some(row.created_at),
+                updated_at: Some(row.updated_at),
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    let settings = if options.include_settings {
+        sqlx::query("SELECT key, value FROM user_settings ORDER BY key")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to read settings: {}", e))?
+            .into_iter()
+            .map(|row| ArchiveSetting {
+                key: row.get("key"),
+                value: row.get("value"),
+            })
+            .filter(|setting| !NON_PORTABLE_SETTINGS.contains(&setting.key.as_str()))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let clipboard = if options.include_clipboard {
+        let items = sqlx::query(
+            "SELECT kind, content, data, pinned, created_at, updated_at FROM clipboard_items
+             ORDER BY created_at",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to read clipboard history: {}", e))?
+        .into_iter()
+        .map(|row| {
+            let data = row
+                .get::<Option<Vec<u8>>, _>("data")
+                .map(|stored| encryption::open_bytes(&stored))
+                .transpose()?;
+            Ok(ArchiveClipboardItem {
+                content: encryption::open(row.get("content"))?,
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+                kind: row.get("kind"),
+                data: data.map(|data| BASE64.encode(data)),
+                pinned: row.get("pinned"),
+            })
+        })
+        .collect::<Result<_, String>>()?;
+        Some(items)
+    } else {
+        None
+    };
+
+    Ok(Archive {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        exported_at: now_timestamp(),
+        app_version: app_version.to_string(),
+        snippets,
+        settings,
+        clipboard,
+    })
+}
+
+/// 导出归档到文件
+pub async fn export_to_file(
+    pool: &SqlitePool,
+    path: &str,
+    options: &ExportOptions,
+    app_version: &str,
+) -> Result<ExportSummary, String> {
+    let archive = build_archive(pool, options, app_version).await?;
+    let json = serde_json::to_string_pretty(&archive)
+        .map_err(|e| format!("Failed to serialize archive: {}", e))?;
+
+    if let Some(parent) = std::path::Path::new(path).parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    std::fs::write(path, json).map_err(|e| format!("Failed to write file: {}", e))?;
+
+    Ok(ExportSummary {
+        path: path.to_string(),
+        version: archive.version,
+        snippets: archive.snippets.len(),
+        settings: archive.settings.len(),
+        clipboard_items: archive.clipboard.map(|items| items.len()).unwrap_or(0),
+    })
+}
+
+// ============================================================================
+// 导入
+// ============================================================================
+
+/// 解析归档内容，兼容旧版本导出的纯代码片段数组（视为版本 0）
+pub fn parse_archive(contents: &str) -> Result<Archive, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(contents).map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    if value.is_array() {
+        let snippets: Vec<ArchiveSnippet> = serde_json::from_value(value)
+            .map_err(|e| format!("Invalid legacy snippet list: {}", e))?;
+        return Ok(Archive {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: 0,
+            exported_at: String::new(),
+            app_version: String::new(),
+            snippets,
+            settings: Vec::new(),
+            clipboard: None,
+        });
+    }
+
+    let format = value.get("format").and_then(|f| f.as_str()).unwrap_or("");
+    if format != ARCHIVE_FORMAT {
+        return Err(format!("Unrecognized archive format '{}'", format));
+    }
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version > ARCHIVE_VERSION as u64 {
+        return Err(format!(
+            "Archive version {} is newer than the supported version {}, please update SeekCode",
+            version, ARCHIVE_VERSION
+        ));
+    }
+
+    serde_json::from_value(value).map_err(|e| format!("Invalid archive: {}", e))
+}
+
+/// 导入归档，所有写入在单个事务中完成；dry_run 时回滚并返回预计结果
+pub async fn import_archive(
+    pool: &SqlitePool,
+    archive: Archive,
+    mode: ImportMode,
+    dry_run: bool,
+) -> Result<ImportReport, String> {
+    let limits = SnippetLimits::load(pool).await;
+    let snippet_action = SecretAction::for_snippets(pool).await;
+    let clipboard_action = SecretAction::for_clipboard(pool).await;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let mut report = ImportReport {
+        archive_version: archive.version,
+        dry_run,
+        snippets_total: archive.snippets.len(),
+        ..Default::default()
+    };
+
+    import_snippets(
+        &mut tx,
+        archive.snippets,
+        mode,
+        &limits,
+        snippet_action,
+        &mut report,
+    )
+    .await?;
+    report.settings_imported = import_settings(&mut tx, &archive.settings, mode).await?;
+    if let Some(items) = &archive.clipboard {
+        report.clipboard_imported =
+            import_clipboard(&mut tx, items, mode, clipboard_action).await?;
+    }
+
+    if dry_run {
+        tx.rollback().await.map_err(|e| e.to_string())?;
+    } else {
+        tx.commit().await.map_err(|e| e.to_string())?;
+    }
+    Ok(report)
+}
+
+async fn import_snippets(
+    conn: &mut SqliteConnection,
+    items: Vec<ArchiveSnippet>,
+    mode: ImportMode,
+    limits: &SnippetLimits,
+    action: SecretAction,
+    report: &mut ImportReport,
+) -> Result<(), String> {
+    if mode == ImportMode::Overwrite {
+        report.snippets_removed = sqlx::query("DELETE FROM code_snippets")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?
+            .rows_affected();
+    }
+
+    for (index, item) in items.into_iter().enumerate() {
+        // 与新建代码片段一样按 snippet_secret_action 处理密钥
+        let input = screen_code(item.code, action).and_then(|code| {
+            validate_new(
+                NewSnippet {
+                    title: item.title.clone(),
+                    language: item.language,
+                    code,
+                    tags: item.tags,
+                },
+                limits,
+            )
+        });
+        let input = match input {
+            Ok(input) => input,
+            Err(e) => {
+                report
+                    .errors
+                    .push(format!("#{} '{}': {}", index + 1, item.title, e));
+                continue;
+            }
+        };
+
+        let existing = find_by_hash(&mut *conn, &content_hash(&input.code))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        match existing {
+            Some(existing) if mode == ImportMode::Merge => {
+                let mut tags: Vec<String> =
+                    serde_json::from_str(&existing.tags).unwrap_or_default();
+                let before = tags.len();
+                for tag in &input.tags {
+                    if !tags.contains(tag) {
+                        tags.push(tag.clone());
+                    }
+                }
+                if tags.len() != before {
+                    let patch = SnippetPatch {
+                        tags: Some(tags),
+                        ..Default::default()
+                    };
+                    if let Err(e) = apply_patch(&mut *conn, existing.id, patch, limits).await {
+                        report
+                            .errors
+                            .push(format!("#{} '{}': {}", index + 1, item.title, e));
+                        continue;
+                    }
+                }
+                report.snippets_merged += 1;
+            }
+            Some(_) => report.snippets_skipped += 1,
+            None => {
+                let now = now_timestamp();
+                let created_at = item.created_at.unwrap_or_else(|| now.clone());
+                let updated_at = item.updated_at.unwrap_or_else(|| created_at.clone());
+                insert_snippet_at(&mut *conn, &input, &created_at, &updated_at)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                report.snippets_created += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn import_settings(
+    conn: &mut SqliteConnection,
+    settings: &[ArchiveSetting],
+    mode: ImportMode,
+) -> Result<u64, String> {
+    let now = now_timestamp();
+    let mut imported = 0;
+
+    for setting in settings {
+        if NON_PORTABLE_SETTINGS.contains(&setting.key.as_str()) {
+            continue;
+        }
+        let sql = if mode == ImportMode::Overwrite {
+            "INSERT OR REPLACE INTO user_settings (key, value, created_at, updated_at)
+             VALUES (?, ?, COALESCE((SELECT created_at FROM user_settings WHERE key = ?), ?), ?)"
+        } else {
+            "INSERT OR IGNORE INTO user_settings (key, value, created_at, updated_at)
+             VALUES (?, ?, COALESCE((SELECT created_at FROM user_settings WHERE key = ?), ?), ?)"
+        };
+        imported += sqlx::query(sql)
+            .bind(&setting.key)
+            .bind(&setting.value)
+            .bind(&setting.key)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?
+            .rows_affected();
+    }
+    Ok(imported)
+}
+
+async fn import_clipboard(
+    conn: &mut SqliteConnection,
+    items: &[ArchiveClipboardItem],
+    mode: ImportMode,
+    action: SecretAction,
+) -> Result<u64, String> {
+    if mode == ImportMode::Overwrite {
+        sqlx::query("DELETE FROM clipboard_items")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    let mut imported = 0;
+    for item in items {
+        let data = item
+            .data
+            .as_deref()
+            .map(|data| BASE64.decode(data))
+            .transpose()
+            .map_err(|e| format!("Invalid clipboard data: {}", e))?;
+        // 与捕获剪贴板一样按 clipboard_secret_action 处理密钥
+        let Some(screened) = clipboard::screen_content(
+            ClipboardKind::parse(&item.kind),
+            &item.content,
+            data,
+            action,
+        ) else {
+            continue;
+        };
+
+        // 加密后的内容无法在 SQL 中比较，按创建时间取出后解密比较
+        let existing: Vec<String> =
+            sqlx::query_scalar("SELECT content FROM clipboard_items WHERE created_at = ?")
+                .bind(&item.created_at)
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+        if existing
+            .iter()
+            .any(|content| encryption::open(content).as_deref() == Ok(screened.content.as_str()))
+        {
+            continue;
+        }
+
+        let (kind, data) = (screened.kind, screened.data);
+        let thumbnail = match (kind, data.as_deref()) {
+            (ClipboardKind::Image, Some(png)) => Some(clipboard::png_thumbnail(png)?),
+            _ => None,
+        };
+        let content_hash = encryption::content_digest(&clipboard::fingerprint(
+            kind.as_str(),
+            &screened.content,
+            data.as_deref(),
+        ));
+
+        imported += sqlx::query(
+            "INSERT INTO clipboard_items
+             (kind, mime_type, content, data, thumbnail, content_hash, secret_flags, pinned,
+              created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(kind.as_str())
+        .bind(kind.mime_type())
+        .bind(encryption::seal(&screened.content)?)
+        .bind(data.as_deref().map(encryption::seal_bytes).transpose()?)
+        .bind(
+            thumbnail
+                .as_deref()
+                .map(encryption::seal_bytes)
+                .transpose()?,
+        )
+        .bind(&content_hash)
+        .bind(&screened.secret_flags)
+        .bind(item.pinned)
+        .bind(&item.created_at)
+        .bind(&item.updated_at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .rows_affected();
+    }
+    Ok(imported)
+}
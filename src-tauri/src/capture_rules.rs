@@ -0,0 +1,332 @@
+use crate::db::{get_setting, set_setting};
+use crate::i18n::{t, t_with};
+use chrono::{Local, NaiveDateTime, NaiveTime};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+#[cfg(unix)]
+use tokio::process::Command;
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+const TIME_OF_DAY_FORMAT: &str = "%H:%M";
+
+// ============================================================================
+// 规则配置
+// ============================================================================
+
+/// 剪贴板记录规则，各项分别存储在 user_settings 中
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaptureRules {
+    /// 匹配任一正则的内容不记录（clipboard_exclude_patterns，JSON 数组）
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
+    /// 去除首尾空白后的最小字符数，0 表示不限制（clipboard_min_length）
+    #[serde(default)]
+    pub min_length: usize,
+    /// 最大字符数，0 表示不限制（clipboard_max_length）
+    #[serde(default)]
+    pub max_length: usize,
+    /// 来自这些应用的内容不记录，按名称不区分大小写包含匹配（clipboard_excluded_apps，JSON 数组）
+    ///
+    /// 只在系统能报告前台应用时生效（macOS、装有 xdotool 的 X11 桌面）。
+    #[serde(default)]
+    pub excluded_apps: Vec<String>,
+    /// 每日暂停时段的开始和结束（HH:MM，可跨越午夜），均设置时生效
+    #[serde(default)]
+    pub quiet_start: Option<String>,
+    #[serde(default)]
+    pub quiet_end: Option<String>,
+}
+
+fn parse_time_of_day(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), TIME_OF_DAY_FORMAT)
+        .map_err(|_| t_with("errors.invalidTimeOfDay", &[("value", &value)]))
+}
+
+fn parse_list(value: Option<String>) -> Vec<String> {
+    value
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default()
+}
+
+fn parse_length(value: Option<String>) -> usize {
+    value
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or(0)
+}
+
+impl CaptureRules {
+    /// 从 user_settings 读取规则
+    pub async fn load(pool: &SqlitePool) -> Self {
+        let non_empty = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+        Self {
+            exclude_patterns: parse_list(get_setting(pool, "clipboard_exclude_patterns").await),
+            min_length: parse_length(get_setting(pool, "clipboard_min_length").await),
+            max_length: parse_length(get_setting(pool, "clipboard_max_length").await),
+            excluded_apps: parse_list(get_setting(pool, "clipboard_excluded_apps").await),
+            quiet_start: non_empty(get_setting(pool, "clipboard_quiet_start").await),
+            quiet_end: non_empty(get_setting(pool, "clipboard_quiet_end").await),
+        }
+    }
+
+    /// 校验规则（正则、时间格式、长度范围），去掉空白条目
+    pub fn validate(self) -> Result<Self, String> {
+        let clean = |items: Vec<String>| -> Vec<String> {
+            items
+                .into_iter()
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        };
+        let exclude_patterns = clean(self.exclude_patterns);
+        for pattern in &exclude_patterns {
+            Regex::new(pattern).map_err(|e| {
+                t_with(
+                    "errors.invalidPattern",
+                    &[("pattern", pattern), ("error", &e)],
+                )
+            })?;
+        }
+        if self.max_length > 0 && self.min_length > self.max_length {
+            return Err(t("errors.minLengthExceedsMax"));
+        }
+
+        let quiet_start = self.quiet_start.filter(|v| !v.trim().is_empty());
+        let quiet_end = self.quiet_end.filter(|v| !v.trim().is_empty());
+        if quiet_start.is_some() != quiet_end.is_some() {
+            return Err(t("errors.quietHoursIncomplete"));
+        }
+        for value in quiet_start.iter().chain(quiet_end.iter()) {
+            parse_time_of_day(value)?;
+        }
+
+        Ok(Self {
+            exclude_patterns,
+            min_length: self.min_length,
+            max_length: self.max_length,
+            excluded_apps: clean(self.excluded_apps),
+            quiet_start,
+            quiet_end,
+        })
+    }
+
+    /// 校验后写入 user_settings（单个事务）
+    pub async fn save(self, pool: &SqlitePool) -> Result<Self, String> {
+        let rules = self.validate()?;
+        let to_json = |items: &Vec<String>| serde_json::to_string(items).unwrap_or_default();
+
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        set_setting(
+            &mut *tx,
+            "clipboard_exclude_patterns",
+            &to_json(&rules.exclude_patterns),
+        )
+        .await?;
+        set_setting(
+            &mut *tx,
+            "clipboard_min_length",
+            &rules.min_length.to_string(),
+        )
+        .await?;
+        set_setting(
+            &mut *tx,
+            "clipboard_max_length",
+            &rules.max_length.to_string(),
+        )
+        .await?;
+        set_setting(
+            &mut *tx,
+            "clipboard_excluded_apps",
+            &to_json(&rules.excluded_apps),
+        )
+        .await?;
+        set_setting(
+            &mut *tx,
+            "clipboard_quiet_start",
+            rules.quiet_start.as_deref().unwrap_or(""),
+        )
+        .await?;
+        set_setting(
+            &mut *tx,
+            "clipboard_quiet_end",
+            rules.quiet_end.as_deref().unwrap_or(""),
+        )
+        .await?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+        Ok(rules)
+    }
+
+    /// 判断当前时间是否处于每日暂停时段
+    fn in_quiet_hours(&self, now: NaiveTime) -> bool {
+        let (Some(start), Some(end)) = (&self.quiet_start, &self.quiet_end) else {
+            return false;
+        };
+        let (Ok(start), Ok(end)) = (parse_time_of_day(start), parse_time_of_day(end)) else {
+            return false;
+        };
+        if start <= end {
+            now >= start && now < end
+        } else {
+            // 跨越午夜，如 22:00 - 07:00
+            now >= start || now < end
+        }
+    }
+
+    /// 按规则检查内容，返回不记录的原因；None 表示可以记录
+    pub fn check(
+        &self,
+        content: &str,
+        source_app: Option<&str>,
+        now: NaiveDateTime,
+    ) -> Option<SkipReason> {
+        if self.in_quiet_hours(now.time()) {
+            return Some(SkipReason::QuietHours);
+        }
+
+        let length = content.trim().chars().count();
+        if length < self.min_length {
+            return Some(SkipReason::TooShort);
+        }
+        if self.max_length > 0 && content.chars().count() > self.max_length {
+            return Some(SkipReason::TooLong);
+        }
+
+        if let Some(app) = source_app {
+            let app_lower = app.to_lowercase();
+            if self
+                .excluded_apps
+                .iter()
+                .any(|excluded| app_lower.contains(&excluded.to_lowercase()))
+            {
+                return Some(SkipReason::ExcludedApp(app.to_string()));
+            }
+        }
+
+        self.exclude_patterns
+            .iter()
+            .find(|pattern| Regex::new(pattern).is_ok_and(|re| re.is_match(content)))
+            .map(|pattern| SkipReason::Pattern(pattern.clone()))
+    }
+}
+
+/// 未记录剪贴板内容的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    /// 已关闭记录
+    Disabled,
+    /// 已手动暂停记录
+    Paused,
+    /// 处于每日暂停时段
+    QuietHours,
+    TooShort,
+    TooLong,
+    /// 来自排除的应用
+    ExcludedApp(String),
+    /// 匹配排除规则
+    Pattern(String),
+}
+
+// ============================================================================
+// 开关与临时暂停
+// ============================================================================
+
+/// 是否记录剪贴板（clipboard_capture_enabled，默认开启）
+pub async fn is_enabled(pool: &SqlitePool) -> bool {
+    get_setting(pool, "clipboard_capture_enabled")
+        .await
+        .as_deref()
+        != Some("false")
+}
+
+/// 开启或关闭记录，开启时同时取消暂停
+pub async fn set_enabled(pool: &SqlitePool, enabled: bool) -> Result<(), String> {
+    set_setting(
+        pool,
+        "clipboard_capture_enabled",
+        if enabled { "true" } else { "false" },
+    )
+    .await?;
+    if enabled {
+        resume(pool).await?;
+    }
+    Ok(())
+}
+
+/// 暂停记录到的时间（clipboard_capture_paused_until），已过期时返回 None
+pub async fn paused_until(pool: &SqlitePool) -> Option<String> {
+    let until = get_setting(pool, "clipboard_capture_paused_until").await?;
+    let deadline = NaiveDateTime::parse_from_str(until.trim(), TIMESTAMP_FORMAT).ok()?;
+    (deadline > Local::now().naive_local()).then_some(until)
+}
+
+/// 距离暂停结束的剩余时间，未暂停时返回 None
+pub async fn pause_remaining(pool: &SqlitePool) -> Option<std::time::Duration> {
+    let until = paused_until(pool).await?;
+    let deadline = NaiveDateTime::parse_from_str(until.trim(), TIMESTAMP_FORMAT).ok()?;
+    (deadline - Local::now().naive_local()).to_std().ok()
+}
+
+/// 暂停记录 minutes 分钟，返回恢复时间
+pub async fn pause(pool: &SqlitePool, minutes: u32) -> Result<String, String> {
+    if minutes == 0 {
+        return Err(t("errors.pauseTooShort"));
+    }
+    let until = (Local::now() + chrono::Duration::minutes(minutes as i64))
+        .format(TIMESTAMP_FORMAT)
+        .to_string();
+    set_setting(pool, "clipboard_capture_paused_until", &until).await?;
+    Ok(until)
+}
+
+/// 立即恢复记录
+pub async fn resume(pool: &SqlitePool) -> Result<(), String> {
+    set_setting(pool, "clipboard_capture_paused_until", "").await
+}
+
+/// 按开关、暂停状态和规则检查即将记录的内容，返回不记录的原因
+pub async fn evaluate(pool: &SqlitePool, content: &str) -> Option<SkipReason> {
+    if !is_enabled(pool).await {
+        return Some(SkipReason::Disabled);
+    }
+    if paused_until(pool).await.is_some() {
+        return Some(SkipReason::Paused);
+    }
+    let rules = CaptureRules::load(pool).await;
+    // 查询前台应用需要启动外部进程，只在配置了排除应用时进行
+    let source_app = if rules.excluded_apps.is_empty() {
+        None
+    } else {
+        frontmost_app().await
+    };
+    rules.check(content, source_app.as_deref(), Local::now().naive_local())
+}
+
+// ============================================================================
+// 前台应用
+// ============================================================================
+
+/// 当前前台应用的名称，系统不支持或查询失败时返回 None
+pub async fn frontmost_app() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    let output = Command::new("osascript")
+        .args([
+            "-e",
+            "tell application \"System Events\" to get name of first application process whose frontmost is true",
+        ])
+        .output()
+        .await;
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let output = Command::new("xdotool")
+        .args(["getactivewindow", "getwindowclassname"])
+        .output()
+        .await;
+    #[cfg(not(unix))]
+    let output: std::io::Result<std::process::Output> = Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "frontmost application is not available on this platform",
+    ));
+
+    let output = output.ok().filter(|output| output.status.success())?;
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
@@ -0,0 +1,1014 @@
+use crate::capture_rules;
+use crate::db::{get_setting, now_timestamp};
+use crate::encryption;
+use crate::i18n::{t, t_with};
+use crate::language_detect::{self, LanguageDetection};
+use crate::models::{ClipboardItem, CodeSnippet};
+use crate::secrets::{self, SecretAction};
+use crate::snippets::{self, NewSnippet};
+use crate::validation::normalize_language;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use image::{imageops, ImageFormat, RgbaImage};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// 默认保留的剪贴板历史条数，对应 user_settings 中的 max_clipboard_items（由 retention 定期清理）
+pub const DEFAULT_MAX_CLIPBOARD_ITEMS: i64 = 100;
+/// 默认允许保存的最大图片（PNG 编码后），对应 user_settings 中的 clipboard_max_image_mb
+const DEFAULT_MAX_IMAGE_MB: usize = 10;
+/// 缩略图最长边（像素）
+const THUMBNAIL_SIZE: u32 = 256;
+
+const ITEM_COLUMNS: &str =
+    "id, kind, mime_type, content, thumbnail, secret_flags, language, language_confidence,
+     pinned, created_at, updated_at";
+/// 置顶的条目排在最前，其余按时间倒序
+const ITEM_ORDER: &str = "pinned DESC, created_at DESC, id DESC";
+
+lazy_static::lazy_static! {
+    /// 上次轮询看到的系统剪贴板内容摘要，用于判断内容是否变化
+    static ref LAST_SEEN: Mutex<Option<String>> = Mutex::new(None);
+    static ref HTML_TAG: Regex = Regex::new(r"(?s)<[^>]*>").expect("tag pattern is valid");
+}
+
+// ============================================================================
+// 内容类型
+// ============================================================================
+
+/// 剪贴板内容类型，对应 clipboard_items.kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardKind {
+    Text,
+    Html,
+    Image,
+    /// 文件列表，content 中每行一个路径
+    Files,
+}
+
+impl ClipboardKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ClipboardKind::Text => "text",
+            ClipboardKind::Html => "html",
+            ClipboardKind::Image => "image",
+            ClipboardKind::Files => "files",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "html" => ClipboardKind::Html,
+            "image" => ClipboardKind::Image,
+            "files" => ClipboardKind::Files,
+            _ => ClipboardKind::Text,
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ClipboardKind::Text => "text/plain",
+            ClipboardKind::Html => "text/html",
+            ClipboardKind::Image => "image/png",
+            ClipboardKind::Files => "text/uri-list",
+        }
+    }
+}
+
+/// 系统剪贴板中的内容
+#[derive(Debug, Clone)]
+pub enum ClipboardPayload {
+    Text(String),
+    /// HTML 及其纯文本形式
+    Html {
+        html: String,
+        text: String,
+    },
+    /// RGBA 像素
+    Image {
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    },
+    Files(Vec<PathBuf>),
+}
+
+impl ClipboardPayload {
+    /// 内容摘要，只用于判断剪贴板是否变化（不写入数据库）
+    fn seen_digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        match self {
+            ClipboardPayload::Text(text) => hasher.update(text.as_bytes()),
+            ClipboardPayload::Html { html, .. } => hasher.update(html.as_bytes()),
+            ClipboardPayload::Image { rgba, .. } => hasher.update(rgba),
+            ClipboardPayload::Files(paths) => {
+                for path in paths {
+                    hasher.update(path.to_string_lossy().as_bytes());
+                }
+            }
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// 去掉 HTML 标签，得到粗略的纯文本（剪贴板没有提供纯文本形式时使用）
+fn html_to_text(html: &str) -> String {
+    HTML_TAG
+        .replace_all(html, "")
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+/// 用于去重的内容指纹：图片为 PNG 数据的 SHA-256，其余为文本内容
+pub fn fingerprint(kind: &str, content: &str, data: Option<&[u8]>) -> String {
+    match (ClipboardKind::parse(kind), data) {
+        (ClipboardKind::Image, Some(data)) => {
+            format!("image:{:x}", Sha256::digest(data))
+        }
+        _ => content.to_string(),
+    }
+}
+
+// ============================================================================
+// 系统剪贴板
+// ============================================================================
+
+/// 把文件列表写入系统剪贴板（阻塞调用）
+pub fn write_file_list(paths: &[PathBuf]) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set().file_list(paths))
+        .map_err(|e| format!("Failed to write clipboard: {}", e))
+}
+
+/// 读取系统剪贴板，依次尝试文件列表、HTML、纯文本和图片（阻塞调用）
+pub fn read_system_clipboard() -> Result<Option<ClipboardPayload>, String> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?;
+
+    if let Ok(files) = clipboard.get().file_list() {
+        if !files.is_empty() {
+            return Ok(Some(ClipboardPayload::Files(files)));
+        }
+    }
+
+    let text = clipboard
+        .get_text()
+        .ok()
+        .filter(|text| !text.trim().is_empty());
+    if let Ok(html) = clipboard.get().html() {
+        if !html.trim().is_empty() {
+            let text = text.unwrap_or_else(|| html_to_text(&html));
+            return Ok(Some(ClipboardPayload::Html { html, text }));
+        }
+    }
+    if let Some(text) = text {
+        return Ok(Some(ClipboardPayload::Text(text)));
+    }
+
+    if let Ok(image) = clipboard.get_image() {
+        return Ok(Some(ClipboardPayload::Image {
+            width: image.width as u32,
+            height: image.height as u32,
+            rgba: image.bytes.into_owned(),
+        }));
+    }
+    Ok(None)
+}
+
+// ============================================================================
+// 写入
+// ============================================================================
+
+/// 准备写入数据库的条目
+struct PreparedItem {
+    kind: ClipboardKind,
+    /// 文本形式：文本、HTML 的纯文本、图片说明或文件路径列表
+    content: String,
+    /// HTML 原文或 PNG 图片
+    data: Option<Vec<u8>>,
+    thumbnail: Option<Vec<u8>>,
+}
+
+fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(png)
+}
+
+fn thumbnail_of(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let (width, height) = image.dimensions();
+    let scale = (THUMBNAIL_SIZE as f64 / width.max(height).max(1) as f64).min(1.0);
+    encode_png(&imageops::thumbnail(
+        image,
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    ))
+}
+
+/// 为 PNG 图片生成缩略图（用于导入等只有 PNG 数据的场景）
+pub fn png_thumbnail(png: &[u8]) -> Result<Vec<u8>, String> {
+    let image = image::load_from_memory_with_format(png, ImageFormat::Png)
+        .map_err(|e| format!("Failed to decode image: {}", e))?
+        .to_rgba8();
+    thumbnail_of(&image)
+}
+
+/// 把剪贴板内容转换为数据库条目，图片编码为 PNG 并生成缩略图（CPU 密集）
+fn prepare(payload: ClipboardPayload) -> Result<PreparedItem, String> {
+    Ok(match payload {
+        ClipboardPayload::Text(text) => PreparedItem {
+            kind: ClipboardKind::Text,
+            content: text,
+            data: None,
+            thumbnail: None,
+        },
+        ClipboardPayload::Html { html, text } => PreparedItem {
+            kind: ClipboardKind::Html,
+            content: text,
+            data: Some(html.into_bytes()),
+            thumbnail: None,
+        },
+        ClipboardPayload::Image {
+            width,
+            height,
+            rgba,
+        } => {
+            let image = RgbaImage::from_raw(width, height, rgba)
+                .ok_or_else(|| "Invalid image data".to_string())?;
+            PreparedItem {
+                kind: ClipboardKind::Image,
+                content: format!("Image {}×{}", width, height),
+                data: Some(encode_png(&image)?),
+                thumbnail: Some(thumbnail_of(&image)?),
+            }
+        }
+        ClipboardPayload::Files(paths) => PreparedItem {
+            kind: ClipboardKind::Files,
+            content: paths
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            data: None,
+            thumbnail: None,
+        },
+    })
+}
+
+async fn prepare_blocking(payload: ClipboardPayload) -> Result<PreparedItem, String> {
+    tokio::task::spawn_blocking(move || prepare(payload))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 检测文本和 HTML 条目的编程语言，其余类型返回 None
+fn detect_language(kind: ClipboardKind, content: &str) -> Option<LanguageDetection> {
+    matches!(kind, ClipboardKind::Text | ClipboardKind::Html)
+        .then(|| language_detect::detect(content, None))
+}
+
+/// 经密钥筛查后要保存的条目内容
+pub struct ScreenedContent {
+    pub kind: ClipboardKind,
+    pub content: String,
+    pub data: Option<Vec<u8>>,
+    /// 写入 secret_flags 列的 JSON
+    pub secret_flags: String,
+}
+
+/// 按处理方式筛查条目中的密钥，跳过时返回 None
+///
+/// HTML 条目同时检查 HTML 原文（属性等不可见部分也可能包含密钥）；
+/// 遮盖 HTML 时丢弃 HTML 原文，只保留遮盖后的纯文本。
+pub fn screen_content(
+    kind: ClipboardKind,
+    content: &str,
+    data: Option<Vec<u8>>,
+    action: SecretAction,
+) -> Option<ScreenedContent> {
+    let html = match (kind, data.as_deref()) {
+        (ClipboardKind::Html, Some(data)) => Some(String::from_utf8_lossy(data).into_owned()),
+        _ => None,
+    };
+    let html_has_secrets = html
+        .as_deref()
+        .is_some_and(|html| !secrets::scan(html).is_empty());
+    if html_has_secrets && action == SecretAction::Skip {
+        return None;
+    }
+    let screened = secrets::screen(content, action)?;
+    let redact_html = screened != content || (html_has_secrets && action == SecretAction::Redact);
+    let (kind, data, html) = if redact_html && kind == ClipboardKind::Html {
+        (ClipboardKind::Text, None, None)
+    } else {
+        (kind, data, html)
+    };
+
+    // 保留的 HTML 原文中的密钥也要标记
+    let secret_flags = match &html {
+        Some(html) => secrets::flags_json(&format!("{}\n{}", screened, html)),
+        None => secrets::flags_json(&screened),
+    };
+    Some(ScreenedContent {
+        kind,
+        content: screened,
+        data,
+        secret_flags,
+    })
+}
+
+/// 保存条目（启用加密时加密存储），并删除超出保留条数的旧条目
+///
+/// 保存前按 clipboard_secret_action 处理其中的密钥，跳过时返回 None。
+async fn store(pool: &SqlitePool, item: PreparedItem) -> Result<Option<ClipboardItem>, String> {
+    let action = SecretAction::for_clipboard(pool).await;
+    let Some(ScreenedContent {
+        kind,
+        content,
+        data,
+        secret_flags,
+    }) = screen_content(item.kind, &item.content, item.data, action)
+    else {
+        return Ok(None);
+    };
+
+    if let Some(data) = data.as_ref().filter(|_| kind == ClipboardKind::Image) {
+        let max_mb = get_setting(pool, "clipboard_max_image_mb")
+            .await
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .unwrap_or(DEFAULT_MAX_IMAGE_MB);
+        if max_mb > 0 && data.len() > max_mb * 1024 * 1024 {
+            tracing::debug!(
+                "skipped clipboard image: {} bytes exceeds {} MB",
+                data.len(),
+                max_mb
+            );
+            return Ok(None);
+        }
+    }
+
+    let detection = detect_language(kind, &content);
+    // 在写事务中加密，与启用/关闭加密的重写事务串行，避免按旧状态写入
+    let mut tx = pool
+        .begin_with("BEGIN IMMEDIATE")
+        .await
+        .map_err(|e| e.to_string())?;
+    let content_hash =
+        encryption::content_digest(&fingerprint(kind.as_str(), &content, data.as_deref()));
+    let now = now_timestamp();
+    let result = sqlx::query(
+        "INSERT INTO clipboard_items
+         (kind, mime_type, content, data, thumbnail, content_hash, secret_flags,
+          language, language_confidence, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(kind.as_str())
+    .bind(kind.mime_type())
+    .bind(encryption::seal(&content)?)
+    .bind(data.as_deref().map(encryption::seal_bytes).transpose()?)
+    .bind(
+        item.thumbnail
+            .as_deref()
+            .map(encryption::seal_bytes)
+            .transpose()?,
+    )
+    .bind(&content_hash)
+    .bind(&secret_flags)
+    .bind(detection.as_ref().map(|d| d.language.as_str()))
+    .bind(detection.as_ref().map(|d| d.confidence))
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to add clipboard item: {}", e))?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(Some(ClipboardItem {
+        id: result.last_insert_rowid(),
+        content,
+        created_at: now.clone(),
+        updated_at: now,
+        kind: kind.as_str().to_string(),
+        mime_type: kind.mime_type().to_string(),
+        thumbnail: item.thumbnail.as_deref().map(thumbnail_data_url),
+        secret_flags: Some(secret_flags),
+        language_confidence: detection.as_ref().map(|d| d.confidence),
+        language: detection.map(|d| d.language),
+        pinned: false,
+    }))
+}
+
+/// 添加纯文本剪贴板条目
+pub async fn add_item(pool: &SqlitePool, content: &str) -> Result<Option<ClipboardItem>, String> {
+    store(pool, prepare(ClipboardPayload::Text(content.to_string()))?).await
+}
+
+/// 记录剪贴板内容：先按暂停状态和记录规则过滤，再保存
+///
+/// 被规则排除或因包含密钥被跳过时返回 None。
+pub async fn capture_item(
+    pool: &SqlitePool,
+    payload: ClipboardPayload,
+) -> Result<Option<ClipboardItem>, String> {
+    let item = prepare_blocking(payload).await?;
+    if let Some(reason) = capture_rules::evaluate(pool, &item.content).await {
+        tracing::debug!("skipped clipboard content: {:?}", reason);
+        return Ok(None);
+    }
+    store(pool, item).await
+}
+
+/// 轮询系统剪贴板：内容变化且历史中没有相同内容时记录
+///
+/// 启动后的第一次调用只记下当前内容，不保存（与之前前端监听的行为一致）。
+pub async fn capture_system_clipboard(pool: &SqlitePool) -> Result<Option<ClipboardItem>, String> {
+    let Some(payload) = tokio::task::spawn_blocking(read_system_clipboard)
+        .await
+        .map_err(|e| e.to_string())??
+    else {
+        return Ok(None);
+    };
+
+    let seen = payload.seen_digest();
+    {
+        let mut last = LAST_SEEN.lock().unwrap_or_else(|e| e.into_inner());
+        let first_poll = last.is_none();
+        if last.as_deref() == Some(seen.as_str()) {
+            return Ok(None);
+        }
+        *last = Some(seen);
+        if first_poll {
+            return Ok(None);
+        }
+    }
+
+    let item = prepare_blocking(payload).await?;
+    let content_hash = encryption::content_digest(&fingerprint(
+        item.kind.as_str(),
+        &item.content,
+        item.data.as_deref(),
+    ));
+    let exists: Option<i64> =
+        sqlx::query_scalar("SELECT id FROM clipboard_items WHERE content_hash = ? LIMIT 1")
+            .bind(&content_hash)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if exists.is_some() {
+        return Ok(None);
+    }
+
+    if let Some(reason) = capture_rules::evaluate(pool, &item.content).await {
+        tracing::debug!("skipped clipboard content: {:?}", reason);
+        return Ok(None);
+    }
+    store(pool, item).await
+}
+
+/// 批量置顶或取消置顶，返回更新的条数（置顶的条目不受保留策略清理）
+pub async fn set_pinned(pool: &SqlitePool, ids: &[i64], pinned: bool) -> Result<u64, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut updated = 0;
+    for id in ids {
+        updated += sqlx::query("UPDATE clipboard_items SET pinned = ? WHERE id = ?")
+            .bind(pinned)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to pin clipboard item: {}", e))?
+            .rows_affected();
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(updated)
+}
+
+/// 删除指定的条目，返回删除的条数
+pub async fn delete_items(pool: &SqlitePool, ids: &[i64]) -> Result<u64, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut deleted = 0;
+    for id in ids {
+        deleted += sqlx::query("DELETE FROM clipboard_items WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to delete clipboard item: {}", e))?
+            .rows_affected();
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(deleted)
+}
+
+/// 清空剪贴板历史，返回删除的条数
+pub async fn clear(pool: &SqlitePool) -> Result<u64, String> {
+    sqlx::query("DELETE FROM clipboard_items")
+        .execute(pool)
+        .await
+        .map(|result| result.rows_affected())
+        .map_err(|e| format!("Failed to clear clipboard history: {}", e))
+}
+
+// ============================================================================
+// 读取
+// ============================================================================
+
+fn thumbnail_data_url(png: &[u8]) -> String {
+    format!("data:image/png;base64,{}", BASE64.encode(png))
+}
+
+/// 把查询结果行转换为剪贴板条目（解密 content 和缩略图）
+///
+/// 早于语言检测保存的条目没有 language，读取时现场检测。
+fn item_from_row(row: &SqliteRow) -> Result<ClipboardItem, String> {
+    let thumbnail = row
+        .get::<Option<Vec<u8>>, _>("thumbnail")
+        .map(|stored| encryption::open_bytes(&stored))
+        .transpose()?;
+    let content = encryption::open(row.get("content"))?;
+    let kind: String = row.get("kind");
+    let (language, language_confidence) = match row.get::<Option<String>, _>("language") {
+        Some(language) => (Some(language), row.get("language_confidence")),
+        None => match detect_language(ClipboardKind::parse(&kind), &content) {
+            Some(detection) => (Some(detection.language), Some(detection.confidence)),
+            None => (None, None),
+        },
+    };
+    Ok(ClipboardItem {
+        id: row.get("id"),
+        content,
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        kind,
+        mime_type: row.get("mime_type"),
+        thumbnail: thumbnail.as_deref().map(thumbnail_data_url),
+        secret_flags: row.get("secret_flags"),
+        language,
+        language_confidence,
+        pinned: row.get("pinned"),
+    })
+}
+
+/// 获取剪贴板历史，置顶的条目在前，其余按时间倒序
+pub async fn history(pool: &SqlitePool, limit: i64) -> Result<Vec<ClipboardItem>, String> {
+    sqlx::query(&format!(
+        "SELECT {} FROM clipboard_items ORDER BY {} LIMIT ?",
+        ITEM_COLUMNS, ITEM_ORDER
+    ))
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to get clipboard history: {}", e))?
+    .iter()
+    .map(item_from_row)
+    .collect()
+}
+
+/// 根据 ID 获取剪贴板条目
+pub async fn get_item(pool: &SqlitePool, id: i64) -> Result<Option<ClipboardItem>, String> {
+    sqlx::query(&format!(
+        "SELECT {} FROM clipboard_items WHERE id = ?",
+        ITEM_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to get clipboard item: {}", e))?
+    .as_ref()
+    .map(item_from_row)
+    .transpose()
+}
+
+/// 读取条目的完整内容，用于写回系统剪贴板
+pub async fn load_payload(pool: &SqlitePool, id: i64) -> Result<Option<ClipboardPayload>, String> {
+    let Some(row) = sqlx::query("SELECT kind, content, data FROM clipboard_items WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to get clipboard item: {}", e))?
+    else {
+        return Ok(None);
+    };
+
+    let content = encryption::open(row.get("content"))?;
+    let data = row
+        .get::<Option<Vec<u8>>, _>("data")
+        .map(|stored| encryption::open_bytes(&stored))
+        .transpose()?;
+    let kind = ClipboardKind::parse(row.get("kind"));
+
+    Ok(Some(match (kind, data) {
+        (ClipboardKind::Html, Some(html)) => ClipboardPayload::Html {
+            html: String::from_utf8_lossy(&html).into_owned(),
+            text: content,
+        },
+        (ClipboardKind::Image, Some(png)) => tokio::task::spawn_blocking(move || {
+            let image = image::load_from_memory_with_format(&png, ImageFormat::Png)
+                .map_err(|e| format!("Failed to decode image: {}", e))?
+                .to_rgba8();
+            Ok::<_, String>(ClipboardPayload::Image {
+                width: image.width(),
+                height: image.height(),
+                rgba: image.into_raw(),
+            })
+        })
+        .await
+        .map_err(|e| e.to_string())??,
+        (ClipboardKind::Files, _) => {
+            ClipboardPayload::Files(content.lines().map(PathBuf::from).collect())
+        }
+        _ => ClipboardPayload::Text(content),
+    }))
+}
+
+// ============================================================================
+// 搜索
+// ============================================================================
+
+/// 每页默认条数
+const DEFAULT_PAGE_SIZE: i64 = 50;
+/// 每页最大条数
+const MAX_PAGE_SIZE: i64 = 500;
+/// trigram 全文索引要求的最短关键词（字符数），更短时退回 LIKE
+const FTS_MIN_QUERY_CHARS: usize = 3;
+
+/// 剪贴板历史查询条件，未提供的条件不过滤
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ClipboardQuery {
+    /// 关键词（不区分大小写的子串匹配）
+    #[serde(default)]
+    pub text: Option<String>,
+    /// 创建时间范围，YYYY-MM-DD 或 YYYY-MM-DDTHH:MM[:SS]，两端都包含
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    /// 内容类型：text、html、image、files
+    #[serde(default)]
+    pub kinds: Option<Vec<String>>,
+    /// 检测到的编程语言
+    #[serde(default)]
+    pub language: Option<String>,
+    /// 只返回置顶（true）或未置顶（false）的条目
+    #[serde(default)]
+    pub pinned: Option<bool>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+/// 一页查询结果，total 为满足条件的总条数
+#[derive(Debug, Clone, Serialize)]
+pub struct ClipboardPage {
+    pub items: Vec<ClipboardItem>,
+    pub total: i64,
+    pub offset: i64,
+    pub limit: i64,
+}
+
+/// 解析时间范围的一端，返回可与 created_at 比较的字符串
+///
+/// 结束时间返回下一天（只有日期时）或下一秒，配合 `<` 使用，使该日/该秒内的条目都包含在内。
+fn time_bound(value: &str, end: bool) -> Result<String, String> {
+    let value = value.trim();
+    let parsed = if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let start = date.and_time(NaiveTime::MIN);
+        if end {
+            start + Duration::days(1)
+        } else {
+            start
+        }
+    } else {
+        let datetime = ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+            .ok_or_else(|| t_with("errors.invalidDate", &[("value", &value)]))?;
+        if end {
+            datetime + Duration::seconds(1)
+        } else {
+            datetime
+        }
+    };
+    Ok(parsed.format("%Y-%m-%dT%H:%M:%S").to_string())
+}
+
+/// 把关键词转换为 FTS5 短语查询
+fn fts_phrase(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+/// 转义 LIKE 通配符
+fn like_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// 为早于语言检测保存的文本和 HTML 条目补充 language（启动和解锁后执行一次，之后所有写入都会检测语言）
+pub async fn backfill_languages(pool: &SqlitePool) -> Result<u64, String> {
+    let rows = sqlx::query(
+        "SELECT id, kind, content FROM clipboard_items
+         WHERE language IS NULL AND kind IN ('text', 'html')",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for row in &rows {
+        let content = encryption::open(row.get("content"))?;
+        let Some(detection) = detect_language(ClipboardKind::parse(row.get("kind")), &content)
+        else {
+            continue;
+        };
+        sqlx::query(
+            "UPDATE clipboard_items SET language = ?, language_confidence = ? WHERE id = ?",
+        )
+        .bind(&detection.language)
+        .bind(detection.confidence)
+        .bind(row.get::<i64, _>("id"))
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(rows.len() as u64)
+}
+
+/// 每页条数和起始位置
+fn page_bounds(query: &ClipboardQuery) -> (i64, i64) {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    (limit, query.offset.unwrap_or(0).max(0))
+}
+
+/// 除关键词外的查询条件（时间范围、类型、语言、置顶）及其参数
+fn filter_conditions(query: &ClipboardQuery) -> Result<(Vec<String>, Vec<String>), String> {
+    let mut conditions: Vec<String> = Vec::new();
+    let mut params: Vec<String> = Vec::new();
+
+    let from = query
+        .from
+        .as_deref()
+        .map(|v| time_bound(v, false))
+        .transpose()?;
+    let to = query
+        .to
+        .as_deref()
+        .map(|v| time_bound(v, true))
+        .transpose()?;
+    if let (Some(from), Some(to)) = (&from, &to) {
+        if from >= to {
+            return Err(t("errors.invalidDateRange"));
+        }
+    }
+    if let Some(from) = from {
+        conditions.push("created_at >= ?".to_string());
+        params.push(from);
+    }
+    if let Some(to) = to {
+        conditions.push("created_at < ?".to_string());
+        params.push(to);
+    }
+
+    let kinds: Vec<&str> = query
+        .kinds
+        .iter()
+        .flatten()
+        .map(|kind| ClipboardKind::parse(kind).as_str())
+        .collect();
+    if !kinds.is_empty() {
+        conditions.push(format!("kind IN ({})", vec!["?"; kinds.len()].join(", ")));
+        params.extend(kinds.iter().map(|kind| kind.to_string()));
+    }
+
+    if let Some(language) = query.language.as_deref().filter(|l| !l.trim().is_empty()) {
+        conditions.push("language = ?".to_string());
+        params.push(normalize_language(language)?);
+    }
+
+    if let Some(pinned) = query.pinned {
+        conditions.push(if pinned { "pinned = 1" } else { "pinned = 0" }.to_string());
+    }
+    Ok((conditions, params))
+}
+
+fn where_clause(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    }
+}
+
+/// 按关键词、时间范围、类型和语言分页查询剪贴板历史，置顶的条目在前，其余按时间倒序
+///
+/// 未加密时关键词通过 clipboard_fts（trigram 全文索引）匹配；
+/// 启用加密后索引中没有内容，解密后在内存中匹配。
+pub async fn search(pool: &SqlitePool, query: &ClipboardQuery) -> Result<ClipboardPage, String> {
+    let text = query
+        .text
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty());
+    if let Some(text) = text.filter(|_| encryption::is_enabled()) {
+        let keyword = text.to_lowercase();
+        return search_matching(pool, query, |item| {
+            item.content.to_lowercase().contains(&keyword)
+        })
+        .await;
+    }
+
+    let (limit, offset) = page_bounds(query);
+    let (mut conditions, mut params) = filter_conditions(query)?;
+    if let Some(text) = text {
+        if text.chars().count() >= FTS_MIN_QUERY_CHARS {
+            conditions.push(
+                "id IN (SELECT rowid FROM clipboard_fts WHERE clipboard_fts MATCH ?)".to_string(),
+            );
+            params.push(fts_phrase(text));
+        } else {
+            conditions.push("content LIKE ? ESCAPE '\\'".to_string());
+            params.push(like_pattern(text));
+        }
+    }
+    let where_sql = where_clause(&conditions);
+
+    let count_sql = format!("SELECT COUNT(*) FROM clipboard_items{}", where_sql);
+    let mut count = sqlx::query_scalar::<_, i64>(&count_sql);
+    for param in &params {
+        count = count.bind(param);
+    }
+    let total = count
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to search clipboard history: {}", e))?;
+
+    let page_sql = format!(
+        "SELECT {} FROM clipboard_items{} ORDER BY {} LIMIT ? OFFSET ?",
+        ITEM_COLUMNS, where_sql, ITEM_ORDER
+    );
+    let mut select = sqlx::query(&page_sql);
+    for param in &params {
+        select = select.bind(param);
+    }
+    let items = select
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to search clipboard history: {}", e))?
+        .iter()
+        .map(item_from_row)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ClipboardPage {
+        items,
+        total,
+        offset,
+        limit,
+    })
+}
+
+/// 与 search 相同，但忽略 query.text，改为对解密后的条目调用 matches 在内存中筛选
+///
+/// 用于无法在 SQL 中按原文匹配的场景：内容已加密，或返回给调用方的是遮盖后的内容。
+pub async fn search_matching(
+    pool: &SqlitePool,
+    query: &ClipboardQuery,
+    matches: impl Fn(&ClipboardItem) -> bool,
+) -> Result<ClipboardPage, String> {
+    let (limit, offset) = page_bounds(query);
+    let (conditions, params) = filter_conditions(query)?;
+    let select_sql = format!(
+        "SELECT {} FROM clipboard_items{} ORDER BY {}",
+        ITEM_COLUMNS,
+        where_clause(&conditions),
+        ITEM_ORDER
+    );
+
+    let mut select = sqlx::query(&select_sql);
+    for param in &params {
+        select = select.bind(param);
+    }
+    let mut matched = Vec::new();
+    for row in select
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to search clipboard history: {}", e))?
+    {
+        let item = item_from_row(&row)?;
+        if matches(&item) {
+            matched.push(item);
+        }
+    }
+    let total = matched.len() as i64;
+    let items = matched
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect();
+    Ok(ClipboardPage {
+        items,
+        total,
+        offset,
+        limit,
+    })
+}
+
+// ============================================================================
+// 转换为代码片段
+// ============================================================================
+
+/// 建议标题的最大字符数
+const SUGGESTED_TITLE_LENGTH: usize = 60;
+
+/// 从内容中取第一行有意义的文本作为标题：跳过空行、shebang 和只有符号的行，去掉注释标记
+pub fn suggest_title(content: &str) -> Option<String> {
+    // 注释、标题和列表标记（如 //、#、<!--、-->、*），后面需要跟空白才会去掉
+    let is_marker = |c: char| "/*#-!<>".contains(c);
+
+    content.lines().find_map(|line| {
+        let mut line = line.trim();
+        if line.starts_with("#!") {
+            return None;
+        }
+        let rest = line.trim_start_matches(is_marker);
+        if rest.len() < line.len() && (rest.is_empty() || rest.starts_with(char::is_whitespace)) {
+            line = rest.trim_start();
+        }
+        let rest = line.trim_end_matches(is_marker);
+        if rest.len() < line.len() && rest.ends_with(char::is_whitespace) {
+            line = rest.trim_end();
+        }
+        if !line.chars().any(char::is_alphanumeric) {
+            return None;
+        }
+
+        let title: String = line.chars().take(SUGGESTED_TITLE_LENGTH).collect();
+        Some(if line.chars().count() > SUGGESTED_TITLE_LENGTH {
+            format!("{}…", title.trim_end())
+        } else {
+            title
+        })
+    })
+}
+
+/// 把剪贴板条目转换为代码片段
+///
+/// 未提供标题时使用 suggest_title，语言使用条目检测到的语言，创建时间沿用条目的时间。
+/// 已存在相同内容的代码片段时按 duplicate_snippet_policy 返回已有记录或报错。
+/// delete_item 为 true 时转换后删除该剪贴板条目。
+pub async fn promote_item(
+    pool: &SqlitePool,
+    id: i64,
+    title: Option<String>,
+    tags: Vec<String>,
+    delete_item: bool,
+) -> Result<CodeSnippet, String> {
+    let item = get_item(pool, id)
+        .await?
+        .ok_or_else(|| t_with("errors.clipboardItemNotFound", &[("id", &id)]))?;
+    if item.kind == ClipboardKind::Image.as_str() {
+        return Err(t("errors.imageToSnippet"));
+    }
+
+    let title = title
+        .filter(|title| !title.trim().is_empty())
+        .or_else(|| suggest_title(&item.content))
+        .unwrap_or_else(|| format!("Clipboard {}", item.created_at));
+    let input = NewSnippet {
+        title,
+        language: item.language.unwrap_or_default(),
+        code: item.content,
+        tags,
+    };
+    let snippet = snippets::create_snippet_at(pool, input, &item.created_at, &item.updated_at)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_snippet();
+
+    if delete_item {
+        sqlx::query("DELETE FROM clipboard_items WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to delete clipboard item: {}", e))?;
+    }
+    Ok(snippet)
+}
@@ -0,0 +1,327 @@
+use crate::db::now_timestamp;
+use crate::i18n::{t, t_with};
+use crate::models::{Collection, CollectionNode};
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+
+/// 集合名称最大长度
+pub const MAX_COLLECTION_NAME_LENGTH: usize = 100;
+
+/// 指定集合及其全部子集合的 ID（递归 CTE），绑定一个参数：集合 ID
+pub const SUBTREE_SQL: &str = "WITH RECURSIVE subtree(id) AS (
+         SELECT CAST(? AS INTEGER)
+         UNION ALL
+         SELECT c.id FROM collections c JOIN subtree s ON c.parent_id = s.id
+     )
+     SELECT id FROM subtree";
+
+/// 规范化集合名称：去除首尾空白，不允许为空或包含路径分隔符
+fn normalize_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(t("errors.collectionNameEmpty"));
+    }
+    if name.contains('/') {
+        return Err(t("errors.collectionNameHasSlash"));
+    }
+    if name.chars().count() > MAX_COLLECTION_NAME_LENGTH {
+        return Err(t_with(
+            "errors.collectionNameTooLong",
+            &[("max", &MAX_COLLECTION_NAME_LENGTH)],
+        ));
+    }
+    Ok(name.to_string())
+}
+
+async fn get_collection(
+    conn: &mut SqliteConnection,
+    id: i64,
+) -> Result<Option<Collection>, String> {
+    sqlx::query_as::<_, Collection>(
+        "SELECT id, name, parent_id, sort_order, created_at, updated_at
+         FROM collections WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+async fn require_collection(conn: &mut SqliteConnection, id: i64) -> Result<Collection, String> {
+    get_collection(conn, id)
+        .await?
+        .ok_or_else(|| t_with("errors.collectionNotFound", &[("id", &id)]))
+}
+
+/// 同一父集合下不允许重名（不区分大小写）
+async fn ensure_unique_name(
+    conn: &mut SqliteConnection,
+    parent_id: Option<i64>,
+    name: &str,
+    exclude_id: Option<i64>,
+) -> Result<(), String> {
+    let existing: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM collections
+         WHERE parent_id IS ? AND LOWER(name) = LOWER(?) AND id IS NOT ?",
+    )
+    .bind(parent_id)
+    .bind(name)
+    .bind(exclude_id)
+    .fetch_optional(conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    match existing {
+        Some(_) => Err(t_with("errors.collectionNameExists", &[("name", &name)])),
+        None => Ok(()),
+    }
+}
+
+/// 集合及其全部子集合的 ID
+async fn subtree_ids(conn: &mut SqliteConnection, id: i64) -> Result<Vec<i64>, String> {
+    sqlx::query_scalar(SUBTREE_SQL)
+        .bind(id)
+        .fetch_all(conn)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 以树形结构返回全部集合，附带每个集合直接包含的代码片段数
+pub async fn list_collections(pool: &SqlitePool) -> Result<Vec<CollectionNode>, String> {
+    let collections = sqlx::query_as::<_, Collection>(
+        "SELECT id, name, parent_id, sort_order, created_at, updated_at
+         FROM collections ORDER BY sort_order, name COLLATE NOCASE",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let counts: HashMap<i64, i64> = sqlx::query(
+        "SELECT collection_id, COUNT(*) AS count FROM code_snippets
+         WHERE collection_id IS NOT NULL GROUP BY collection_id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .map(|row| (row.get("collection_id"), row.get("count")))
+    .collect();
+
+    let mut children: HashMap<Option<i64>, Vec<Collection>> = HashMap::new();
+    for collection in collections {
+        children
+            .entry(collection.parent_id)
+            .or_default()
+            .push(collection);
+    }
+
+    fn build(
+        parent_id: Option<i64>,
+        children: &mut HashMap<Option<i64>, Vec<Collection>>,
+        counts: &HashMap<i64, i64>,
+    ) -> Vec<CollectionNode> {
+        children
+            .remove(&parent_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|collection| {
+                let id = collection.id;
+                CollectionNode {
+                    snippet_count: counts.get(&id).copied().unwrap_or(0),
+                    children: build(Some(id), children, counts),
+                    collection,
+                }
+            })
+            .collect()
+    }
+
+    Ok(build(None, &mut children, &counts))
+}
+
+/// 创建集合，parent_id 为空时创建顶层集合
+pub async fn create_collection(
+    pool: &SqlitePool,
+    name: &str,
+    parent_id: Option<i64>,
+) -> Result<Collection, String> {
+    let name = normalize_name(name)?;
+    // 查重与写入放在同一个立即获取写锁的事务里，避免并发创建出同名集合
+    let mut tx = pool
+        .begin_with("BEGIN IMMEDIATE")
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(parent_id) = parent_id {
+        require_collection(&mut tx, parent_id).await?;
+    }
+    ensure_unique_name(&mut tx, parent_id, &name, None).await?;
+
+    let now = now_timestamp();
+    let result = sqlx::query(
+        "INSERT INTO collections (name, parent_id, sort_order, created_at, updated_at)
+         VALUES (?, ?, (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM collections WHERE parent_id IS ?), ?, ?)",
+    )
+    .bind(&name)
+    .bind(parent_id)
+    .bind(parent_id)
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let collection = require_collection(&mut tx, result.last_insert_rowid()).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(collection)
+}
+
+/// 重命名集合
+pub async fn rename_collection(
+    pool: &SqlitePool,
+    id: i64,
+    name: &str,
+) -> Result<Collection, String> {
+    let name = normalize_name(name)?;
+    let mut tx = pool
+        .begin_with("BEGIN IMMEDIATE")
+        .await
+        .map_err(|e| e.to_string())?;
+    let collection = require_collection(&mut tx, id).await?;
+    ensure_unique_name(&mut tx, collection.parent_id, &name, Some(id)).await?;
+
+    sqlx::query("UPDATE collections SET name = ?, updated_at = ? WHERE id = ?")
+        .bind(&name)
+        .bind(now_timestamp())
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let collection = require_collection(&mut tx, id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(collection)
+}
+
+/// 移动集合到新的父集合下（parent_id 为空时移到顶层），不允许移到自身或其子集合下
+pub async fn move_collection(
+    pool: &SqlitePool,
+    id: i64,
+    parent_id: Option<i64>,
+) -> Result<Collection, String> {
+    // 环检查与更新放在同一个事务里，避免并发移动形成环
+    let mut tx = pool
+        .begin_with("BEGIN IMMEDIATE")
+        .await
+        .map_err(|e| e.to_string())?;
+    let collection = require_collection(&mut tx, id).await?;
+
+    if let Some(parent_id) = parent_id {
+        require_collection(&mut tx, parent_id).await?;
+        if subtree_ids(&mut tx, id).await?.contains(&parent_id) {
+            return Err(t("errors.collectionMoveIntoSelf"));
+        }
+    }
+    ensure_unique_name(&mut tx, parent_id, &collection.name, Some(id)).await?;
+
+    sqlx::query("UPDATE collections SET parent_id = ?, updated_at = ? WHERE id = ?")
+        .bind(parent_id)
+        .bind(now_timestamp())
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let collection = require_collection(&mut tx, id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(collection)
+}
+
+/// 删除集合及其全部子集合，其中的代码片段移到被删除集合的父集合（顶层集合则变为未分类）
+pub async fn delete_collection(pool: &SqlitePool, id: i64) -> Result<u64, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let collection = require_collection(&mut tx, id).await?;
+    let ids = subtree_ids(&mut tx, id).await?;
+
+    let mut moved = 0;
+    for collection_id in &ids {
+        moved += sqlx::query("UPDATE code_snippets SET collection_id = ? WHERE collection_id = ?")
+            .bind(collection.parent_id)
+            .bind(collection_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .rows_affected();
+        sqlx::query("DELETE FROM collections WHERE id = ?")
+            .bind(collection_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(moved)
+}
+
+/// 将代码片段移入集合，collection_id 为空时移出集合
+pub async fn assign_snippets(
+    pool: &SqlitePool,
+    ids: &[i64],
+    collection_id: Option<i64>,
+) -> Result<u64, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    if let Some(collection_id) = collection_id {
+        require_collection(&mut tx, collection_id).await?;
+    }
+
+    let mut updated = 0;
+    for id in ids {
+        updated += sqlx::query("UPDATE code_snippets SET collection_id = ? WHERE id = ?")
+            .bind(collection_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .rows_affected();
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(updated)
+}
+
+/// 按路径（如 "Backend/SQL"）查找集合；单个名称在顶层找不到时按名称在任意层级查找
+pub async fn resolve_path(pool: &SqlitePool, path: &str) -> Result<Option<i64>, String> {
+    let segments: Vec<&str> = path
+        .split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    if segments.is_empty() {
+        return Ok(None);
+    }
+
+    let mut parent_id: Option<i64> = None;
+    for segment in &segments {
+        let id: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM collections WHERE parent_id IS ? AND LOWER(name) = LOWER(?)",
+        )
+        .bind(parent_id)
+        .bind(segment)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        match id {
+            Some(id) => parent_id = Some(id),
+            None if segments.len() == 1 => {
+                return sqlx::query_scalar(
+                    "SELECT id FROM collections WHERE LOWER(name) = LOWER(?) ORDER BY id LIMIT 1",
+                )
+                .bind(segment)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| e.to_string());
+            }
+            None => return Ok(None),
+        }
+    }
+    Ok(parent_id)
+}
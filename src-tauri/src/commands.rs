@@ -1,11 +1,41 @@
+use crate::archive::{
+    self, ExportOptions, ExportSummary, ExternalImportPreview, ImportMode, ImportReport,
+};
+use crate::capture_rules::{self, CaptureRules};
+use crate::clipboard::{self, ClipboardPage, ClipboardPayload, ClipboardQuery};
+use crate::collections;
+use crate::db::{db_path, get_pool, get_setting, set_setting, with_pool_closed};
+use crate::encryption::{self, EncryptionStatus};
+use crate::exporters;
+use crate::git_sync::{self, GitConflictResolution, GitSyncConfig, GitSyncReport};
+use crate::i18n::{self, t, t_with, Locale};
+use crate::importers::{self, ImportSource};
+use crate::language_detect::{self, LanguageDetection};
+use crate::maintenance::{
+    self, AutoBackupConfig, BackupInfo, IntegrityReport, OptimizeReport, RestoreReport,
+};
 use crate::mcp_server::{
-    get_server_address, is_server_running, start_server_with_permissions, stop_server,
+    get_server_address, get_server_config, is_server_running, start_server_with_permissions,
+    stop_server, ServerPermissions,
 };
+use crate::models::{
+    BatchItemResult, ClipboardItem, CodeSnippet, Collection, CollectionNode, DatabaseStats,
+    DuplicateGroup, SnippetUsage,
+};
+use crate::palette::{self, PaletteItem, PaletteSource};
+use crate::retention::{self, RetentionPolicy, RetentionReport};
+use crate::secrets::{self, SecretFinding};
+use crate::snippets::{self, NewSnippet, SnippetFilter, SnippetPatch};
+use crate::stats;
+use crate::sync::{self, ConflictResolution, FolderSyncStatus, SyncConflict, SyncReport};
+use crate::tray;
+use serde::Serialize;
 use std::fs;
-use std::path::Path;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use tauri::Manager;
 use tauri_plugin_aptabase::EventTracker;
-use tauri_plugin_sql::{Migration, MigrationKind};
+use tauri_plugin_sql::{DbInstances, DbPool, Migration, MigrationKind};
 
 // ============================================================================
 // 数据库迁移定义
@@ -233,6 +263,233 @@ func main() {
             "#,
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 5,
+            description: "add_snippet_limit_settings",
+            sql: r#"
+                INSERT OR IGNORE INTO user_settings (key, value, created_at, updated_at) VALUES
+                ('max_snippet_code_size', '262144', datetime('now'), datetime('now'));
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 6,
+            description: "add_snippet_content_hash",
+            sql: r#"
+                ALTER TABLE code_snippets ADD COLUMN content_hash TEXT;
+
+                CREATE INDEX IF NOT EXISTS idx_snippets_content_hash ON code_snippets(content_hash);
+
+                INSERT OR IGNORE INTO user_settings (key, value, created_at, updated_at) VALUES
+                ('duplicate_snippet_policy', 'return_existing', datetime('now'), datetime('now'));
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 7,
+            description: "add_snippet_sync_state",
+            sql: r#"
+                ALTER TABLE code_snippets ADD COLUMN sync_id TEXT;
+
+                CREATE UNIQUE INDEX IF NOT EXISTS idx_snippets_sync_id ON code_snippets(sync_id);
+
+                CREATE TABLE IF NOT EXISTS sync_state (
+                    target TEXT NOT NULL,
+                    sync_id TEXT NOT NULL,
+                    file_name TEXT NOT NULL,
+                    file_hash TEXT NOT NULL,
+                    db_updated_at TEXT NOT NULL,
+                    synced_at TEXT NOT NULL,
+                    PRIMARY KEY (target, sync_id)
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 8,
+            description: "add_sync_conflicts",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS sync_conflicts (
+                    target TEXT NOT NULL,
+                    sync_id TEXT NOT NULL,
+                    file_name TEXT NOT NULL,
+                    remote_contents TEXT,
+                    detected_at TEXT NOT NULL,
+                    PRIMARY KEY (target, sync_id)
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 9,
+            description: "add_snippet_collections",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS collections (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL,
+                    parent_id INTEGER,
+                    sort_order INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_collections_parent ON collections(parent_id);
+
+                ALTER TABLE code_snippets ADD COLUMN collection_id INTEGER;
+
+                CREATE INDEX IF NOT EXISTS idx_snippets_collection ON code_snippets(collection_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "add_snippet_usage",
+            sql: r#"
+                ALTER TABLE code_snippets ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE code_snippets ADD COLUMN use_count INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE code_snippets ADD COLUMN last_used_at TEXT;
+
+                CREATE INDEX IF NOT EXISTS idx_snippets_usage ON code_snippets(pinned, use_count);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 11,
+            description: "add_auto_backup_settings",
+            sql: r#"
+                INSERT OR IGNORE INTO user_settings (key, value, created_at, updated_at) VALUES
+                ('auto_backup_enabled', 'false', datetime('now'), datetime('now')),
+                ('auto_backup_interval_hours', '24', datetime('now'), datetime('now')),
+                ('auto_backup_keep', '7', datetime('now'), datetime('now'));
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 12,
+            description: "add_encryption_settings",
+            sql: r#"
+                INSERT OR IGNORE INTO user_settings (key, value, created_at, updated_at) VALUES
+                ('encryption_enabled', 'false', datetime('now'), datetime('now'));
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 13,
+            description: "add_secret_flags",
+            sql: r#"
+                ALTER TABLE code_snippets ADD COLUMN secret_flags TEXT;
+                ALTER TABLE clipboard_items ADD COLUMN secret_flags TEXT;
+
+                INSERT OR IGNORE INTO user_settings (key, value, created_at, updated_at) VALUES
+                ('clipboard_secret_action', 'redact', datetime('now'), datetime('now')),
+                ('snippet_secret_action', 'flag', datetime('now'), datetime('now'));
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 14,
+            description: "add_clipboard_capture_rules",
+            sql: r#"
+                INSERT OR IGNORE INTO user_settings (key, value, created_at, updated_at) VALUES
+                ('clipboard_exclude_patterns', '[]', datetime('now'), datetime('now')),
+                ('clipboard_min_length', '3', datetime('now'), datetime('now')),
+                ('clipboard_max_length', '0', datetime('now'), datetime('now')),
+                ('clipboard_excluded_apps', '[]', datetime('now'), datetime('now')),
+                ('clipboard_quiet_start', '', datetime('now'), datetime('now')),
+                ('clipboard_quiet_end', '', datetime('now'), datetime('now')),
+                ('clipboard_capture_paused_until', '', datetime('now'), datetime('now'));
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 15,
+            description: "add_rich_clipboard_items",
+            sql: r#"
+                ALTER TABLE clipboard_items ADD COLUMN kind TEXT NOT NULL DEFAULT 'text';
+                ALTER TABLE clipboard_items ADD COLUMN mime_type TEXT NOT NULL DEFAULT 'text/plain';
+                ALTER TABLE clipboard_items ADD COLUMN data BLOB;
+                ALTER TABLE clipboard_items ADD COLUMN thumbnail BLOB;
+                ALTER TABLE clipboard_items ADD COLUMN content_hash TEXT;
+                CREATE INDEX IF NOT EXISTS idx_clipboard_content_hash ON clipboard_items(content_hash);
+
+                INSERT OR IGNORE INTO user_settings (key, value, created_at, updated_at) VALUES
+                ('clipboard_max_image_mb', '10', datetime('now'), datetime('now'));
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 16,
+            description: "add_clipboard_language",
+            sql: r#"
+                ALTER TABLE clipboard_items ADD COLUMN language TEXT;
+                ALTER TABLE clipboard_items ADD COLUMN language_confidence REAL;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 17,
+            description: "add_clipboard_search",
+            sql: r#"
+                CREATE INDEX IF NOT EXISTS idx_clipboard_created_at ON clipboard_items(created_at);
+                CREATE INDEX IF NOT EXISTS idx_clipboard_kind ON clipboard_items(kind);
+
+                -- 剪贴板内容全文索引（trigram，支持子串搜索），加密后的内容不进入索引
+                CREATE VIRTUAL TABLE IF NOT EXISTS clipboard_fts USING fts5(content, tokenize = 'trigram');
+
+                CREATE TRIGGER IF NOT EXISTS clipboard_fts_insert AFTER INSERT ON clipboard_items
+                WHEN new.content NOT LIKE 'enc:v1:%'
+                BEGIN
+                    INSERT INTO clipboard_fts(rowid, content) VALUES (new.id, new.content);
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS clipboard_fts_delete AFTER DELETE ON clipboard_items
+                BEGIN
+                    DELETE FROM clipboard_fts WHERE rowid = old.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS clipboard_fts_update AFTER UPDATE OF content ON clipboard_items
+                BEGIN
+                    DELETE FROM clipboard_fts WHERE rowid = old.id;
+                    INSERT INTO clipboard_fts(rowid, content)
+                    SELECT new.id, new.content WHERE new.content NOT LIKE 'enc:v1:%';
+                END;
+
+                INSERT INTO clipboard_fts(rowid, content)
+                SELECT id, content FROM clipboard_items WHERE content NOT LIKE 'enc:v1:%';
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 18,
+            description: "add_clipboard_retention",
+            sql: r#"
+                INSERT OR IGNORE INTO user_settings (key, value, created_at, updated_at) VALUES
+                ('clipboard_max_age_days', '0', datetime('now'), datetime('now')),
+                ('clipboard_max_total_mb', '0', datetime('now'), datetime('now'));
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 19,
+            description: "add_clipboard_pinned",
+            sql: r#"
+                ALTER TABLE clipboard_items ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+                CREATE INDEX IF NOT EXISTS idx_clipboard_pinned ON clipboard_items(pinned, created_at);
+
+                INSERT OR IGNORE INTO user_settings (key, value, created_at, updated_at) VALUES
+                ('clipboard_keep_pinned', 'true', datetime('now'), datetime('now'));
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 20,
+            description: "add_palette_shortcut_setting",
+            sql: r#"
+                INSERT OR IGNORE INTO user_settings (key, value, created_at, updated_at) VALUES
+                ('palette_shortcut', 'CommandOrControl+Shift+Space', datetime('now'), datetime('now'));
+            "#,
+            kind: MigrationKind::Up,
+        },
     ]
 }
 
@@ -247,6 +504,21 @@ pub fn get_current_timestamp() -> String {
     chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string()
 }
 
+/// 保存界面语言（system / zh-CN / en-US），同步切换托盘菜单和后端信息的语言，返回实际使用的语言
+#[tauri::command]
+pub async fn set_language(app: tauri::AppHandle, language: String) -> Result<String, String> {
+    let language = language.trim();
+    if !i18n::is_valid_setting(language) {
+        return Err(t_with("errors.invalidLanguage", &[("language", &language)]));
+    }
+    let pool = get_pool(&app).await?;
+    set_setting(&pool, "language", language).await?;
+    let locale = Locale::from_setting(Some(language));
+    i18n::set_current(locale);
+    tray::refresh(&app);
+    Ok(locale.code().to_string())
+}
+
 // ============================================================================
 // 剪贴板相关
 // ============================================================================
@@ -262,115 +534,1393 @@ pub async fn get_clipboard_content(app: tauri::AppHandle) -> Result<String, Stri
         .map_err(|e| format!("Failed to read clipboard: {}", e))
 }
 
-/// 设置系统剪贴板内容
+/// 设置系统剪贴板内容：指定 item_id 时按原类型写回该历史条目（文本、HTML、图片、文件列表），
+/// 否则写入 content 文本
 #[tauri::command]
-pub async fn set_clipboard_content(app: tauri::AppHandle, content: String) -> Result<(), String> {
+pub async fn set_clipboard_content(
+    app: tauri::AppHandle,
+    content: Option<String>,
+    item_id: Option<i64>,
+) -> Result<(), String> {
     use tauri_plugin_clipboard_manager::ClipboardExt;
 
+    let payload = match (item_id, content) {
+        (Some(id), _) => {
+            let pool = get_pool(&app).await?;
+            clipboard::load_payload(&pool, id)
+                .await?
+                .ok_or_else(|| t_with("errors.clipboardItemNotFound", &[("id", &id)]))?
+        }
+        (None, Some(content)) => ClipboardPayload::Text(content),
+        (None, None) => return Err(t("errors.nothingToWrite")),
+    };
+
     let clipboard = app.clipboard();
-    clipboard
-        .write_text(content)
-        .map_err(|e| format!("Failed to write clipboard: {}", e))
+    let result = match payload {
+        ClipboardPayload::Text(text) => clipboard.write_text(text),
+        ClipboardPayload::Html { html, text } => clipboard.write_html(html, Some(text)),
+        ClipboardPayload::Image {
+            width,
+            height,
+            rgba,
+        } => clipboard.write_image(&tauri::image::Image::new_owned(rgba, width, height)),
+        // 剪贴板插件不支持写入文件列表，直接通过 arboard 写入
+        ClipboardPayload::Files(paths) => {
+            return tokio::task::spawn_blocking(move || clipboard::write_file_list(&paths))
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    };
+    result.map_err(|e| format!("Failed to write clipboard: {}", e))
 }
 
-// ============================================================================
-// 窗口控制
-// ============================================================================
-
-/// 显示主窗口
+/// 轮询系统剪贴板（文本、HTML、图片、文件列表），内容变化且未被规则排除时记录，返回新条目
 #[tauri::command]
-pub fn show_window(app: tauri::AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        window
-            .show()
-            .map_err(|e| format!("Failed to show window: {}", e))?;
-        window
-            .set_focus()
-            .map_err(|e| format!("Failed to focus window: {}", e))?;
+pub async fn capture_system_clipboard(
+    app: tauri::AppHandle,
+) -> Result<Option<ClipboardItem>, String> {
+    let pool = ready_pool(&app).await?;
+    let item = clipboard::capture_system_clipboard(&pool).await?;
+    if item.is_some() {
+        tray::refresh(&app);
     }
-    Ok(())
+    Ok(item)
 }
 
-/// 隐藏主窗口
+/// 添加剪贴板历史条目（启用加密时加密存储），包含密钥且设置为跳过时返回 None
 #[tauri::command]
-pub fn hide_window(app: tauri::AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        window
-            .hide()
-            .map_err(|e| format!("Failed to hide window: {}", e))?;
+pub async fn add_clipboard_item(
+    app: tauri::AppHandle,
+    content: String,
+) -> Result<Option<ClipboardItem>, String> {
+    let pool = ready_pool(&app).await?;
+    let item = clipboard::add_item(&pool, &content).await?;
+    if item.is_some() {
+        tray::refresh(&app);
     }
-    Ok(())
+    Ok(item)
 }
 
-/// 检查窗口是否可见
+/// 记录监听到的剪贴板内容（按暂停状态和记录规则过滤），未记录时返回 None
 #[tauri::command]
-pub fn is_window_visible(app: tauri::AppHandle) -> Result<bool, String> {
-    if let Some(window) = app.get_webview_window("main") {
-        window
-            .is_visible()
-            .map_err(|e| format!("Failed to check window visibility: {}", e))
-    } else {
-        Ok(false)
+pub async fn capture_clipboard_item(
+    app: tauri::AppHandle,
+    content: String,
+) -> Result<Option<ClipboardItem>, String> {
+    let pool = ready_pool(&app).await?;
+    let item = clipboard::capture_item(&pool, ClipboardPayload::Text(content)).await?;
+    if item.is_some() {
+        tray::refresh(&app);
     }
+    Ok(item)
 }
 
-// ============================================================================
-// MCP 服务器控制
-// ============================================================================
-
-/// 启动MCP服务器
+/// 获取剪贴板历史（置顶的条目在前）
 #[tauri::command]
-pub async fn start_mcp_server(
+pub async fn get_clipboard_history(
     app: tauri::AppHandle,
-    _host: Option<String>,
-    port: Option<u16>,
-    allow_query: Option<bool>,
-    allow_create: Option<bool>,
-    allow_update: Option<bool>,
-    allow_delete: Option<bool>,
-) -> Result<String, String> {
-    let port = port.unwrap_or(9800);
-    let allow_query = allow_query.unwrap_or(true);
-    let allow_create = allow_create.unwrap_or(true);
-    let allow_update = allow_update.unwrap_or(false);
-    let allow_delete = allow_delete.unwrap_or(false);
+    limit: Option<i64>,
+) -> Result<Vec<ClipboardItem>, String> {
+    let pool = get_pool(&app).await?;
+    clipboard::history(
+        &pool,
+        limit.unwrap_or(clipboard::DEFAULT_MAX_CLIPBOARD_ITEMS),
+    )
+    .await
+}
 
-    // 创建数据库连接池
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .unwrap_or_else(|_| std::path::PathBuf::from("."));
-    let db_path = app_data_dir.join("seekcode.db");
-    let db_url = format!("sqlite:{}", db_path.to_string_lossy());
+/// 根据 ID 获取剪贴板历史条目
+#[tauri::command]
+pub async fn get_clipboard_item(
+    app: tauri::AppHandle,
+    id: i64,
+) -> Result<Option<ClipboardItem>, String> {
+    let pool = get_pool(&app).await?;
+    clipboard::get_item(&pool, id).await
+}
 
-    let db_pool = sqlx::SqlitePool::connect(&db_url)
-        .await
-        .map_err(|e| format!("数据库连接失败: {}", e))?;
+/// 按关键词、时间范围、类型和语言分页搜索剪贴板历史
+#[tauri::command]
+pub async fn search_clipboard(
+    app: tauri::AppHandle,
+    query: ClipboardQuery,
+) -> Result<ClipboardPage, String> {
+    let pool = get_pool(&app).await?;
+    clipboard::search(&pool, &query).await
+}
 
-    match start_server_with_permissions(
-        db_pool,
-        port,
-        allow_query,
-        allow_create,
-        allow_update,
-        allow_delete,
+/// 把剪贴板条目转换为代码片段（自动检测语言、建议标题并沿用条目的时间）
+#[tauri::command]
+pub async fn promote_clipboard_item(
+    app: tauri::AppHandle,
+    id: i64,
+    title: Option<String>,
+    tags: Option<Vec<String>>,
+    delete_item: Option<bool>,
+) -> Result<CodeSnippet, String> {
+    let pool = get_pool(&app).await?;
+    let snippet = clipboard::promote_item(
+        &pool,
+        id,
+        title,
+        tags.unwrap_or_default(),
+        delete_item.unwrap_or(false),
     )
-    .await
-    {
-        Ok(addr) => Ok(format!("MCP服务器启动成功，地址: {}", addr)),
-        Err(e) => Err(format!("启动MCP服务器失败: {}", e)),
-    }
+    .await?;
+    tray::refresh(&app);
+    Ok(snippet)
 }
 
-/// 停止MCP服务器
+/// 置顶或取消置顶剪贴板条目，返回更新的数量
 #[tauri::command]
-pub async fn stop_mcp_server() -> Result<String, String> {
-    match stop_server().await {
-        Ok(_) => Ok("MCP服务器停止成功".to_string()),
-        Err(e) => Err(format!("停止MCP服务器失败: {}", e)),
+pub async fn set_clipboard_items_pinned(
+    app: tauri::AppHandle,
+    ids: Vec<i64>,
+    pinned: bool,
+) -> Result<u64, String> {
+    let pool = get_pool(&app).await?;
+    let updated = clipboard::set_pinned(&pool, &ids, pinned).await?;
+    tray::refresh(&app);
+    Ok(updated)
+}
+
+/// 删除剪贴板历史条目，返回删除的数量
+#[tauri::command]
+pub async fn delete_clipboard_items(app: tauri::AppHandle, ids: Vec<i64>) -> Result<u64, String> {
+    let pool = get_pool(&app).await?;
+    let deleted = clipboard::delete_items(&pool, &ids).await?;
+    tray::refresh(&app);
+    Ok(deleted)
+}
+
+/// 清空剪贴板历史，返回删除的数量
+#[tauri::command]
+pub async fn clear_clipboard_history(app: tauri::AppHandle) -> Result<u64, String> {
+    let pool = get_pool(&app).await?;
+    let deleted = clipboard::clear(&pool).await?;
+    tray::refresh(&app);
+    Ok(deleted)
+}
+
+/// 剪贴板历史保留策略与最近一次清理结果
+#[derive(Debug, Serialize)]
+pub struct ClipboardRetentionStatus {
+    pub policy: RetentionPolicy,
+    pub last_report: Option<RetentionReport>,
+}
+
+/// 应用启动时启动剪贴板历史的定期清理
+pub async fn init_clipboard_retention(app: tauri::AppHandle) {
+    let Ok(pool) = get_pool(&app).await else {
+        return;
+    };
+    retention::start(pool, move || tray::refresh(&app)).await;
+}
+
+/// 获取剪贴板历史保留策略
+#[tauri::command]
+pub async fn get_clipboard_retention(
+    app: tauri::AppHandle,
+) -> Result<ClipboardRetentionStatus, String> {
+    let pool = get_pool(&app).await?;
+    Ok(ClipboardRetentionStatus {
+        policy: RetentionPolicy::load(&pool).await,
+        last_report: retention::last_report().await,
+    })
+}
+
+/// 保存剪贴板历史保留策略并立即按新策略清理
+#[tauri::command]
+pub async fn set_clipboard_retention(
+    app: tauri::AppHandle,
+    policy: RetentionPolicy,
+) -> Result<ClipboardRetentionStatus, String> {
+    let pool = get_pool(&app).await?;
+    let policy = policy.save(&pool).await?;
+    let report = retention::enforce(&pool, &policy).await?;
+    tray::refresh(&app);
+    Ok(ClipboardRetentionStatus {
+        policy,
+        last_report: Some(report),
+    })
+}
+
+/// 立即按当前策略清理剪贴板历史
+#[tauri::command]
+pub async fn run_clipboard_retention(app: tauri::AppHandle) -> Result<RetentionReport, String> {
+    let pool = get_pool(&app).await?;
+    let report = retention::run(&pool).await?;
+    tray::refresh(&app);
+    Ok(report)
+}
+
+/// 剪贴板记录状态
+#[derive(Debug, Serialize)]
+pub struct ClipboardCaptureStatus {
+    /// 是否开启记录
+    pub enabled: bool,
+    /// 手动暂停时为恢复记录的时间
+    pub paused_until: Option<String>,
+    pub rules: CaptureRules,
+}
+
+async fn clipboard_capture_status(pool: &sqlx::SqlitePool) -> ClipboardCaptureStatus {
+    ClipboardCaptureStatus {
+        enabled: capture_rules::is_enabled(pool).await,
+        paused_until: capture_rules::paused_until(pool).await,
+        rules: CaptureRules::load(pool).await,
     }
 }
 
+/// 获取剪贴板记录规则与暂停状态
+#[tauri::command]
+pub async fn get_clipboard_capture_status(
+    app: tauri::AppHandle,
+) -> Result<ClipboardCaptureStatus, String> {
+    let pool = get_pool(&app).await?;
+    Ok(clipboard_capture_status(&pool).await)
+}
+
+/// 设置剪贴板记录规则（排除正则、长度限制、排除应用、每日暂停时段）
+#[tauri::command]
+pub async fn set_clipboard_capture_rules(
+    app: tauri::AppHandle,
+    rules: CaptureRules,
+) -> Result<ClipboardCaptureStatus, String> {
+    let pool = get_pool(&app).await?;
+    rules.save(&pool).await?;
+    Ok(clipboard_capture_status(&pool).await)
+}
+
+/// 暂停记录剪贴板 minutes 分钟
+#[tauri::command]
+pub async fn pause_clipboard_capture(
+    app: tauri::AppHandle,
+    minutes: u32,
+) -> Result<ClipboardCaptureStatus, String> {
+    let pool = get_pool(&app).await?;
+    capture_rules::pause(&pool, minutes).await?;
+    tray::refresh(&app);
+    Ok(clipboard_capture_status(&pool).await)
+}
+
+/// 恢复记录剪贴板
+#[tauri::command]
+pub async fn resume_clipboard_capture(
+    app: tauri::AppHandle,
+) -> Result<ClipboardCaptureStatus, String> {
+    let pool = get_pool(&app).await?;
+    capture_rules::resume(&pool).await?;
+    tray::refresh(&app);
+    Ok(clipboard_capture_status(&pool).await)
+}
+
+/// 开启或关闭剪贴板记录（开启时同时取消暂停）
+#[tauri::command]
+pub async fn set_clipboard_capture_enabled(
+    app: tauri::AppHandle,
+    enabled: bool,
+) -> Result<ClipboardCaptureStatus, String> {
+    let pool = get_pool(&app).await?;
+    capture_rules::set_enabled(&pool, enabled).await?;
+    tray::refresh(&app);
+    Ok(clipboard_capture_status(&pool).await)
+}
+
+/// 扫描文本中的密钥（结果只包含类型、行号和遮盖后的预览）
+#[tauri::command]
+pub fn scan_secrets(text: String) -> Vec<SecretFinding> {
+    secrets::scan(&text)
+}
+
+/// 检测代码的编程语言，hint 为可选的文件名或扩展名
+#[tauri::command]
+pub fn detect_language(content: String, hint: Option<String>) -> LanguageDetection {
+    language_detect::detect(&content, hint.as_deref())
+}
+
+// ============================================================================
+// 代码片段
+// ============================================================================
+
+/// 创建代码片段（经过校验），内容重复时按 duplicate_snippet_policy 返回已有记录或报错
+#[tauri::command]
+pub async fn create_snippet(
+    app: tauri::AppHandle,
+    snippet: NewSnippet,
+) -> Result<CodeSnippet, String> {
+    let pool = get_pool(&app).await?;
+    snippets::create_snippet(&pool, snippet)
+        .await
+        .map(|outcome| outcome.into_snippet())
+        .map_err(|e| e.to_string())
+}
+
+/// 更新代码片段（经过校验）
+#[tauri::command]
+pub async fn update_snippet(
+    app: tauri::AppHandle,
+    id: i64,
+    patch: SnippetPatch,
+) -> Result<CodeSnippet, String> {
+    let pool = get_pool(&app).await?;
+    let snippet = snippets::update_snippet(&pool, id, patch)
+        .await
+        .map_err(|e| e.to_string())?;
+    tray::refresh(&app);
+    Ok(snippet)
+}
+
+/// 根据 ID 获取代码片段
+#[tauri::command]
+pub async fn get_snippet(app: tauri::AppHandle, id: i64) -> Result<Option<CodeSnippet>, String> {
+    let pool = get_pool(&app).await?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    snippets::get_snippet(&mut conn, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 按关键词搜索代码片段的标题、代码和标签
+#[tauri::command]
+pub async fn search_snippets(
+    app: tauri::AppHandle,
+    query: String,
+) -> Result<Vec<CodeSnippet>, String> {
+    let pool = get_pool(&app).await?;
+    snippets::search_snippets(&pool, &query)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 按条件列出代码片段（语言、标签、集合、置顶），支持按 frecency 等方式排序
+#[tauri::command]
+pub async fn list_snippets(
+    app: tauri::AppHandle,
+    filter: Option<SnippetFilter>,
+) -> Result<Vec<CodeSnippet>, String> {
+    let pool = get_pool(&app).await?;
+    snippets::query_snippets(&pool, &filter.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 记录一次代码片段使用（复制、插入等）
+#[tauri::command]
+pub async fn record_snippet_use(app: tauri::AppHandle, id: i64) -> Result<(), String> {
+    let pool = get_pool(&app).await?;
+    snippets::record_use(&pool, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 设置或取消置顶，返回更新的数量
+#[tauri::command]
+pub async fn set_snippets_pinned(
+    app: tauri::AppHandle,
+    ids: Vec<i64>,
+    pinned: bool,
+) -> Result<u64, String> {
+    let pool = get_pool(&app).await?;
+    let updated = snippets::set_pinned(&pool, &ids, pinned)
+        .await
+        .map_err(|e| e.to_string())?;
+    tray::refresh(&app);
+    Ok(updated)
+}
+
+/// 获取使用次数最多的代码片段
+#[tauri::command]
+pub async fn get_top_snippets(
+    app: tauri::AppHandle,
+    limit: Option<i64>,
+) -> Result<Vec<SnippetUsage>, String> {
+    let pool = get_pool(&app).await?;
+    snippets::top_used(&pool, limit.unwrap_or(10).clamp(1, 100))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 查找内容重复的代码片段分组
+#[tauri::command]
+pub async fn find_duplicates(app: tauri::AppHandle) -> Result<Vec<DuplicateGroup>, String> {
+    let pool = get_pool(&app).await?;
+    snippets::find_duplicates(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 批量创建代码片段，返回每个条目的结果
+#[tauri::command]
+pub async fn bulk_create_snippets(
+    app: tauri::AppHandle,
+    snippets: Vec<NewSnippet>,
+) -> Result<Vec<BatchItemResult>, String> {
+    let pool = get_pool(&app).await?;
+    snippets::bulk_create(&pool, snippets)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 批量更新代码片段字段（如语言），返回更新条数
+#[tauri::command]
+pub async fn bulk_update_snippets(
+    app: tauri::AppHandle,
+    ids: Vec<i64>,
+    patch: SnippetPatch,
+) -> Result<u64, String> {
+    let pool = get_pool(&app).await?;
+    let updated = snippets::bulk_update(&pool, &ids, patch)
+        .await
+        .map_err(|e| e.to_string())?;
+    tray::refresh(&app);
+    Ok(updated)
+}
+
+/// 批量添加标签，返回发生变化的条数
+#[tauri::command]
+pub async fn bulk_add_tags(
+    app: tauri::AppHandle,
+    ids: Vec<i64>,
+    tags: Vec<String>,
+) -> Result<u64, String> {
+    let pool = get_pool(&app).await?;
+    snippets::bulk_add_tags(&pool, &ids, &tags)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 批量移除标签，返回发生变化的条数
+#[tauri::command]
+pub async fn bulk_remove_tags(
+    app: tauri::AppHandle,
+    ids: Vec<i64>,
+    tags: Vec<String>,
+) -> Result<u64, String> {
+    let pool = get_pool(&app).await?;
+    snippets::bulk_remove_tags(&pool, &ids, &tags)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 批量删除代码片段，返回删除条数
+#[tauri::command]
+pub async fn bulk_delete_snippets(app: tauri::AppHandle, ids: Vec<i64>) -> Result<u64, String> {
+    let pool = get_pool(&app).await?;
+    let deleted = snippets::bulk_delete(&pool, &ids)
+        .await
+        .map_err(|e| e.to_string())?;
+    tray::refresh(&app);
+    Ok(deleted)
+}
+
+// ============================================================================
+// 数据导入导出
+// ============================================================================
+
+/// 导出数据为版本化的 JSON 归档
+#[tauri::command]
+pub async fn export_data(
+    app: tauri::AppHandle,
+    path: String,
+    options: Option<ExportOptions>,
+) -> Result<ExportSummary, String> {
+    let pool = get_pool(&app).await?;
+    let app_version = app.package_info().version.to_string();
+    archive::export_to_file(&pool, &path, &options.unwrap_or_default(), &app_version).await
+}
+
+/// 导入归档内容；dry_run 为 true 时只返回预计结果，不写入数据库
+#[tauri::command]
+pub async fn import_data(
+    app: tauri::AppHandle,
+    contents: String,
+    mode: ImportMode,
+    dry_run: Option<bool>,
+) -> Result<ImportReport, String> {
+    let pool = get_pool(&app).await?;
+    let archive = archive::parse_archive(&contents)?;
+    let dry_run = dry_run.unwrap_or(false);
+    let report = archive::import_archive(&pool, archive, mode, dry_run).await?;
+    if !dry_run {
+        tray::refresh(&app);
+    }
+    Ok(report)
+}
+
+/// 预览外部来源（VS Code / JetBrains / 目录 / Gist）的导入结果，不写入数据库
+#[tauri::command]
+pub async fn preview_external_import(
+    app: tauri::AppHandle,
+    source: ImportSource,
+    path: String,
+    mode: ImportMode,
+) -> Result<ExternalImportPreview, String> {
+    let pool = get_pool(&app).await?;
+    let archive = importers::read_source(source, &path)?;
+    let snippets = archive.snippets.clone();
+    let report = archive::import_archive(&pool, archive, mode, true).await?;
+    Ok(ExternalImportPreview { snippets, report })
+}
+
+/// 从外部来源导入代码片段
+#[tauri::command]
+pub async fn import_external(
+    app: tauri::AppHandle,
+    source: ImportSource,
+    path: String,
+    mode: ImportMode,
+) -> Result<ImportReport, String> {
+    let pool = get_pool(&app).await?;
+    let archive = importers::read_source(source, &path)?;
+    let report = archive::import_archive(&pool, archive, mode, false).await?;
+    tray::refresh(&app);
+    Ok(report)
+}
+
+/// 导出为 VS Code 代码片段文件（每种语言一个文件），返回写入的文件路径
+#[tauri::command]
+pub async fn export_vscode_snippets(
+    app: tauri::AppHandle,
+    dir: String,
+    filter: Option<SnippetFilter>,
+) -> Result<Vec<String>, String> {
+    let pool = get_pool(&app).await?;
+    let snippets = snippets::query_snippets(&pool, &filter.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())?;
+    exporters::export_vscode(&snippets, Path::new(&dir))
+}
+
+/// 导出为 JetBrains 实时模板 XML，返回导出的模板数量
+#[tauri::command]
+pub async fn export_jetbrains_templates(
+    app: tauri::AppHandle,
+    path: String,
+    filter: Option<SnippetFilter>,
+) -> Result<usize, String> {
+    let pool = get_pool(&app).await?;
+    let snippets = snippets::query_snippets(&pool, &filter.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())?;
+    exporters::export_jetbrains(&snippets, Path::new(&path))
+}
+
+// ============================================================================
+// 集合
+// ============================================================================
+
+/// 以树形结构获取全部集合
+#[tauri::command]
+pub async fn list_collections(app: tauri::AppHandle) -> Result<Vec<CollectionNode>, String> {
+    let pool = get_pool(&app).await?;
+    collections::list_collections(&pool).await
+}
+
+/// 创建集合，parent_id 为空时创建顶层集合
+#[tauri::command]
+pub async fn create_collection(
+    app: tauri::AppHandle,
+    name: String,
+    parent_id: Option<i64>,
+) -> Result<Collection, String> {
+    let pool = get_pool(&app).await?;
+    collections::create_collection(&pool, &name, parent_id).await
+}
+
+/// 重命名集合
+#[tauri::command]
+pub async fn rename_collection(
+    app: tauri::AppHandle,
+    id: i64,
+    name: String,
+) -> Result<Collection, String> {
+    let pool = get_pool(&app).await?;
+    collections::rename_collection(&pool, id, &name).await
+}
+
+/// 移动集合到新的父集合下
+#[tauri::command]
+pub async fn move_collection(
+    app: tauri::AppHandle,
+    id: i64,
+    parent_id: Option<i64>,
+) -> Result<Collection, String> {
+    let pool = get_pool(&app).await?;
+    collections::move_collection(&pool, id, parent_id).await
+}
+
+/// 删除集合及其子集合，返回被移到上级集合的代码片段数
+#[tauri::command]
+pub async fn delete_collection(app: tauri::AppHandle, id: i64) -> Result<u64, String> {
+    let pool = get_pool(&app).await?;
+    collections::delete_collection(&pool, id).await
+}
+
+/// 将代码片段移入集合（collection_id 为空时移出），返回更新的数量
+#[tauri::command]
+pub async fn move_snippets_to_collection(
+    app: tauri::AppHandle,
+    ids: Vec<i64>,
+    collection_id: Option<i64>,
+) -> Result<u64, String> {
+    let pool = get_pool(&app).await?;
+    collections::assign_snippets(&pool, &ids, collection_id).await
+}
+
+// ============================================================================
+// 文件夹同步
+// ============================================================================
+
+/// 读取已配置的同步文件夹
+async fn configured_sync_folder(pool: &sqlx::SqlitePool) -> Result<PathBuf, String> {
+    get_setting(pool, "sync_folder_path")
+        .await
+        .filter(|path| !path.trim().is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| t("errors.syncFolderNotConfigured"))
+}
+
+/// 应用启动时根据设置恢复文件夹监听
+pub async fn restore_folder_sync(app: tauri::AppHandle) {
+    let Ok(pool) = ready_pool(&app).await else {
+        return;
+    };
+    if get_setting(&pool, "sync_folder_enabled").await.as_deref() != Some("true") {
+        return;
+    }
+    if let Ok(dir) = configured_sync_folder(&pool).await {
+        if let Err(e) = sync::start_watch(pool, dir, move || tray::refresh(&app)).await {
+            tracing::warn!("failed to start folder sync: {}", e);
+        }
+    }
+}
+
+/// 配置文件夹同步：保存路径与开关，并启动或停止监听
+#[tauri::command]
+pub async fn configure_folder_sync(
+    app: tauri::AppHandle,
+    path: Option<String>,
+    enabled: bool,
+) -> Result<FolderSyncStatus, String> {
+    let pool = ready_pool(&app).await?;
+    if let Some(path) = &path {
+        set_setting(&pool, "sync_folder_path", path).await?;
+    }
+    set_setting(
+        &pool,
+        "sync_folder_enabled",
+        if enabled { "true" } else { "false" },
+    )
+    .await?;
+
+    if enabled {
+        let dir = configured_sync_folder(&pool).await?;
+        let handle = app.clone();
+        sync::start_watch(pool, dir, move || tray::refresh(&handle)).await?;
+    } else {
+        sync::stop_watch().await;
+    }
+    Ok(sync::folder_status().await)
+}
+
+/// 立即执行一次文件夹同步
+#[tauri::command]
+pub async fn sync_folder_now(app: tauri::AppHandle) -> Result<SyncReport, String> {
+    let pool = ready_pool(&app).await?;
+    let dir = configured_sync_folder(&pool).await?;
+    let report = sync::sync_folder(&pool, &dir).await?;
+    tray::refresh(&app);
+    Ok(report)
+}
+
+/// 查询文件夹同步状态及最近一次同步结果
+#[tauri::command]
+pub async fn get_folder_sync_status() -> Result<FolderSyncStatus, String> {
+    Ok(sync::folder_status().await)
+}
+
+/// 解决文件夹同步冲突
+#[tauri::command]
+pub async fn resolve_folder_sync_conflict(
+    app: tauri::AppHandle,
+    sync_id: String,
+    resolution: ConflictResolution,
+) -> Result<(), String> {
+    let pool = get_pool(&app).await?;
+    let dir = configured_sync_folder(&pool).await?;
+    sync::resolve_conflict(&pool, &dir, sync::FOLDER_TARGET, &sync_id, resolution).await?;
+    tray::refresh(&app);
+    Ok(())
+}
+
+// ============================================================================
+// Git 仓库同步
+// ============================================================================
+
+/// 读取 Git 同步配置
+async fn configured_git_sync(pool: &sqlx::SqlitePool) -> Result<GitSyncConfig, String> {
+    let path = get_setting(pool, "git_sync_path")
+        .await
+        .filter(|path| !path.trim().is_empty())
+        .ok_or_else(|| t("errors.gitSyncNotConfigured"))?;
+    let remote = get_setting(pool, "git_sync_remote")
+        .await
+        .filter(|remote| !remote.trim().is_empty());
+    let branch = get_setting(pool, "git_sync_branch")
+        .await
+        .filter(|branch| !branch.trim().is_empty())
+        .unwrap_or_else(|| git_sync::DEFAULT_BRANCH.to_string());
+
+    Ok(GitSyncConfig {
+        path: PathBuf::from(path),
+        remote,
+        branch,
+    })
+}
+
+/// Git 同步状态
+#[derive(Debug, Serialize)]
+pub struct GitSyncStatus {
+    pub config: Option<GitSyncConfig>,
+    pub last_report: Option<GitSyncReport>,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+/// 配置 Git 同步：本地仓库路径、远程地址（可为空）和分支
+#[tauri::command]
+pub async fn configure_git_sync(
+    app: tauri::AppHandle,
+    path: String,
+    remote: Option<String>,
+    branch: Option<String>,
+) -> Result<GitSyncConfig, String> {
+    let pool = ready_pool(&app).await?;
+    if path.trim().is_empty() {
+        return Err(t("errors.repositoryPathEmpty"));
+    }
+    set_setting(&pool, "git_sync_path", path.trim()).await?;
+    set_setting(
+        &pool,
+        "git_sync_remote",
+        remote.as_deref().map(str::trim).unwrap_or(""),
+    )
+    .await?;
+    set_setting(
+        &pool,
+        "git_sync_branch",
+        branch.as_deref().map(str::trim).unwrap_or(""),
+    )
+    .await?;
+    configured_git_sync(&pool).await
+}
+
+/// 立即执行一次 Git 同步（提交、拉取合并、推送）
+#[tauri::command]
+pub async fn sync_git_now(app: tauri::AppHandle) -> Result<GitSyncReport, String> {
+    let pool = ready_pool(&app).await?;
+    let config = configured_git_sync(&pool).await?;
+    let report = git_sync::sync_repo(&pool, &config).await?;
+    tray::refresh(&app);
+    Ok(report)
+}
+
+/// 查询 Git 同步配置、最近一次同步结果和待解决的冲突
+#[tauri::command]
+pub async fn get_git_sync_status(app: tauri::AppHandle) -> Result<GitSyncStatus, String> {
+    let pool = get_pool(&app).await?;
+    Ok(GitSyncStatus {
+        config: configured_git_sync(&pool).await.ok(),
+        last_report: git_sync::last_report().await,
+        conflicts: git_sync::list_conflicts(&pool).await?,
+    })
+}
+
+/// 解决 Git 合并冲突，并立即重新同步
+#[tauri::command]
+pub async fn resolve_git_sync_conflict(
+    app: tauri::AppHandle,
+    sync_id: String,
+    resolution: GitConflictResolution,
+) -> Result<GitSyncReport, String> {
+    let pool = get_pool(&app).await?;
+    let config = configured_git_sync(&pool).await?;
+    let report = git_sync::resolve_conflict(&pool, &config, &sync_id, resolution).await?;
+    tray::refresh(&app);
+    Ok(report)
+}
+
+// ============================================================================
+// 数据库统计
+// ============================================================================
+
+/// 获取数据库统计信息：数量、语言/标签分布、文件大小、时间范围和最近 30 天增长
+#[tauri::command]
+pub async fn get_database_stats(app: tauri::AppHandle) -> Result<DatabaseStats, String> {
+    let pool = get_pool(&app).await?;
+    stats::collect_stats(&pool).await
+}
+
+// ============================================================================
+// 数据库加密
+// ============================================================================
+
+/// 应用启动时根据设置初始化加密状态（密钥在系统钥匙串中时自动解锁）
+pub async fn init_encryption(app: tauri::AppHandle) {
+    match get_pool(&app).await {
+        Ok(pool) => encryption::init(&pool).await,
+        Err(e) => {
+            tracing::warn!("failed to initialize encryption: {}", e);
+            encryption::init_unavailable();
+        }
+    }
+}
+
+/// 获取连接池，并等待启动时的加密状态初始化完成（避免在读取加密设置前写入明文）
+async fn ready_pool(app: &tauri::AppHandle) -> Result<sqlx::SqlitePool, String> {
+    let pool = get_pool(app).await?;
+    encryption::wait_initialized().await;
+    Ok(pool)
+}
+
+/// 查询加密状态
+#[tauri::command]
+pub async fn get_encryption_status(app: tauri::AppHandle) -> Result<EncryptionStatus, String> {
+    let pool = ready_pool(&app).await?;
+    Ok(encryption::status(&pool).await)
+}
+
+/// 启用加密并加密现有数据
+#[tauri::command]
+pub async fn enable_encryption(
+    app: tauri::AppHandle,
+    passphrase: String,
+    remember: Option<bool>,
+) -> Result<EncryptionStatus, String> {
+    let pool = ready_pool(&app).await?;
+    encryption::enable(&pool, &passphrase, remember.unwrap_or(false)).await
+}
+
+/// 关闭加密并解密全部数据
+#[tauri::command]
+pub async fn disable_encryption(
+    app: tauri::AppHandle,
+    passphrase: String,
+) -> Result<EncryptionStatus, String> {
+    let pool = get_pool(&app).await?;
+    encryption::disable(&pool, &passphrase).await
+}
+
+/// 用口令解锁数据库
+#[tauri::command]
+pub async fn unlock_database(
+    app: tauri::AppHandle,
+    passphrase: String,
+    remember: Option<bool>,
+) -> Result<EncryptionStatus, String> {
+    let pool = ready_pool(&app).await?;
+    let status = encryption::unlock(&pool, &passphrase, remember.unwrap_or(false)).await?;
+    tauri::async_runtime::spawn(init_data_backfill(app.clone()));
+    tray::refresh(&app);
+    Ok(status)
+}
+
+/// 锁定数据库
+#[tauri::command]
+pub async fn lock_database(app: tauri::AppHandle) -> Result<EncryptionStatus, String> {
+    let pool = get_pool(&app).await?;
+    let status = encryption::lock(&pool).await?;
+    tray::refresh(&app);
+    Ok(status)
+}
+
+// ============================================================================
+// 数据库维护
+// ============================================================================
+
+/// 默认的自动备份目录
+fn default_backup_dir(app: &tauri::AppHandle) -> PathBuf {
+    db_path(app)
+        .parent()
+        .map(|dir| dir.join("backups"))
+        .unwrap_or_else(|| PathBuf::from("backups"))
+}
+
+/// 自动备份状态
+#[derive(Debug, Serialize)]
+pub struct AutoBackupStatus {
+    pub config: AutoBackupConfig,
+    pub running: bool,
+    pub backups: Vec<BackupInfo>,
+}
+
+async fn auto_backup_status(app: &tauri::AppHandle) -> Result<AutoBackupStatus, String> {
+    let pool = get_pool(app).await?;
+    let config = AutoBackupConfig::load(&pool, default_backup_dir(app)).await;
+    Ok(AutoBackupStatus {
+        backups: maintenance::list_backups(&config.dir),
+        running: maintenance::auto_backup_running().await,
+        config,
+    })
+}
+
+/// 为旧数据补齐后续写入时才会计算的字段（启动、解锁和恢复备份后各执行一次，而不是在每次读写时检查）
+pub async fn init_data_backfill(app: tauri::AppHandle) {
+    let Ok(pool) = get_pool(&app).await else {
+        return;
+    };
+    let result = match pool.acquire().await {
+        Ok(mut conn) => snippets::backfill_content_hashes(&mut conn)
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    match result {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("backfilled content hashes for {} snippets", count),
+        Err(e) => tracing::warn!("snippet content hash backfill failed: {}", e),
+    }
+
+    let result = match pool.acquire().await {
+        Ok(mut conn) => secrets::backfill_flags(&mut conn).await,
+        Err(e) => Err(e.to_string()),
+    };
+    match result {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("backfilled secret flags for {} records", count),
+        Err(e) => tracing::warn!("secret flag backfill failed: {}", e),
+    }
+
+    match clipboard::backfill_languages(&pool).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("backfilled languages for {} clipboard items", count),
+        Err(e) => tracing::warn!("clipboard language backfill failed: {}", e),
+    }
+}
+
+/// 应用启动时根据设置启动自动备份
+pub async fn init_auto_backup(app: tauri::AppHandle) {
+    let Ok(pool) = get_pool(&app).await else {
+        return;
+    };
+    let config = AutoBackupConfig::load(&pool, default_backup_dir(&app)).await;
+    maintenance::start_auto_backup(db_path(&app), config).await;
+}
+
+/// 将数据库在线备份到指定路径
+#[tauri::command]
+pub async fn backup_database(app: tauri::AppHandle, path: String) -> Result<BackupInfo, String> {
+    maintenance::backup_database(&db_path(&app), Path::new(&path)).await
+}
+
+/// 执行 VACUUM 和 ANALYZE
+#[tauri::command]
+pub async fn optimize_database(app: tauri::AppHandle) -> Result<OptimizeReport, String> {
+    let pool = get_pool(&app).await?;
+    maintenance::optimize_database(&pool).await
+}
+
+/// 检查数据库完整性
+#[tauri::command]
+pub async fn check_database_integrity(app: tauri::AppHandle) -> Result<IntegrityReport, String> {
+    let pool = get_pool(&app).await?;
+    maintenance::check_integrity(&pool).await
+}
+
+/// 配置自动备份，未提供的参数保持原值
+#[tauri::command]
+pub async fn configure_auto_backup(
+    app: tauri::AppHandle,
+    enabled: bool,
+    interval_hours: Option<u64>,
+    keep: Option<usize>,
+    dir: Option<String>,
+) -> Result<AutoBackupStatus, String> {
+    // 先校验全部参数，再在一个事务中写入，避免部分参数被拒绝时留下半套配置
+    if interval_hours == Some(0) {
+        return Err(t("errors.backupIntervalTooShort"));
+    }
+    if keep == Some(0) {
+        return Err(t("errors.keepAtLeastOneBackup"));
+    }
+
+    let pool = get_pool(&app).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    set_setting(
+        &mut *tx,
+        "auto_backup_enabled",
+        if enabled { "true" } else { "false" },
+    )
+    .await?;
+    if let Some(hours) = interval_hours {
+        set_setting(&mut *tx, "auto_backup_interval_hours", &hours.to_string()).await?;
+    }
+    if let Some(keep) = keep {
+        set_setting(&mut *tx, "auto_backup_keep", &keep.to_string()).await?;
+    }
+    if let Some(dir) = &dir {
+        set_setting(&mut *tx, "auto_backup_dir", dir.trim()).await?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    init_auto_backup(app.clone()).await;
+    auto_backup_status(&app).await
+}
+
+/// 查询自动备份配置和已有备份
+#[tauri::command]
+pub async fn get_auto_backup_status(app: tauri::AppHandle) -> Result<AutoBackupStatus, String> {
+    auto_backup_status(&app).await
+}
+
+/// 立即在自动备份目录中创建一个备份（同样参与轮换）
+#[tauri::command]
+pub async fn create_backup_now(app: tauri::AppHandle) -> Result<BackupInfo, String> {
+    let pool = get_pool(&app).await?;
+    let config = AutoBackupConfig::load(&pool, default_backup_dir(&app)).await;
+    maintenance::create_rotating_backup(&db_path(&app), &config).await
+}
+
+/// 删除自动备份目录中的全部备份（启用加密后，之前的备份仍是明文），返回删除的数量
+#[tauri::command]
+pub async fn delete_auto_backups(app: tauri::AppHandle) -> Result<usize, String> {
+    let pool = get_pool(&app).await?;
+    let config = AutoBackupConfig::load(&pool, default_backup_dir(&app)).await;
+    Ok(maintenance::delete_backups(&config.dir))
+}
+
+/// 关闭前端与后端的连接池后替换数据库文件，完成后重新打开前端连接池
+async fn swap_with_pools_closed(
+    app: &tauri::AppHandle,
+    staging: &Path,
+    safety_copy: &Path,
+) -> Result<(), String> {
+    let instances = app.state::<DbInstances>();
+    let mut pools = instances.0.write().await;
+    for pool in pools.values() {
+        let DbPool::Sqlite(sqlite) = pool;
+        sqlite.close().await;
+    }
+
+    let result = with_pool_closed(maintenance::swap_database(
+        &db_path(app),
+        staging,
+        safety_copy,
+    ))
+    .await;
+
+    // 无论替换是否成功都重新打开前端连接池
+    for pool in pools.values_mut() {
+        let DbPool::Sqlite(sqlite) = pool;
+        let options = sqlite.connect_options();
+        match sqlx::SqlitePool::connect_with(options.as_ref().clone()).await {
+            Ok(reopened) => *pool = DbPool::Sqlite(reopened),
+            Err(e) => tracing::warn!("failed to reopen database connection: {}", e),
+        }
+    }
+    result
+}
+
+/// 按原权限重新启动 MCP 服务器（旧服务器释放端口可能需要片刻）
+async fn restart_mcp_server(
+    app: &tauri::AppHandle,
+    addr: SocketAddr,
+    permissions: ServerPermissions,
+) {
+    let Ok(pool) = get_pool(app).await else {
+        return;
+    };
+    for attempt in 1..=10 {
+        match start_server_with_permissions(
+            pool.clone(),
+            addr.port(),
+            permissions.allow_query,
+            permissions.allow_create,
+            permissions.allow_update,
+            permissions.allow_delete,
+            permissions.allow_secrets,
+        )
+        .await
+        {
+            Ok(_) => return,
+            Err(e) if attempt == 10 => tracing::warn!("failed to restart MCP server: {}", e),
+            Err(_) => tokio::time::sleep(std::time::Duration::from_millis(200)).await,
+        }
+    }
+}
+
+/// 从备份文件恢复数据库
+///
+/// 备份的迁移版本较旧时先迁移到当前版本，较新时拒绝恢复。替换期间暂停 MCP 服务器、
+/// 文件夹同步和自动备份，恢复前的数据库保存为安全副本。
+#[tauri::command]
+pub async fn restore_backup(app: tauri::AppHandle, path: String) -> Result<RestoreReport, String> {
+    let _guard = maintenance::lock_restore().await;
+    let live = db_path(&app);
+    let staging = live.with_extension("db-restore");
+    let (backup_version, schema_version) =
+        maintenance::prepare_restore(Path::new(&path), &staging, get_migrations()).await?;
+
+    // 暂停所有持有数据库连接的后台任务
+    let mcp_config = get_server_config().await;
+    if mcp_config.is_some() {
+        let _ = stop_server().await;
+    }
+    sync::stop_watch().await;
+    maintenance::stop_auto_backup().await;
+    retention::stop().await;
+
+    let safety_copy = default_backup_dir(&app).join(format!(
+        "seekcode-pre-restore-{}.db",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let result = swap_with_pools_closed(&app, &staging, &safety_copy).await;
+    if result.is_err() {
+        let _ = std::fs::remove_file(&staging);
+    }
+
+    // 恢复后台任务（设置可能随数据库一起变化）
+    if let Some((addr, permissions)) = mcp_config {
+        restart_mcp_server(&app, addr, permissions).await;
+    }
+    init_encryption(app.clone()).await;
+    restore_folder_sync(app.clone()).await;
+    init_auto_backup(app.clone()).await;
+    init_clipboard_retention(app.clone()).await;
+    init_data_backfill(app.clone()).await;
+    tray::refresh(&app);
+
+    result?;
+    Ok(RestoreReport {
+        restored_from: path,
+        backup_version,
+        schema_version,
+        migrated: backup_version < schema_version,
+        safety_copy: safety_copy.to_string_lossy().into_owned(),
+        restored_at: crate::db::now_timestamp(),
+    })
+}
+
+// ============================================================================
+// 快速粘贴面板
+// ============================================================================
+
+/// 应用启动时注册快速粘贴面板的全局快捷键
+pub async fn init_palette_shortcut(app: tauri::AppHandle) {
+    let Ok(pool) = get_pool(&app).await else {
+        return;
+    };
+    let shortcut = palette::load_shortcut(&pool).await;
+    if let Err(e) = palette::register_shortcut(&app, &shortcut) {
+        tracing::warn!("failed to register quick paste shortcut: {}", e);
+    }
+}
+
+/// 获取快速粘贴面板的全局快捷键（为空表示未启用）
+#[tauri::command]
+pub async fn get_palette_shortcut(app: tauri::AppHandle) -> Result<String, String> {
+    let pool = get_pool(&app).await?;
+    Ok(palette::load_shortcut(&pool).await)
+}
+
+/// 设置快速粘贴面板的全局快捷键，注册成功后保存；为空时取消注册
+#[tauri::command]
+pub async fn set_palette_shortcut(
+    app: tauri::AppHandle,
+    shortcut: String,
+) -> Result<String, String> {
+    let pool = get_pool(&app).await?;
+    let shortcut = shortcut.trim().to_string();
+    palette::register_shortcut(&app, &shortcut)?;
+    set_setting(&pool, "palette_shortcut", &shortcut).await?;
+    Ok(shortcut)
+}
+
+/// 在代码片段和剪贴板历史中搜索，供快速粘贴面板使用
+#[tauri::command]
+pub async fn search_palette(
+    app: tauri::AppHandle,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<PaletteItem>, String> {
+    let pool = get_pool(&app).await?;
+    palette::search(&pool, &query, limit).await
+}
+
+/// 把选中的代码片段或剪贴板条目写入系统剪贴板，然后隐藏面板
+#[tauri::command]
+pub async fn select_palette_item(
+    app: tauri::AppHandle,
+    source: PaletteSource,
+    id: i64,
+) -> Result<(), String> {
+    copy_item_to_clipboard(&app, source, id).await?;
+    palette::hide(&app)
+}
+
+/// 把代码片段或剪贴板历史条目写入系统剪贴板（代码片段同时记录一次使用）
+pub async fn copy_item_to_clipboard(
+    app: &tauri::AppHandle,
+    source: PaletteSource,
+    id: i64,
+) -> Result<(), String> {
+    match source {
+        PaletteSource::Snippet => {
+            let pool = get_pool(app).await?;
+            let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+            let snippet = snippets::get_snippet(&mut conn, id)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| t_with("errors.snippetNotFound", &[("id", &id)]))?;
+            drop(conn);
+            set_clipboard_content(app.clone(), Some(snippet.code), None).await?;
+            snippets::record_use(&pool, id)
+                .await
+                .map_err(|e| e.to_string())
+        }
+        PaletteSource::Clipboard => set_clipboard_content(app.clone(), None, Some(id)).await,
+    }
+}
+
+/// 显示快速粘贴面板（异步命令，避免在主线程上创建窗口时阻塞）
+#[tauri::command]
+pub async fn show_palette(app: tauri::AppHandle) -> Result<(), String> {
+    palette::show(&app)
+}
+
+/// 隐藏快速粘贴面板
+#[tauri::command]
+pub fn hide_palette(app: tauri::AppHandle) -> Result<(), String> {
+    palette::hide(&app)
+}
+
+// ============================================================================
+// 窗口控制
+// ============================================================================
+
+/// 显示主窗口
+#[tauri::command]
+pub fn show_window(app: tauri::AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window("main") {
+        window
+            .show()
+            .map_err(|e| format!("Failed to show window: {}", e))?;
+        window
+            .set_focus()
+            .map_err(|e| format!("Failed to focus window: {}", e))?;
+    }
+    Ok(())
+}
+
+/// 隐藏主窗口
+#[tauri::command]
+pub fn hide_window(app: tauri::AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window("main") {
+        window
+            .hide()
+            .map_err(|e| format!("Failed to hide window: {}", e))?;
+    }
+    Ok(())
+}
+
+/// 检查窗口是否可见
+#[tauri::command]
+pub fn is_window_visible(app: tauri::AppHandle) -> Result<bool, String> {
+    if let Some(window) = app.get_webview_window("main") {
+        window
+            .is_visible()
+            .map_err(|e| format!("Failed to check window visibility: {}", e))
+    } else {
+        Ok(false)
+    }
+}
+
+// ============================================================================
+// MCP 服务器控制
+// ============================================================================
+
+/// 启动MCP服务器
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn start_mcp_server(
+    app: tauri::AppHandle,
+    _host: Option<String>,
+    port: Option<u16>,
+    allow_query: Option<bool>,
+    allow_create: Option<bool>,
+    allow_update: Option<bool>,
+    allow_delete: Option<bool>,
+    allow_secrets: Option<bool>,
+) -> Result<String, String> {
+    let port = port.unwrap_or(9800);
+    let allow_query = allow_query.unwrap_or(true);
+    let allow_create = allow_create.unwrap_or(true);
+    let allow_update = allow_update.unwrap_or(false);
+    let allow_delete = allow_delete.unwrap_or(false);
+    let allow_secrets = allow_secrets.unwrap_or(false);
+
+    // 获取数据库连接池
+    let db_pool = ready_pool(&app).await?;
+
+    match start_server_with_permissions(
+        db_pool,
+        port,
+        allow_query,
+        allow_create,
+        allow_update,
+        allow_delete,
+        allow_secrets,
+    )
+    .await
+    {
+        Ok(addr) => {
+            tray::refresh(&app);
+            Ok(t_with("mcp.started", &[("address", &addr)]))
+        }
+        Err(e) => Err(t_with("mcp.startFailed", &[("error", &e)])),
+    }
+}
+
+/// 停止MCP服务器
+#[tauri::command]
+pub async fn stop_mcp_server(app: tauri::AppHandle) -> Result<String, String> {
+    match stop_server().await {
+        Ok(_) => {
+            tray::refresh(&app);
+            Ok(t("mcp.stopped"))
+        }
+        Err(e) => Err(t_with("mcp.stopFailed", &[("error", &e)])),
+    }
+}
+
+/// 按 mcp_server 设置启动 MCP 服务器，已运行时停止（托盘菜单使用）
+pub async fn toggle_mcp_server(app: &tauri::AppHandle) -> Result<String, String> {
+    if is_server_running().await {
+        return stop_mcp_server(app.clone()).await;
+    }
+
+    let pool = ready_pool(app).await?;
+    let config: serde_json::Value = get_setting(&pool, "mcp_server")
+        .await
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default();
+    let flag = |key: &str| config.get(key).and_then(|value| value.as_bool());
+    start_mcp_server(
+        app.clone(),
+        None,
+        config
+            .get("port")
+            .and_then(|value| value.as_u64())
+            .and_then(|port| u16::try_from(port).ok()),
+        flag("allow_query"),
+        flag("allow_create"),
+        flag("allow_update"),
+        flag("allow_delete"),
+        flag("allow_secrets"),
+    )
+    .await
+}
+
 /// 查询MCP服务器状态
 #[tauri::command]
 pub async fn get_mcp_server_status() -> Result<serde_json::Value, String> {
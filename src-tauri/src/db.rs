@@ -0,0 +1,100 @@
+use crate::i18n::t_with;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Row, SqlitePool};
+use tauri::Manager;
+use tokio::sync::RwLock;
+
+// 全局数据库连接池 - 与前端 tauri-plugin-sql 共用同一个 seekcode.db 文件
+lazy_static::lazy_static! {
+    static ref DB_POOL: RwLock<Option<SqlitePool>> = RwLock::new(None);
+}
+
+/// 数据库文件路径
+///
+/// 必须与 tauri-plugin-sql 的 path_mapper 保持一致（app_config_dir 下的 seekcode.db），
+/// 否则 Rust 与前端会读写两个不同的文件。
+pub fn db_path(app: &tauri::AppHandle) -> std::path::PathBuf {
+    let app_config_dir = app
+        .path()
+        .app_config_dir()
+        .unwrap_or_else(|_| std::path::PathBuf::from("."));
+    app_config_dir.join("seekcode.db")
+}
+
+/// 获取（必要时创建）数据库连接池
+///
+/// 数据库文件由前端插件在执行迁移时创建，这里不会自动创建空文件。
+pub async fn get_pool(app: &tauri::AppHandle) -> Result<SqlitePool, String> {
+    if let Some(pool) = DB_POOL.read().await.as_ref() {
+        return Ok(pool.clone());
+    }
+
+    let mut guard = DB_POOL.write().await;
+    if let Some(pool) = guard.as_ref() {
+        return Ok(pool.clone());
+    }
+
+    let path = db_path(app);
+    if !path.is_file() {
+        return Err(t_with(
+            "errors.databaseNotFound",
+            &[("path", &path.display())],
+        ));
+    }
+    let options = SqliteConnectOptions::new()
+        .filename(&path)
+        .create_if_missing(false);
+    let pool = SqlitePool::connect_with(options)
+        .await
+        .map_err(|e| format!("数据库连接失败: {}", e))?;
+    *guard = Some(pool.clone());
+    Ok(pool)
+}
+
+/// 关闭连接池，并在 f 执行期间阻止重新连接（用于替换数据库文件）
+///
+/// f 完成后连接池保持为空，下次 get_pool 时重新连接。
+pub async fn with_pool_closed<F: std::future::Future>(f: F) -> F::Output {
+    let mut guard = DB_POOL.write().await;
+    if let Some(pool) = guard.take() {
+        pool.close().await;
+    }
+    f.await
+}
+
+/// 当前时间戳，与前端 get_current_timestamp 保持同一格式
+pub fn now_timestamp() -> String {
+    chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+/// 读取单个用户设置
+pub async fn get_setting(pool: &SqlitePool, key: &str) -> Option<String> {
+    sqlx::query("SELECT value FROM user_settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .ok()
+        .flatten()
+        .map(|row| row.get::<String, _>("value"))
+}
+
+/// 写入单个用户设置，保留原有的 created_at（可在连接池或事务中执行）
+pub async fn set_setting<'e, E>(executor: E, key: &str, value: &str) -> Result<(), String>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    let now = now_timestamp();
+    sqlx::query(
+        "INSERT OR REPLACE INTO user_settings (key, value, created_at, updated_at)
+         VALUES (?, ?, COALESCE((SELECT created_at FROM user_settings WHERE key = ?), ?), ?)",
+    )
+    .bind(key)
+    .bind(value)
+    .bind(key)
+    .bind(&now)
+    .bind(&now)
+    .execute(executor)
+    .await
+    .map_err(|e| format!("Failed to save setting {}: {}", key, e))?;
+    Ok(())
+}
@@ -0,0 +1,798 @@
+use crate::clipboard;
+use crate::db::{get_setting, set_setting};
+use crate::i18n::{t, t_with};
+use crate::snippets::normalize_for_hash;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::{hmac, pbkdf2};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::num::NonZeroU32;
+use std::sync::{Arc, RwLock};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::watch;
+
+/// 加密字段的前缀，没有该前缀的值视为明文
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+/// 用于校验口令的已知明文
+const VERIFIER_PLAINTEXT: &str = "seekcode-encryption-check";
+pub const MIN_PASSPHRASE_LENGTH: usize = 8;
+
+const KEYRING_SERVICE: &str = "SeekCode";
+const KEYRING_ACCOUNT: &str = "database-key";
+
+// ============================================================================
+// 密钥与加解密
+// ============================================================================
+
+/// 由口令派生的密钥：AEAD 加密 code/content 字段，HMAC 计算内容哈希（避免明文哈希泄露内容）
+struct DatabaseKey {
+    raw: [u8; KEY_LEN],
+    aead: LessSafeKey,
+    hash: hmac::Key,
+}
+
+impl DatabaseKey {
+    fn from_raw(raw: [u8; KEY_LEN]) -> Self {
+        let aead = LessSafeKey::new(
+            UnboundKey::new(&CHACHA20_POLY1305, &raw).expect("key length matches algorithm"),
+        );
+        let hash_key = hmac::sign(
+            &hmac::Key::new(hmac::HMAC_SHA256, &raw),
+            b"seekcode content hash",
+        );
+        Self {
+            raw,
+            aead,
+            hash: hmac::Key::new(hmac::HMAC_SHA256, hash_key.as_ref()),
+        }
+    }
+
+    fn derive(passphrase: &str, salt: &[u8]) -> Self {
+        let mut raw = [0u8; KEY_LEN];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            NonZeroU32::new(PBKDF2_ITERATIONS).expect("iterations are non-zero"),
+            salt,
+            passphrase.as_bytes(),
+            &mut raw,
+        );
+        Self::from_raw(raw)
+    }
+
+    /// 加密任意字节，返回 nonce‖密文
+    fn seal_raw(&self, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| "Failed to generate nonce".to_string())?;
+
+        let mut buffer = plaintext.to_vec();
+        self.aead
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut buffer,
+            )
+            .map_err(|_| "Failed to encrypt value".to_string())?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&buffer);
+        Ok(payload)
+    }
+
+    /// 解密 nonce‖密文
+    fn open_raw(&self, payload: &[u8]) -> Result<Vec<u8>, String> {
+        if payload.len() < NONCE_LEN {
+            return Err(t("errors.encryptedValueCorrupted"));
+        }
+
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| t("errors.encryptedValueCorrupted"))?;
+        let mut buffer = ciphertext.to_vec();
+        let plaintext = self
+            .aead
+            .open_in_place(nonce, Aad::empty(), &mut buffer)
+            .map_err(|_| t("errors.decryptFailed"))?;
+        Ok(plaintext.to_vec())
+    }
+
+    fn seal(&self, plaintext: &str) -> Result<String, String> {
+        let payload = self.seal_raw(plaintext.as_bytes())?;
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(payload)))
+    }
+
+    fn open(&self, stored: &str) -> Result<String, String> {
+        let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let payload = BASE64
+            .decode(encoded)
+            .map_err(|_| t("errors.encryptedValueCorrupted"))?;
+        String::from_utf8(self.open_raw(&payload)?).map_err(|_| t("errors.decryptedNotUtf8"))
+    }
+
+    /// BLOB 字段直接存储 前缀‖nonce‖密文，不做 base64 编码
+    fn seal_bytes(&self, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        let mut stored = ENCRYPTED_PREFIX.as_bytes().to_vec();
+        stored.extend(self.seal_raw(plaintext)?);
+        Ok(stored)
+    }
+
+    fn open_bytes(&self, stored: &[u8]) -> Result<Vec<u8>, String> {
+        match stored.strip_prefix(ENCRYPTED_PREFIX.as_bytes()) {
+            Some(payload) => self.open_raw(payload),
+            None => Ok(stored.to_vec()),
+        }
+    }
+}
+
+/// 内容哈希：启用加密时使用 HMAC，否则为 SHA-256
+fn digest(key: Option<&DatabaseKey>, code: &str) -> String {
+    let normalized = normalize_for_hash(code);
+    match key {
+        Some(key) => to_hex(hmac::sign(&key.hash, normalized.as_bytes()).as_ref()),
+        None => to_hex(&Sha256::digest(normalized.as_bytes())),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+enum EncryptionState {
+    /// 启动时尚未读取加密设置，按已加密处理，拒绝读写
+    Uninitialized,
+    Disabled,
+    Locked,
+    Unlocked(Arc<DatabaseKey>),
+}
+
+lazy_static::lazy_static! {
+    static ref STATE: RwLock<EncryptionState> = RwLock::new(EncryptionState::Uninitialized);
+    static ref INITIALIZED: watch::Sender<bool> = watch::channel(false).0;
+}
+
+fn set_state(state: EncryptionState) {
+    *STATE.write().unwrap_or_else(|e| e.into_inner()) = state;
+}
+
+fn current_key() -> Result<Option<Arc<DatabaseKey>>, String> {
+    match &*STATE.read().unwrap_or_else(|e| e.into_inner()) {
+        EncryptionState::Uninitialized => Err(t("errors.encryptionInitializing")),
+        EncryptionState::Disabled => Ok(None),
+        EncryptionState::Locked => Err(t("errors.databaseLocked")),
+        EncryptionState::Unlocked(key) => Ok(Some(key.clone())),
+    }
+}
+
+/// 等待启动时的加密状态初始化完成
+pub async fn wait_initialized() {
+    let mut initialized = INITIALIZED.subscribe();
+    let _ = initialized.wait_for(|done| *done).await;
+}
+
+/// 是否启用了加密（无论是否已解锁）；尚未初始化时按已启用处理
+pub fn is_enabled() -> bool {
+    !matches!(
+        &*STATE.read().unwrap_or_else(|e| e.into_inner()),
+        EncryptionState::Disabled
+    )
+}
+
+/// 数据库已加密且未解锁时返回错误
+pub fn ensure_unlocked() -> Result<(), String> {
+    current_key().map(|_| ())
+}
+
+/// 加密要写入 code/content 字段的值；未启用加密时原样返回
+pub fn seal(plaintext: &str) -> Result<String, String> {
+    match current_key()? {
+        Some(key) => key.seal(plaintext),
+        None => Ok(plaintext.to_string()),
+    }
+}
+
+/// 解密从 code/content 字段读出的值；明文值原样返回
+pub fn open(stored: &str) -> Result<String, String> {
+    if !stored.starts_with(ENCRYPTED_PREFIX) {
+        return Ok(stored.to_string());
+    }
+    match current_key() {
+        Ok(Some(key)) => key.open(stored),
+        _ => Err(t("errors.databaseLocked")),
+    }
+}
+
+/// 加密 BLOB 字段（图片、HTML 等）；未启用加密时原样返回
+pub fn seal_bytes(plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    match current_key()? {
+        Some(key) => key.seal_bytes(plaintext),
+        None => Ok(plaintext.to_vec()),
+    }
+}
+
+/// 解密 BLOB 字段；明文值原样返回
+pub fn open_bytes(stored: &[u8]) -> Result<Vec<u8>, String> {
+    if !stored.starts_with(ENCRYPTED_PREFIX.as_bytes()) {
+        return Ok(stored.to_vec());
+    }
+    match current_key() {
+        Ok(Some(key)) => key.open_bytes(stored),
+        _ => Err(t("errors.databaseLocked")),
+    }
+}
+
+/// 按当前加密状态计算代码内容哈希
+pub fn content_digest(code: &str) -> String {
+    digest(current_key().ok().flatten().as_deref(), code)
+}
+
+// ============================================================================
+// 系统钥匙串（通过平台自带的命令行工具访问）
+// ============================================================================
+
+async fn run_tool(program: &str, args: &[&str], stdin: Option<&str>) -> Result<String, String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    if let Some(mut pipe) = child.stdin.take() {
+        if let Some(input) = stdin {
+            pipe.write_all(input.as_bytes())
+                .await
+                .map_err(|e| format!("Failed to write to {}: {}", program, e))?;
+        }
+    }
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 将密钥保存到系统钥匙串（密钥经 stdin 传入，不出现在进程参数中）
+async fn keyring_store(secret: &str) -> Result<(), String> {
+    if cfg!(target_os = "macos") {
+        let command = format!(
+            "add-generic-password -U -a {} -s {} -w {}\n",
+            KEYRING_ACCOUNT, KEYRING_SERVICE, secret
+        );
+        run_tool("security", &["-i"], Some(&command)).await?;
+    } else if cfg!(target_os = "linux") {
+        run_tool(
+            "secret-tool",
+            &[
+                "store",
+                "--label=SeekCode database key",
+                "service",
+                KEYRING_SERVICE,
+                "account",
+                KEYRING_ACCOUNT,
+            ],
+            Some(secret),
+        )
+        .await?;
+    } else {
+        return Err(t("errors.keyringUnsupported"));
+    }
+
+    // security -i 在命令失败时也可能返回成功，读回确认
+    match keyring_load().await? {
+        Some(stored) if stored == secret => Ok(()),
+        _ => Err(t("errors.keyringSaveFailed")),
+    }
+}
+
+async fn keyring_load() -> Result<Option<String>, String> {
+    let result = if cfg!(target_os = "macos") {
+        run_tool(
+            "security",
+            &[
+                "find-generic-password",
+                "-a",
+                KEYRING_ACCOUNT,
+                "-s",
+                KEYRING_SERVICE,
+                "-w",
+            ],
+            None,
+        )
+        .await
+    } else if cfg!(target_os = "linux") {
+        run_tool(
+            "secret-tool",
+            &[
+                "lookup",
+                "service",
+                KEYRING_SERVICE,
+                "account",
+                KEYRING_ACCOUNT,
+            ],
+            None,
+        )
+        .await
+    } else {
+        return Err(t("errors.keyringUnsupported"));
+    };
+    // 找不到条目时工具以非零状态退出
+    Ok(result.ok().filter(|secret| !secret.is_empty()))
+}
+
+async fn keyring_delete() {
+    let result = if cfg!(target_os = "macos") {
+        run_tool(
+            "security",
+            &[
+                "delete-generic-password",
+                "-a",
+                KEYRING_ACCOUNT,
+                "-s",
+                KEYRING_SERVICE,
+            ],
+            None,
+        )
+        .await
+    } else if cfg!(target_os = "linux") {
+        run_tool(
+            "secret-tool",
+            &[
+                "clear",
+                "service",
+                KEYRING_SERVICE,
+                "account",
+                KEYRING_ACCOUNT,
+            ],
+            None,
+        )
+        .await
+    } else {
+        return;
+    };
+    if let Err(e) = result {
+        tracing::debug!("failed to remove key from keyring: {}", e);
+    }
+}
+
+// ============================================================================
+// 启用、关闭、解锁与锁定
+// ============================================================================
+
+/// 加密状态
+#[derive(Debug, Clone, Serialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    /// 数据是否可读（未启用加密时始终为 true）
+    pub unlocked: bool,
+    /// 密钥是否保存在系统钥匙串中（启动时自动解锁）
+    pub remembered: bool,
+}
+
+pub async fn status(pool: &SqlitePool) -> EncryptionStatus {
+    let (enabled, unlocked) = match &*STATE.read().unwrap_or_else(|e| e.into_inner()) {
+        EncryptionState::Disabled => (false, true),
+        EncryptionState::Uninitialized | EncryptionState::Locked => (true, false),
+        EncryptionState::Unlocked(_) => (true, true),
+    };
+    EncryptionStatus {
+        enabled,
+        unlocked,
+        remembered: enabled
+            && get_setting(pool, "encryption_key_source").await.as_deref() == Some("keyring"),
+    }
+}
+
+/// 读取盐值和校验值
+async fn load_key_material(pool: &SqlitePool) -> Result<(Vec<u8>, String), String> {
+    let salt = get_setting(pool, "encryption_salt")
+        .await
+        .and_then(|salt| BASE64.decode(salt).ok())
+        .ok_or_else(|| t("errors.encryptionSaltMissing"))?;
+    let verifier = get_setting(pool, "encryption_verifier")
+        .await
+        .ok_or_else(|| t("errors.encryptionVerifierMissing"))?;
+    Ok((salt, verifier))
+}
+
+fn verify(key: &DatabaseKey, verifier: &str) -> bool {
+    key.open(verifier).ok().as_deref() == Some(VERIFIER_PLAINTEXT)
+}
+
+/// 用口令派生密钥并校验（PBKDF2 较慢，放到阻塞线程执行）
+async fn derive_verified(pool: &SqlitePool, passphrase: &str) -> Result<DatabaseKey, String> {
+    let (salt, verifier) = load_key_material(pool).await?;
+    let passphrase = passphrase.to_string();
+    let key = tokio::task::spawn_blocking(move || DatabaseKey::derive(&passphrase, &salt))
+        .await
+        .map_err(|e| t_with("errors.keyDerivationFailed", &[("error", &e)]))?;
+    if !verify(&key, &verifier) {
+        return Err(t("errors.incorrectPassphrase"));
+    }
+    Ok(key)
+}
+
+/// 保存或移除钥匙串中的密钥，返回实际是否已保存
+async fn remember_key(pool: &SqlitePool, key: &DatabaseKey, remember: bool) -> bool {
+    let remembered = if remember {
+        match keyring_store(&BASE64.encode(key.raw)).await {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("failed to save key to keyring: {}", e);
+                false
+            }
+        }
+    } else {
+        keyring_delete().await;
+        false
+    };
+    let source = if remembered { "keyring" } else { "passphrase" };
+    if let Err(e) = set_setting(pool, "encryption_key_source", source).await {
+        tracing::warn!("{}", e);
+    }
+    remembered
+}
+
+/// 根据设置初始化加密状态；密钥保存在钥匙串中时自动解锁
+///
+/// 只有成功读到设置且未启用加密时才进入未加密状态，读取失败时保持锁定。
+pub async fn init(pool: &SqlitePool) {
+    set_state(load_state(pool).await);
+    INITIALIZED.send_replace(true);
+}
+
+/// 无法打开数据库时结束初始化：保持锁定，避免按未加密状态写入
+pub fn init_unavailable() {
+    set_state(EncryptionState::Locked);
+    INITIALIZED.send_replace(true);
+}
+
+async fn load_state(pool: &SqlitePool) -> EncryptionState {
+    let enabled: Option<String> = match sqlx::query_scalar(
+        "SELECT value FROM user_settings WHERE key = 'encryption_enabled'",
+    )
+    .fetch_optional(pool)
+    .await
+    {
+        Ok(enabled) => enabled,
+        Err(e) => {
+            tracing::warn!("failed to read encryption settings: {}", e);
+            return EncryptionState::Locked;
+        }
+    };
+    if enabled.as_deref() != Some("true") {
+        return EncryptionState::Disabled;
+    }
+
+    if get_setting(pool, "encryption_key_source").await.as_deref() != Some("keyring") {
+        return EncryptionState::Locked;
+    }
+    let Ok((_, verifier)) = load_key_material(pool).await else {
+        return EncryptionState::Locked;
+    };
+    let raw = keyring_load()
+        .await
+        .ok()
+        .flatten()
+        .and_then(|secret| BASE64.decode(secret).ok())
+        .and_then(|raw| <[u8; KEY_LEN]>::try_from(raw).ok());
+    match raw.map(DatabaseKey::from_raw) {
+        Some(key) if verify(&key, &verifier) => EncryptionState::Unlocked(Arc::new(key)),
+        _ => EncryptionState::Locked,
+    }
+}
+
+/// 把全部 code/content 字段、剪贴板 BLOB 字段和内容哈希从 `from` 密钥重写为 `to` 密钥
+///
+/// 密钥为 None 表示明文。
+async fn rewrite_columns(
+    conn: &mut SqliteConnection,
+    from: Option<&DatabaseKey>,
+    to: Option<&DatabaseKey>,
+) -> Result<(), String> {
+    let decode = |stored: &str| match from {
+        Some(key) => key.open(stored),
+        None => Ok(stored.to_string()),
+    };
+    let encode = |plain: &str| match to {
+        Some(key) => key.seal(plain),
+        None => Ok(plain.to_string()),
+    };
+    let decode_bytes = |stored: Option<Vec<u8>>| {
+        stored
+            .map(|stored| match from {
+                Some(key) => key.open_bytes(&stored),
+                None => Ok(stored),
+            })
+            .transpose()
+    };
+    let encode_bytes = |plain: &Option<Vec<u8>>| {
+        plain
+            .as_ref()
+            .map(|plain| match to {
+                Some(key) => key.seal_bytes(plain),
+                None => Ok(plain.clone()),
+            })
+            .transpose()
+    };
+
+    let snippets = sqlx::query("SELECT id, code FROM code_snippets")
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    for row in snippets {
+        let id: i64 = row.get("id");
+        let code = decode(&row.get::<String, _>("code"))?;
+        sqlx::query("UPDATE code_snippets SET code = ?, content_hash = ? WHERE id = ?")
+            .bind(encode(&code)?)
+            .bind(digest(to, &code))
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    let items = sqlx::query("SELECT id, kind, content, data, thumbnail FROM clipboard_items")
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    for row in items {
+        let id: i64 = row.get("id");
+        let kind: String = row.get("kind");
+        let content = decode(&row.get::<String, _>("content"))?;
+        let data = decode_bytes(row.get("data"))?;
+        let thumbnail = decode_bytes(row.get("thumbnail"))?;
+        let fingerprint = clipboard::fingerprint(&kind, &content, data.as_deref());
+        sqlx::query(
+            "UPDATE clipboard_items SET content = ?, data = ?, thumbnail = ?, content_hash = ?
+             WHERE id = ?",
+        )
+        .bind(encode(&content)?)
+        .bind(encode_bytes(&data)?)
+        .bind(encode_bytes(&thumbnail)?)
+        .bind(digest(to, &fingerprint))
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    // 加密后触发器已从全文索引中删除明文，合并索引段以清除残留的旧数据
+    if to.is_some() {
+        sqlx::query("INSERT INTO clipboard_fts(clipboard_fts) VALUES ('optimize')")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        return Err(t_with(
+            "errors.passphraseTooShort",
+            &[("min", &MIN_PASSPHRASE_LENGTH)],
+        ));
+    }
+    Ok(())
+}
+
+/// 清除数据库文件中残留的明文：WAL 中的旧页面和空闲页中被覆盖前的内容
+///
+/// 失败时只记录日志，数据本身已经加密。
+async fn purge_plaintext(pool: &SqlitePool) {
+    for statement in [
+        "PRAGMA wal_checkpoint(TRUNCATE)",
+        "VACUUM",
+        "PRAGMA wal_checkpoint(TRUNCATE)",
+    ] {
+        if let Err(e) = sqlx::query(statement).execute(pool).await {
+            tracing::warn!("{} failed after enabling encryption: {}", statement, e);
+        }
+    }
+}
+
+/// 启用加密：用口令派生密钥，并在单个事务中加密现有的代码片段和剪贴板内容
+pub async fn enable(
+    pool: &SqlitePool,
+    passphrase: &str,
+    remember: bool,
+) -> Result<EncryptionStatus, String> {
+    if is_enabled() {
+        return Err(t("errors.encryptionAlreadyEnabled"));
+    }
+    validate_passphrase(passphrase)?;
+
+    let mut salt = [0u8; SALT_LEN];
+    SystemRandom::new()
+        .fill(&mut salt)
+        .map_err(|_| "Failed to generate salt".to_string())?;
+    let passphrase = passphrase.to_string();
+    let key = tokio::task::spawn_blocking(move || DatabaseKey::derive(&passphrase, &salt))
+        .await
+        .map_err(|e| t_with("errors.keyDerivationFailed", &[("error", &e)]))?;
+    let verifier = key.seal(VERIFIER_PLAINTEXT)?;
+
+    // 立即获取写锁：其他写入（剪贴板、代码片段）在事务提交后才能继续，
+    // 并在提交前切换状态，使它们按新状态加密
+    let mut tx = pool
+        .begin_with("BEGIN IMMEDIATE")
+        .await
+        .map_err(|e| e.to_string())?;
+    rewrite_columns(&mut tx, None, Some(&key)).await?;
+    set_setting(&mut *tx, "encryption_salt", &BASE64.encode(salt)).await?;
+    set_setting(&mut *tx, "encryption_verifier", &verifier).await?;
+    set_setting(&mut *tx, "encryption_enabled", "true").await?;
+
+    let key = Arc::new(key);
+    set_state(EncryptionState::Unlocked(key.clone()));
+    if let Err(e) = tx.commit().await {
+        set_state(EncryptionState::Disabled);
+        return Err(e.to_string());
+    }
+
+    purge_plaintext(pool).await;
+    remember_key(pool, &key, remember).await;
+    Ok(status(pool).await)
+}
+
+/// 关闭加密：校验口令后在单个事务中解密全部数据，并移除钥匙串中的密钥
+pub async fn disable(pool: &SqlitePool, passphrase: &str) -> Result<EncryptionStatus, String> {
+    if !is_enabled() {
+        return Err(t("errors.encryptionNotEnabled"));
+    }
+    let key = derive_verified(pool, passphrase).await?;
+
+    let mut tx = pool
+        .begin_with("BEGIN IMMEDIATE")
+        .await
+        .map_err(|e| e.to_string())?;
+    rewrite_columns(&mut tx, Some(&key), None).await?;
+    sqlx::query(
+        "DELETE FROM user_settings
+         WHERE key IN ('encryption_salt', 'encryption_verifier', 'encryption_key_source')",
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    set_setting(&mut *tx, "encryption_enabled", "false").await?;
+
+    set_state(EncryptionState::Disabled);
+    if let Err(e) = tx.commit().await {
+        set_state(EncryptionState::Unlocked(Arc::new(key)));
+        return Err(e.to_string());
+    }
+
+    keyring_delete().await;
+    Ok(status(pool).await)
+}
+
+/// 用口令解锁；remember 为 true 时把密钥保存到系统钥匙串，为 false 时从钥匙串移除
+pub async fn unlock(
+    pool: &SqlitePool,
+    passphrase: &str,
+    remember: bool,
+) -> Result<EncryptionStatus, String> {
+    if !is_enabled() {
+        return Err(t("errors.encryptionNotEnabled"));
+    }
+    let key = Arc::new(derive_verified(pool, passphrase).await?);
+    set_state(EncryptionState::Unlocked(key.clone()));
+    remember_key(pool, &key, remember).await;
+    Ok(status(pool).await)
+}
+
+/// 锁定数据库：丢弃内存中的密钥（钥匙串中的密钥保留，下次启动仍会自动解锁）
+pub async fn lock(pool: &SqlitePool) -> Result<EncryptionStatus, String> {
+    if !is_enabled() {
+        return Err(t("errors.encryptionNotEnabled"));
+    }
+    set_state(EncryptionState::Locked);
+    Ok(status(pool).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> DatabaseKey {
+        DatabaseKey::from_raw([byte; KEY_LEN])
+    }
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let key = key(1);
+        let sealed = key.seal("let secret = 42;").unwrap();
+        assert!(sealed.starts_with(ENCRYPTED_PREFIX));
+        assert!(!sealed.contains("secret"));
+        assert_eq!(key.open(&sealed).unwrap(), "let secret = 42;");
+        // 同一明文每次使用不同的 nonce
+        assert_ne!(key.seal("let secret = 42;").unwrap(), sealed);
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let key = key(2);
+        let data = vec![0u8, 159, 146, 150, 255];
+        let sealed = key.seal_bytes(&data).unwrap();
+        assert!(sealed.starts_with(ENCRYPTED_PREFIX.as_bytes()));
+        assert_eq!(key.open_bytes(&sealed).unwrap(), data);
+    }
+
+    #[test]
+    fn plaintext_values_pass_through() {
+        let key = key(3);
+        assert_eq!(key.open("plain text").unwrap(), "plain text");
+        assert_eq!(key.open_bytes(b"plain").unwrap(), b"plain".to_vec());
+    }
+
+    #[test]
+    fn wrong_key_fails_to_open() {
+        let sealed = key(4).seal("hello").unwrap();
+        assert!(key(5).open(&sealed).is_err());
+
+        let sealed = key(4).seal_bytes(b"hello").unwrap();
+        assert!(key(5).open_bytes(&sealed).is_err());
+    }
+
+    #[test]
+    fn corrupted_values_are_rejected() {
+        let key = key(6);
+        assert!(key.open("enc:v1:not base64!").is_err());
+        assert!(key
+            .open(&format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode([1u8; 4])))
+            .is_err());
+
+        let mut sealed = key.seal_bytes(b"hello").unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 0xff;
+        assert!(key.open_bytes(&sealed).is_err());
+    }
+
+    #[test]
+    fn verifier_accepts_only_the_right_passphrase() {
+        let salt = [7u8; SALT_LEN];
+        let key = DatabaseKey::derive("correct horse", &salt);
+        let verifier = key.seal(VERIFIER_PLAINTEXT).unwrap();
+
+        assert!(verify(
+            &DatabaseKey::derive("correct horse", &salt),
+            &verifier
+        ));
+        assert!(!verify(
+            &DatabaseKey::derive("battery staple", &salt),
+            &verifier
+        ));
+        assert!(!verify(
+            &DatabaseKey::derive("correct horse", &[8u8; SALT_LEN]),
+            &verifier
+        ));
+    }
+
+    #[test]
+    fn digest_depends_on_key() {
+        let plain = digest(None, "code");
+        let keyed = digest(Some(&key(9)), "code");
+        assert_ne!(plain, keyed);
+        assert_eq!(keyed, digest(Some(&key(9)), "code"));
+        assert_ne!(keyed, digest(Some(&key(10)), "code"));
+    }
+}
@@ -0,0 +1,410 @@
+use crate::models::CodeSnippet;
+use quick_xml::escape::escape;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// SeekCode 占位符
+// ============================================================================
+//
+// 代码片段中可以使用以下占位符，导出时转换为各编辑器的 tab-stop 语法：
+//   {{name}}          命名占位符
+//   {{name:default}}  带默认值的命名占位符
+//   {{cursor}}        最终光标位置
+
+/// 解析后的代码片段内容
+#[derive(Debug)]
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder {
+        name: &'a str,
+        default: Option<&'a str>,
+    },
+    Cursor,
+}
+
+fn is_placeholder_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 将代码拆分为普通文本和占位符
+fn parse_placeholders(code: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = code;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        let inner = &after[..end];
+        let (name, default) = match inner.split_once(':') {
+            Some((name, default)) => (name.trim(), Some(default)),
+            None => (inner.trim(), None),
+        };
+
+        if !is_placeholder_name(name) {
+            // 不是占位符（例如模板语言中的 {{ value }} 表达式），原样保留
+            segments.push(Segment::Text(&rest[..start + 2]));
+            rest = after;
+            continue;
+        }
+
+        if start > 0 {
+            segments.push(Segment::Text(&rest[..start]));
+        }
+        if name == "cursor" && default.is_none() {
+            segments.push(Segment::Cursor);
+        } else {
+            segments.push(Segment::Placeholder { name, default });
+        }
+        rest = &after[end + 2..];
+    }
+
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    segments
+}
+
+/// 转义 VS Code 代码片段中有特殊含义的字符：\ 本身是转义符，必须一并转义
+fn escape_vscode(text: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '\\' || special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// 转换为 VS Code 代码片段语法：${1:name}、$0，并转义普通文本中的 \ 和 $
+pub fn to_vscode_body(code: &str) -> String {
+    let mut body = String::with_capacity(code.len());
+    let mut tab_stops: Vec<&str> = Vec::new();
+
+    for segment in parse_placeholders(code) {
+        match segment {
+            Segment::Text(text) => body.push_str(&escape_vscode(text, &['$'])),
+            Segment::Cursor => body.push_str("$0"),
+            Segment::Placeholder { name, default } => {
+                // 同名占位符再次出现时只引用 tab-stop 编号，保持联动编辑
+                if let Some(index) = tab_stops.iter().position(|n| *n == name) {
+                    body.push_str(&format!("${}", index + 1));
+                    continue;
+                }
+                tab_stops.push(name);
+                let label = escape_vscode(default.unwrap_or(name), &['$', '}']);
+                body.push_str(&format!("${{{}:{}}}", tab_stops.len(), label));
+            }
+        }
+    }
+    body
+}
+
+/// 转换为 JetBrains 实时模板语法：$NAME$、$END$，普通文本中的 $ 写作 $$
+/// 返回模板内容以及变量列表（名称、默认值）
+pub fn to_jetbrains_template(code: &str) -> (String, Vec<(String, Option<String>)>) {
+    let mut value = String::with_capacity(code.len());
+    let mut variables: Vec<(String, Option<String>)> = Vec::new();
+
+    for segment in parse_placeholders(code) {
+        match segment {
+            Segment::Text(text) => value.push_str(&text.replace('$', "$$")),
+            Segment::Cursor => value.push_str("$END$"),
+            Segment::Placeholder { name, default } => {
+                let variable = name.to_uppercase();
+                value.push_str(&format!("${}$", variable));
+                if !variables.iter().any(|(n, _)| *n == variable) {
+                    variables.push((variable, default.map(str::to_string)));
+                }
+            }
+        }
+    }
+    (value, variables)
+}
+
+/// 由标题生成缩写/前缀：小写字母数字，单词之间用连字符连接
+fn abbreviation(title: &str, id: i64) -> String {
+    let slug = title
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        format!("snippet-{}", id)
+    } else {
+        slug
+    }
+}
+
+// ============================================================================
+// VS Code
+// ============================================================================
+
+/// SeekCode 语言到 VS Code 语言标识的映射
+fn vscode_language_id(language: &str) -> &str {
+    match language {
+        "shell" => "shellscript",
+        "text" => "plaintext",
+        other => other,
+    }
+}
+
+/// 导出为 VS Code 代码片段文件，每种语言一个 seekcode-<language>.code-snippets
+pub fn export_vscode(snippets: &[CodeSnippet], dir: &Path) -> Result<Vec<String>, String> {
+    let mut by_language: BTreeMap<&str, Vec<&CodeSnippet>> = BTreeMap::new();
+    for snippet in snippets {
+        by_language
+            .entry(snippet.language.as_str())
+            .or_default()
+            .push(snippet);
+    }
+
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let mut written = Vec::new();
+    for (language, snippets) in by_language {
+        let mut definitions = serde_json::Map::new();
+        for snippet in snippets {
+            let mut name = snippet.title.clone();
+            if definitions.contains_key(&name) {
+                name = format!("{} ({})", snippet.title, snippet.id);
+            }
+            let body: Vec<String> = to_vscode_body(&snippet.code)
+                .lines()
+                .map(str::to_string)
+                .collect();
+            definitions.insert(
+                name,
+                serde_json::json!({
+                    "scope": vscode_language_id(language),
+                    "prefix": abbreviation(&snippet.title, snippet.id),
+                    "body": body,
+                    "description": snippet.title,
+                }),
+            );
+        }
+
+        let path: PathBuf = dir.join(format!("seekcode-{}.code-snippets", language));
+        let json = serde_json::to_string_pretty(&definitions)
+            .map_err(|e| format!("Failed to serialize snippets: {}", e))?;
+        std::fs::write(&path, json).map_err(|e| format!("Failed to write file: {}", e))?;
+        written.push(path.to_string_lossy().into_owned());
+    }
+    Ok(written)
+}
+
+// ============================================================================
+// JetBrains
+// ============================================================================
+
+/// SeekCode 语言到 JetBrains 模板上下文的映射
+fn jetbrains_context(language: &str) -> &'static str {
+    match language {
+        "java" => "JAVA_CODE",
+        "python" => "Python",
+        "javascript" => "JAVA_SCRIPT",
+        "typescript" => "TypeScript",
+        "go" => "GO",
+        "rust" => "RUST_FILE",
+        "sql" => "SQL",
+        "html" | "vue" => "HTML",
+        "css" => "CSS",
+        "php" => "PHP",
+        "shell" => "SHELL_SCRIPT",
+        "c" | "cpp" => "OC_SOURCE",
+        "xml" => "XML",
+        "json" => "JSON",
+        "markdown" => "MARKDOWN",
+        "yaml" => "YAML",
+        _ => "OTHER",
+    }
+}
+
+/// 转义 XML 属性值，保留换行
+fn xml_attr(value: &str) -> String {
+    escape(value).replace('\n', "&#10;").replace('\r', "")
+}
+
+/// 导出为 JetBrains 实时模板 XML（templateSet group="SeekCode"）
+pub fn export_jetbrains(snippets: &[CodeSnippet], path: &Path) -> Result<usize, String> {
+    let mut xml = String::from("<templateSet group=\"SeekCode\">\n");
+    let mut names = HashSet::new();
+
+    for snippet in snippets {
+        let (value, variables) = to_jetbrains_template(&snippet.code);
+        // 同一模板组中的名称必须唯一，标题相同的模板追加序号
+        let base = abbreviation(&snippet.title, snippet.id);
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while !names.insert(name.clone()) {
+            name = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        xml.push_str(&format!(
+            "  <template name=\"{}\" value=\"{}\" description=\"{}\" toReformat=\"false\" toShortenFQNames=\"true\">\n",
+            xml_attr(&name),
+            xml_attr(&value),
+            xml_attr(&snippet.title),
+        ));
+        for (name, default) in variables {
+            let default = default
+                .map(|d| format!("\"{}\"", d.replace('\\', "\\\\").replace('"', "\\\"")))
+                .unwrap_or_default();
+            xml.push_str(&format!(
+                "    <variable name=\"{}\" expression=\"\" defaultValue=\"{}\" alwaysStopAt=\"true\" />\n",
+                xml_attr(&name),
+                xml_attr(&default),
+            ));
+        }
+        xml.push_str(&format!(
+            "    <context>\n      <option name=\"{}\" value=\"true\" />\n    </context>\n  </template>\n",
+            jetbrains_context(&snippet.language)
+        ));
+    }
+    xml.push_str("</templateSet>\n");
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    std::fs::write(path, xml).map_err(|e| format!("Failed to write file: {}", e))?;
+    Ok(snippets.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::{
+        from_jetbrains_template, from_vscode_body, parse_jetbrains, parse_vscode,
+    };
+
+    fn snippet(id: i64, title: &str, language: &str, code: &str) -> CodeSnippet {
+        CodeSnippet {
+            id,
+            title: title.to_string(),
+            language: language.to_string(),
+            code: code.to_string(),
+            tags: "[]".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            collection_id: None,
+            pinned: false,
+            use_count: 0,
+            last_used_at: None,
+            secret_flags: None,
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("seekcode-{}-{}", name, uuid::Uuid::new_v4()))
+    }
+
+    /// 导出后再导入应得到原始代码
+    const ROUND_TRIP_CODES: &[&str] = &[
+        "echo \"$HOME\" > C:\\dir\\out.txt",
+        r"let re = /\d+\$/; const price = `\${amount}`;",
+        "printf '%s' \"${{name}}\"\\n{{cursor}}",
+        "{{ user.name }} stays as a template expression",
+    ];
+
+    #[test]
+    fn vscode_body_escapes_backslashes_before_dollars() {
+        assert_eq!(to_vscode_body(r"C:\dir $HOME"), r"C:\\dir \$HOME");
+        // 代码中的 \$ 不能变成 \\$，否则 $ 重新成为变量
+        assert_eq!(to_vscode_body(r"\$x"), r"\\\$x");
+        assert_eq!(to_vscode_body("a{{cursor}}b"), "a$0b");
+    }
+
+    #[test]
+    fn vscode_placeholder_defaults_are_escaped() {
+        assert_eq!(to_vscode_body(r"{{price:$5\}}"), r"${1:\$5\\}");
+        assert_eq!(to_vscode_body("{{a:x}y}}"), r"${1:x\}y}");
+        assert_eq!(to_vscode_body("{{name}} {{name}}"), "${1:name} $1");
+        assert_eq!(
+            from_vscode_body(&to_vscode_body(r"{{price:$5\}}")),
+            r"{{arg1:$5\}}"
+        );
+    }
+
+    #[test]
+    fn vscode_body_round_trips() {
+        for code in ROUND_TRIP_CODES {
+            assert_eq!(from_vscode_body(&to_vscode_body(code)), *code);
+        }
+    }
+
+    #[test]
+    fn jetbrains_template_round_trips() {
+        for code in ROUND_TRIP_CODES {
+            let (value, variables) = to_jetbrains_template(code);
+            let variables: Vec<(String, String)> = variables
+                .into_iter()
+                .map(|(name, default)| (name, default.unwrap_or_default()))
+                .collect();
+            assert_eq!(from_jetbrains_template(&value, &variables), *code);
+        }
+        let (value, variables) = to_jetbrains_template("cost: $1 {{port:8080}}{{cursor}}");
+        assert_eq!(value, "cost: $$1 $PORT$$END$");
+        assert_eq!(
+            variables,
+            vec![("PORT".to_string(), Some("8080".to_string()))]
+        );
+    }
+
+    #[test]
+    fn vscode_files_round_trip() {
+        let dir = temp_dir("vscode-export");
+        let snippets: Vec<CodeSnippet> = ROUND_TRIP_CODES
+            .iter()
+            .enumerate()
+            .map(|(i, code)| snippet(i as i64 + 1, &format!("Snippet {}", i), "shell", code))
+            .collect();
+        let written = export_vscode(&snippets, &dir).unwrap();
+        assert_eq!(written.len(), 1);
+
+        let path = Path::new(&written[0]);
+        let imported = parse_vscode(&std::fs::read_to_string(path).unwrap(), path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(imported.len(), snippets.len());
+        for original in &snippets {
+            let found = imported.iter().find(|s| s.title == original.title).unwrap();
+            assert_eq!(found.code, original.code);
+            assert_eq!(found.language, "shell");
+        }
+    }
+
+    #[test]
+    fn jetbrains_file_round_trips() {
+        let dir = temp_dir("jetbrains-export");
+        let path = dir.join("SeekCode.xml");
+        let snippets = vec![
+            snippet(
+                1,
+                "Price",
+                "python",
+                r#"print("$" + {{amount:say "hi" \o/}}){{cursor}}"#,
+            ),
+            snippet(2, "Path & <tags>", "java", ROUND_TRIP_CODES[0]),
+        ];
+        assert_eq!(export_jetbrains(&snippets, &path).unwrap(), 2);
+
+        let imported = parse_jetbrains(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(imported.len(), 2);
+        assert_eq!(imported[0].title, "Price");
+        assert_eq!(imported[0].language, "python");
+        assert_eq!(
+            imported[0].code,
+            r#"print("$" + {{amount:say "hi" \o/}}){{cursor}}"#
+        );
+        assert_eq!(imported[1].title, "Path & <tags>");
+        assert_eq!(imported[1].language, "java");
+        assert_eq!(imported[1].code, ROUND_TRIP_CODES[0]);
+    }
+}
@@ -0,0 +1,554 @@
+use crate::db::now_timestamp;
+use crate::models::CodeSnippet;
+use crate::snippets;
+use crate::sync::{self, parse_file, SyncConflict, SyncReport};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tokio::sync::{Mutex, RwLock};
+
+/// Git 仓库同步在 sync_state 中使用的目标名称
+pub const GIT_TARGET: &str = "git";
+/// 未配置分支时使用的默认分支
+pub const DEFAULT_BRANCH: &str = "main";
+const REMOTE_NAME: &str = "origin";
+
+/// Git 仓库同步配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitSyncConfig {
+    /// 本地仓库路径（工作区即代码片段文件夹）
+    pub path: PathBuf,
+    /// 远程仓库地址，为空时只做本地提交
+    pub remote: Option<String>,
+    pub branch: String,
+}
+
+/// 一次 Git 同步的结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GitSyncReport {
+    /// 合并前将应用内修改写入工作区的结果
+    pub export: SyncReport,
+    /// 合并后将远程修改写回数据库的结果
+    pub import: SyncReport,
+    /// 本次生成的提交
+    pub committed: bool,
+    pub pulled: bool,
+    pub pushed: bool,
+    /// 本次合并中新产生的冲突数
+    pub new_conflicts: usize,
+    pub finished_at: String,
+}
+
+/// Git 冲突处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GitConflictResolution {
+    /// 保留本机版本，下次推送时覆盖远程
+    KeepLocal,
+    /// 采用远程版本，覆盖本机的代码片段
+    KeepRemote,
+}
+
+// 同一时间只允许一个 Git 同步任务运行
+lazy_static::lazy_static! {
+    static ref GIT_SYNC_LOCK: Mutex<()> = Mutex::new(());
+    static ref LAST_GIT_REPORT: RwLock<Option<GitSyncReport>> = RwLock::new(None);
+}
+
+// ============================================================================
+// git 命令
+// ============================================================================
+
+/// 执行 git 命令并返回标准输出；禁用交互式认证提示，避免后台任务挂起
+async fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!("git {} failed: {}", args.join(" "), stderr.trim()))
+    }
+}
+
+/// 初始化仓库（如不存在），并设置远程地址和提交身份
+async fn ensure_repo(config: &GitSyncConfig) -> Result<(), String> {
+    std::fs::create_dir_all(&config.path)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    let dir = config.path.as_path();
+
+    if !dir.join(".git").exists() {
+        git(dir, &["init", "-q", "-b", &config.branch]).await?;
+    }
+
+    // 未配置提交身份时使用仓库级默认值，不修改用户的全局配置
+    if git(dir, &["config", "user.email"]).await.is_err() {
+        git(dir, &["config", "user.email", "seekcode@localhost"]).await?;
+        git(dir, &["config", "user.name", "SeekCode"]).await?;
+    }
+
+    let current_remote = git(dir, &["remote", "get-url", REMOTE_NAME])
+        .await
+        .ok()
+        .map(|url| url.trim().to_string());
+    match (&config.remote, current_remote) {
+        (Some(remote), None) => {
+            git(dir, &["remote", "add", REMOTE_NAME, remote]).await?;
+        }
+        (Some(remote), Some(current)) if *remote != current => {
+            git(dir, &["remote", "set-url", REMOTE_NAME, remote]).await?;
+        }
+        (None, Some(_)) => {
+            git(dir, &["remote", "remove", REMOTE_NAME]).await?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// 提交工作区的全部修改，没有修改时返回 false
+async fn commit_all(dir: &Path, message: &str) -> Result<bool, String> {
+    git(dir, &["add", "-A"]).await?;
+    if git(dir, &["status", "--porcelain"])
+        .await?
+        .trim()
+        .is_empty()
+    {
+        return Ok(false);
+    }
+    git(dir, &["commit", "-q", "-m", message]).await?;
+    Ok(true)
+}
+
+fn commit_message(report: &SyncReport) -> String {
+    format!(
+        "SeekCode sync: {} exported, {} imported, {} created, {} removed",
+        report.exported,
+        report.imported,
+        report.created,
+        report.deleted_files + report.deleted_snippets
+    )
+}
+
+/// 读取合并冲突中某一侧的文件内容（stage 2 = 本机，stage 3 = 远程），该侧已删除时返回 None
+async fn stage_contents(dir: &Path, stage: u8, file_name: &str) -> Option<String> {
+    git(dir, &["show", &format!(":{}:{}", stage, file_name)])
+        .await
+        .ok()
+}
+
+// ============================================================================
+// 合并冲突
+// ============================================================================
+
+async fn save_conflict(
+    pool: &SqlitePool,
+    sync_id: &str,
+    file_name: &str,
+    remote_contents: Option<&str>,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT OR REPLACE INTO sync_conflicts
+         (target, sync_id, file_name, remote_contents, detected_at)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(GIT_TARGET)
+    .bind(sync_id)
+    .bind(file_name)
+    .bind(remote_contents)
+    .bind(now_timestamp())
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn delete_conflict(pool: &SqlitePool, sync_id: &str) -> Result<(), String> {
+    sqlx::query("DELETE FROM sync_conflicts WHERE target = ? AND sync_id = ?")
+        .bind(GIT_TARGET)
+        .bind(sync_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 合并冲突时逐个文件保留本机版本完成合并，并记录远程版本供用户选择
+async fn resolve_merge_with_local(pool: &SqlitePool, dir: &Path) -> Result<usize, String> {
+    let unmerged = git(dir, &["diff", "--name-only", "--diff-filter=U"]).await?;
+    let mut count = 0;
+
+    for file_name in unmerged.lines().filter(|line| !line.is_empty()) {
+        let local = stage_contents(dir, 2, file_name).await;
+        let remote = stage_contents(dir, 3, file_name).await;
+
+        match &local {
+            Some(contents) => {
+                std::fs::write(dir.join(file_name), contents)
+                    .map_err(|e| format!("Failed to write {}: {}", file_name, e))?;
+                git(dir, &["add", "--", file_name]).await?;
+            }
+            None => {
+                git(dir, &["rm", "-q", "--", file_name]).await?;
+            }
+        }
+
+        let sync_id = local
+            .as_deref()
+            .or(remote.as_deref())
+            .and_then(|contents| parse_file(contents).sync_id);
+        if let Some(sync_id) = sync_id {
+            save_conflict(pool, &sync_id, file_name, remote.as_deref()).await?;
+            count += 1;
+        }
+    }
+
+    git(dir, &["commit", "-q", "--no-edit"]).await?;
+    Ok(count)
+}
+
+/// 拉取远程分支并合并；远程分支尚不存在时返回 false
+async fn pull(pool: &SqlitePool, config: &GitSyncConfig) -> Result<(bool, usize), String> {
+    let dir = config.path.as_path();
+    let heads = git(
+        dir,
+        &["ls-remote", "--heads", REMOTE_NAME, config.branch.as_str()],
+    )
+    .await?;
+    if heads.trim().is_empty() {
+        return Ok((false, 0));
+    }
+
+    git(dir, &["fetch", "-q", REMOTE_NAME, config.branch.as_str()]).await?;
+    let merged = git(
+        dir,
+        &[
+            "merge",
+            "-q",
+            "--no-edit",
+            "--allow-unrelated-histories",
+            "FETCH_HEAD",
+        ],
+    )
+    .await;
+
+    match merged {
+        Ok(_) => Ok((true, 0)),
+        Err(e) => {
+            let unmerged = git(dir, &["diff", "--name-only", "--diff-filter=U"]).await?;
+            if unmerged.trim().is_empty() {
+                let _ = git(dir, &["merge", "--abort"]).await;
+                return Err(e);
+            }
+            let conflicts = resolve_merge_with_local(pool, dir).await?;
+            Ok((true, conflicts))
+        }
+    }
+}
+
+// ============================================================================
+// 同步
+// ============================================================================
+
+/// 执行一次 Git 同步
+///
+/// 1. 将数据库写入工作区并提交
+/// 2. 拉取并合并远程分支，冲突的文件先保留本机版本并记录远程版本
+/// 3. 将合并进来的修改写回数据库，再提交并推送
+pub async fn sync_repo(pool: &SqlitePool, config: &GitSyncConfig) -> Result<GitSyncReport, String> {
+    let _guard = GIT_SYNC_LOCK.lock().await;
+    ensure_repo(config).await?;
+    let dir = config.path.as_path();
+
+    let mut report = GitSyncReport {
+        export: sync::reconcile(pool, dir, GIT_TARGET).await?,
+        ..Default::default()
+    };
+    report.committed = commit_all(dir, &commit_message(&report.export)).await?;
+
+    if config.remote.is_some() {
+        let (pulled, conflicts) = pull(pool, config).await?;
+        report.pulled = pulled;
+        report.new_conflicts = conflicts;
+    }
+
+    report.import = sync::reconcile(pool, dir, GIT_TARGET).await?;
+    // 导入时为新文件补写了 id 等内容，需要再次提交
+    if commit_all(dir, &commit_message(&report.import)).await? {
+        report.committed = true;
+    }
+
+    if config.remote.is_some() {
+        git(
+            dir,
+            &[
+                "push",
+                "-q",
+                "-u",
+                REMOTE_NAME,
+                &format!("HEAD:{}", config.branch),
+            ],
+        )
+        .await?;
+        report.pushed = true;
+    }
+
+    report.finished_at = now_timestamp();
+    *LAST_GIT_REPORT.write().await = Some(report.clone());
+    Ok(report)
+}
+
+/// 最近一次 Git 同步结果
+pub async fn last_report() -> Option<GitSyncReport> {
+    LAST_GIT_REPORT.read().await.clone()
+}
+
+async fn snippet_by_sync_id(pool: &SqlitePool, sync_id: &str) -> Option<CodeSnippet> {
+    sqlx::query_as::<_, CodeSnippet>(
+        "SELECT id, title, language, code, tags, created_at, updated_at, collection_id,
+                pinned, use_count, last_used_at
+         FROM code_snippets WHERE sync_id = ?",
+    )
+    .bind(sync_id)
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten()
+    .and_then(|snippet| snippets::decrypt_snippet(snippet).ok())
+}
+
+/// 列出待解决的合并冲突：app_* 为本机代码片段，file_* 为远程版本
+pub async fn list_conflicts(pool: &SqlitePool) -> Result<Vec<SyncConflict>, String> {
+    let rows = sqlx::query(
+        "SELECT sync_id, file_name, remote_contents FROM sync_conflicts
+         WHERE target = ? ORDER BY detected_at",
+    )
+    .bind(GIT_TARGET)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut conflicts = Vec::new();
+    for row in rows {
+        let sync_id: String = row.get("sync_id");
+        let remote: Option<String> = row.get("remote_contents");
+        let remote = remote.as_deref().map(parse_file);
+        let snippet = snippet_by_sync_id(pool, &sync_id).await;
+
+        conflicts.push(SyncConflict {
+            snippet_id: snippet.as_ref().map(|s| s.id),
+            file_name: row.get("file_name"),
+            app_title: snippet.as_ref().map(|s| s.title.clone()),
+            app_code: snippet.as_ref().map(|s| s.code.clone()),
+            app_updated_at: snippet.as_ref().map(|s| s.updated_at.clone()),
+            file_title: remote.as_ref().and_then(|f| f.title.clone()),
+            file_code: remote.map(|f| f.code),
+            sync_id,
+        });
+    }
+    Ok(conflicts)
+}
+
+/// 解决合并冲突并立即同步
+///
+/// 选择远程版本时把远程内容写回工作区（远程已删除则删除文件），
+/// 随后的同步会把它导入数据库并推送。
+pub async fn resolve_conflict(
+    pool: &SqlitePool,
+    config: &GitSyncConfig,
+    sync_id: &str,
+    resolution: GitConflictResolution,
+) -> Result<GitSyncReport, String> {
+    let row = sqlx::query(
+        "SELECT file_name, remote_contents FROM sync_conflicts WHERE target = ? AND sync_id = ?",
+    )
+    .bind(GIT_TARGET)
+    .bind(sync_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("No pending conflict for {}", sync_id))?;
+
+    if resolution == GitConflictResolution::KeepRemote {
+        let _guard = GIT_SYNC_LOCK.lock().await;
+        let file_name: String = row.get("file_name");
+        let path = config.path.join(&file_name);
+        match row.get::<Option<String>, _>("remote_contents") {
+            Some(contents) => std::fs::write(&path, contents)
+                .map_err(|e| format!("Failed to write {}: {}", file_name, e))?,
+            None => {
+                if path.exists() {
+                    std::fs::remove_file(&path)
+                        .map_err(|e| format!("Failed to remove {}: {}", file_name, e))?;
+                }
+            }
+        }
+    }
+
+    delete_conflict(pool, sync_id).await?;
+    sync_repo(pool, config).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::get_migrations;
+    use crate::snippets::{NewSnippet, SnippetPatch};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    /// 带完整表结构的内存数据库（单连接，保证所有查询使用同一个库）
+    async fn test_pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for migration in get_migrations() {
+            sqlx::raw_sql(migration.sql).execute(&pool).await.unwrap();
+        }
+        // 去掉迁移写入的示例代码片段，只保留测试创建的数据
+        sqlx::query("DELETE FROM code_snippets")
+            .execute(&pool)
+            .await
+            .unwrap();
+        // 测试数据库未启用加密
+        crate::encryption::init(&pool).await;
+        pool
+    }
+
+    fn config(path: PathBuf, remote: &Path) -> GitSyncConfig {
+        GitSyncConfig {
+            path,
+            remote: Some(remote.to_string_lossy().into_owned()),
+            branch: DEFAULT_BRANCH.to_string(),
+        }
+    }
+
+    async fn snippet_code(pool: &SqlitePool, id: i64) -> String {
+        let mut conn = pool.acquire().await.unwrap();
+        snippets::get_snippet(&mut conn, id)
+            .await
+            .unwrap()
+            .unwrap()
+            .code
+    }
+
+    /// 修改代码片段；updated_at 只精确到秒，显式写入不同的时间，避免同一秒内的修改被当成未修改
+    async fn edit_code(pool: &SqlitePool, id: i64, code: &str, updated_at: &str) {
+        let patch = SnippetPatch {
+            code: Some(code.to_string()),
+            ..Default::default()
+        };
+        snippets::update_snippet(pool, id, patch).await.unwrap();
+        sqlx::query("UPDATE code_snippets SET updated_at = ? WHERE id = ?")
+            .bind(updated_at)
+            .bind(id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    /// 两台设备通过本地裸仓库同步：推送、拉取以及两种冲突处理方式
+    #[tokio::test]
+    async fn syncs_through_bare_remote_and_resolves_conflicts() {
+        let root = std::env::temp_dir().join(format!("seekcode-git-sync-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        let remote = root.join("remote.git");
+        git(&root, &["init", "-q", "--bare", "remote.git"])
+            .await
+            .unwrap();
+
+        let pool_a = test_pool().await;
+        let pool_b = test_pool().await;
+        let config_a = config(root.join("a"), &remote);
+        let config_b = config(root.join("b"), &remote);
+
+        // 设备 A 创建代码片段并推送，设备 B 拉取后导入
+        let input = NewSnippet {
+            title: "Greeting".to_string(),
+            language: "rust".to_string(),
+            code: "fn greet() {}".to_string(),
+            tags: Vec::new(),
+        };
+        let id_a = snippets::create_snippet(&pool_a, input)
+            .await
+            .unwrap()
+            .into_snippet()
+            .id;
+        let report = sync_repo(&pool_a, &config_a).await.unwrap();
+        assert!(report.committed);
+        assert!(report.pushed);
+        assert!(!report.pulled);
+
+        let report = sync_repo(&pool_b, &config_b).await.unwrap();
+        assert!(report.pulled);
+        assert_eq!(report.import.created, 1);
+        let imported = snippets::query_snippets(&pool_b, &Default::default())
+            .await
+            .unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].code, "fn greet() {}");
+        let id_b = imported[0].id;
+
+        // 两边同时修改：B 先推送，A 合并时产生冲突，选择远程版本
+        edit_code(&pool_b, id_b, "fn greet_b() {}", "2100-01-01T00:00:01").await;
+        sync_repo(&pool_b, &config_b).await.unwrap();
+        edit_code(&pool_a, id_a, "fn greet_a() {}", "2100-01-01T00:00:02").await;
+        let report = sync_repo(&pool_a, &config_a).await.unwrap();
+        assert_eq!(report.new_conflicts, 1);
+        assert_eq!(snippet_code(&pool_a, id_a).await, "fn greet_a() {}");
+
+        let conflicts = list_conflicts(&pool_a).await.unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].app_code.as_deref(), Some("fn greet_a() {}"));
+        assert_eq!(conflicts[0].file_code.as_deref(), Some("fn greet_b() {}"));
+
+        let sync_id = conflicts[0].sync_id.clone();
+        resolve_conflict(
+            &pool_a,
+            &config_a,
+            &sync_id,
+            GitConflictResolution::KeepRemote,
+        )
+        .await
+        .unwrap();
+        assert!(list_conflicts(&pool_a).await.unwrap().is_empty());
+        assert_eq!(snippet_code(&pool_a, id_a).await, "fn greet_b() {}");
+
+        // 再次同时修改：A 先推送，B 合并时产生冲突，选择本机版本并推送给 A
+        sync_repo(&pool_b, &config_b).await.unwrap();
+        edit_code(&pool_a, id_a, "fn greet_a2() {}", "2100-01-01T00:00:03").await;
+        sync_repo(&pool_a, &config_a).await.unwrap();
+        edit_code(&pool_b, id_b, "fn greet_b2() {}", "2100-01-01T00:00:04").await;
+        let report = sync_repo(&pool_b, &config_b).await.unwrap();
+        assert_eq!(report.new_conflicts, 1);
+
+        let conflicts = list_conflicts(&pool_b).await.unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].file_code.as_deref(), Some("fn greet_a2() {}"));
+        resolve_conflict(
+            &pool_b,
+            &config_b,
+            &sync_id,
+            GitConflictResolution::KeepLocal,
+        )
+        .await
+        .unwrap();
+        assert!(list_conflicts(&pool_b).await.unwrap().is_empty());
+        assert_eq!(snippet_code(&pool_b, id_b).await, "fn greet_b2() {}");
+
+        let report = sync_repo(&pool_a, &config_a).await.unwrap();
+        assert_eq!(report.new_conflicts, 0);
+        assert_eq!(snippet_code(&pool_a, id_a).await, "fn greet_b2() {}");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,530 @@
+use crate::db::get_setting;
+use sqlx::SqlitePool;
+use std::fmt::Display;
+use std::sync::RwLock;
+
+/// 界面语言，对应 user_settings 中的 language（zh-CN / en-US / system）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    ZhCn,
+    EnUs,
+}
+
+impl Locale {
+    /// 语言代码，与前端 i18n 的 locale 一致
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::EnUs => "en-US",
+        }
+    }
+
+    /// 系统语言：中文相关语言使用简体中文，其余使用英文（与前端 getSystemLocale 一致）
+    pub fn system() -> Self {
+        match sys_locale::get_locale() {
+            Some(locale) if locale.to_lowercase().contains("zh") => Locale::ZhCn,
+            _ => Locale::EnUs,
+        }
+    }
+
+    /// 解析设置值，未设置、system 或无法识别时使用系统语言
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value.map(str::trim) {
+            Some("zh-CN") => Locale::ZhCn,
+            Some("en-US") => Locale::EnUs,
+            _ => Self::system(),
+        }
+    }
+}
+
+/// 校验语言设置值
+pub fn is_valid_setting(value: &str) -> bool {
+    matches!(value, "system" | "zh-CN" | "en-US")
+}
+
+lazy_static::lazy_static! {
+    /// 当前界面语言（同步代码中生成菜单和错误信息时使用）
+    static ref CURRENT: RwLock<Locale> = RwLock::new(Locale::system());
+}
+
+/// 当前界面语言
+pub fn current() -> Locale {
+    *CURRENT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 切换当前界面语言
+pub fn set_current(locale: Locale) {
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = locale;
+}
+
+/// 从 user_settings 读取语言设置并设为当前语言
+pub async fn load(pool: &SqlitePool) -> Locale {
+    let locale = Locale::from_setting(get_setting(pool, "language").await.as_deref());
+    set_current(locale);
+    locale
+}
+
+// ============================================================================
+// 翻译
+// ============================================================================
+
+const ZH_CN: &[(&str, &str)] = &[
+    // 托盘菜单
+    ("tray.show", "显示窗口"),
+    ("tray.palette", "快速粘贴"),
+    ("tray.recentClipboard", "最近剪贴板"),
+    ("tray.pinnedSnippets", "置顶代码片段"),
+    ("tray.empty", "（无）"),
+    ("tray.locked", "（数据库已锁定）"),
+    ("tray.captureClipboard", "记录剪贴板"),
+    ("tray.pauseCapture", "暂停记录剪贴板"),
+    ("tray.pause15Minutes", "15 分钟"),
+    ("tray.pause1Hour", "1 小时"),
+    ("tray.pause8Hours", "8 小时"),
+    ("tray.resumeCapture", "恢复记录剪贴板"),
+    ("tray.mcpServer", "MCP 服务器"),
+    ("tray.quit", "退出"),
+    // 原生对话框
+    ("dialog.errorTitle", "SeekCode 出错了"),
+    ("dialog.showPaletteFailed", "打开快速粘贴面板失败：{error}"),
+    ("dialog.toggleCaptureFailed", "切换剪贴板记录失败：{error}"),
+    ("dialog.pauseCaptureFailed", "暂停记录剪贴板失败：{error}"),
+    ("dialog.resumeCaptureFailed", "恢复记录剪贴板失败：{error}"),
+    ("dialog.toggleMcpFailed", "切换 MCP 服务器失败：{error}"),
+    ("dialog.copyFailed", "复制到剪贴板失败：{error}"),
+    // 命令返回的信息
+    ("errors.nothingToWrite", "没有可写入剪贴板的内容"),
+    ("errors.clipboardItemNotFound", "剪贴板条目 {id} 不存在"),
+    ("errors.snippetNotFound", "代码片段 {id} 不存在"),
+    ("errors.invalidLanguage", "不支持的语言：{language}"),
+    (
+        "errors.invalidShortcut",
+        "快捷键 '{shortcut}' 无效：{error}",
+    ),
+    ("errors.registerShortcutFailed", "注册快捷键失败：{error}"),
+    ("errors.unregisterShortcutFailed", "注销快捷键失败：{error}"),
+    ("errors.showPaletteFailed", "打开快速粘贴面板失败：{error}"),
+    ("errors.hidePaletteFailed", "隐藏快速粘贴面板失败：{error}"),
+    (
+        "errors.databaseLocked",
+        "SeekCode 数据库已锁定，请先输入密码解锁",
+    ),
+    ("errors.incorrectPassphrase", "密码错误"),
+    ("errors.passphraseTooShort", "密码至少需要 {min} 个字符"),
+    ("errors.encryptionAlreadyEnabled", "已启用加密"),
+    ("errors.encryptionNotEnabled", "未启用加密"),
+    (
+        "errors.encryptionInitializing",
+        "正在读取加密设置，请稍后重试",
+    ),
+    ("errors.titleEmpty", "标题不能为空"),
+    (
+        "errors.titleTooLong",
+        "标题过长（{length} 个字符，最多 {max} 个）",
+    ),
+    (
+        "errors.unsupportedLanguage",
+        "不支持的语言 '{language}'，可选：{expected}",
+    ),
+    ("errors.codeEmpty", "代码不能为空"),
+    (
+        "errors.codeTooLarge",
+        "代码过大（{size} 字节，最多 {max} 字节）",
+    ),
+    (
+        "errors.tagTooLong",
+        "标签 '{tag}' 过长（最多 {max} 个字符）",
+    ),
+    (
+        "errors.tooManyTags",
+        "标签过多（{count} 个，最多 {max} 个）",
+    ),
+    (
+        "errors.invalidTimeOfDay",
+        "时间 '{value}' 无效，应为 HH:MM 格式",
+    ),
+    (
+        "errors.invalidPattern",
+        "正则表达式 '{pattern}' 无效：{error}",
+    ),
+    ("errors.minLengthExceedsMax", "最小长度不能大于最大长度"),
+    (
+        "errors.quietHoursIncomplete",
+        "免打扰开始和结束时间必须同时设置",
+    ),
+    ("errors.pauseTooShort", "暂停时长至少为 1 分钟"),
+    ("errors.negativeRetention", "保留限制不能为负数"),
+    (
+        "errors.imageToSnippet",
+        "图片类剪贴板条目无法转换为代码片段",
+    ),
+    (
+        "errors.backupPathIsDatabase",
+        "备份路径不能与数据库路径相同",
+    ),
+    ("errors.keepAtLeastOneBackup", "至少需要保留一个备份"),
+    ("errors.backupIntervalTooShort", "备份间隔至少为 1 小时"),
+    (
+        "errors.notSeekCodeDatabase",
+        "不是 SeekCode 数据库：未找到迁移记录",
+    ),
+    ("errors.invalidBackup", "备份不是有效的数据库：{error}"),
+    (
+        "errors.backupIntegrityFailed",
+        "备份未通过完整性检查：{result}",
+    ),
+    (
+        "errors.backupTooNew",
+        "备份由更新版本的 SeekCode 创建（结构版本 {version} > {latest}）",
+    ),
+    ("errors.backupNotFound", "备份文件不存在：{path}"),
+    ("errors.databaseNotFound", "数据库文件不存在：{path}"),
+    ("errors.invalidSnippet", "代码片段无效：{error}"),
+    (
+        "errors.duplicateSnippet",
+        "已存在相同内容的代码片段（ID {id}）",
+    ),
+    ("errors.databaseError", "数据库错误：{error}"),
+    ("errors.codeContainsSecrets", "代码中包含密钥（{kinds}）"),
+    ("errors.collectionNameEmpty", "集合名称不能为空"),
+    ("errors.collectionNameHasSlash", "集合名称不能包含 '/'"),
+    (
+        "errors.collectionNameTooLong",
+        "集合名称过长（最多 {max} 个字符）",
+    ),
+    ("errors.collectionNotFound", "集合 {id} 不存在"),
+    (
+        "errors.collectionNameExists",
+        "此处已存在名为 '{name}' 的集合",
+    ),
+    (
+        "errors.collectionMoveIntoSelf",
+        "不能把集合移动到自身或其子集合中",
+    ),
+    ("errors.syncFolderNotConfigured", "尚未配置同步文件夹"),
+    ("errors.gitSyncNotConfigured", "尚未配置 Git 同步仓库"),
+    ("errors.repositoryPathEmpty", "仓库路径不能为空"),
+    ("errors.encryptedValueCorrupted", "加密数据已损坏"),
+    ("errors.decryptFailed", "解密失败（密钥错误或数据已损坏）"),
+    (
+        "errors.decryptedNotUtf8",
+        "解密后的内容不是有效的 UTF-8 文本",
+    ),
+    ("errors.keyringUnsupported", "当前平台不支持系统钥匙串"),
+    ("errors.keyringSaveFailed", "无法将密钥保存到系统钥匙串"),
+    ("errors.encryptionSaltMissing", "缺少加密盐值"),
+    ("errors.encryptionVerifierMissing", "缺少加密校验值"),
+    ("errors.keyDerivationFailed", "密钥派生失败：{error}"),
+    (
+        "errors.invalidDate",
+        "日期 '{value}' 无效，应为 YYYY-MM-DD 或 YYYY-MM-DDTHH:MM:SS 格式",
+    ),
+    ("errors.invalidDateRange", "开始时间必须早于结束时间"),
+    (
+        "errors.deleteOldClipboardFailed",
+        "删除过期的剪贴板条目失败：{error}",
+    ),
+    ("errors.trimClipboardFailed", "清理剪贴板历史失败：{error}"),
+    ("maintenance.noProblems", "未发现问题"),
+    (
+        "maintenance.problemsFound",
+        "发现 {problems} 个完整性问题和 {foreignKeys} 个外键问题",
+    ),
+    ("mcp.started", "MCP服务器启动成功，地址: {address}"),
+    ("mcp.startFailed", "启动MCP服务器失败: {error}"),
+    ("mcp.stopped", "MCP服务器停止成功"),
+    ("mcp.stopFailed", "停止MCP服务器失败: {error}"),
+];
+
+const EN_US: &[(&str, &str)] = &[
+    // Tray menu
+    ("tray.show", "Show Window"),
+    ("tray.palette", "Quick Paste"),
+    ("tray.recentClipboard", "Recent Clipboard"),
+    ("tray.pinnedSnippets", "Pinned Snippets"),
+    ("tray.empty", "(Empty)"),
+    ("tray.locked", "(Database Locked)"),
+    ("tray.captureClipboard", "Record Clipboard"),
+    ("tray.pauseCapture", "Pause Clipboard Recording"),
+    ("tray.pause15Minutes", "15 Minutes"),
+    ("tray.pause1Hour", "1 Hour"),
+    ("tray.pause8Hours", "8 Hours"),
+    ("tray.resumeCapture", "Resume Clipboard Recording"),
+    ("tray.mcpServer", "MCP Server"),
+    ("tray.quit", "Quit"),
+    // Native dialogs
+    ("dialog.errorTitle", "SeekCode Error"),
+    (
+        "dialog.showPaletteFailed",
+        "Failed to open the quick paste palette: {error}",
+    ),
+    (
+        "dialog.toggleCaptureFailed",
+        "Failed to toggle clipboard recording: {error}",
+    ),
+    (
+        "dialog.pauseCaptureFailed",
+        "Failed to pause clipboard recording: {error}",
+    ),
+    (
+        "dialog.resumeCaptureFailed",
+        "Failed to resume clipboard recording: {error}",
+    ),
+    (
+        "dialog.toggleMcpFailed",
+        "Failed to toggle the MCP server: {error}",
+    ),
+    (
+        "dialog.copyFailed",
+        "Failed to copy to the clipboard: {error}",
+    ),
+    // Command messages
+    ("errors.nothingToWrite", "Nothing to write to the clipboard"),
+    (
+        "errors.clipboardItemNotFound",
+        "Clipboard item {id} not found",
+    ),
+    ("errors.snippetNotFound", "Snippet {id} not found"),
+    ("errors.invalidLanguage", "Unsupported language: {language}"),
+    (
+        "errors.invalidShortcut",
+        "Invalid shortcut '{shortcut}': {error}",
+    ),
+    (
+        "errors.registerShortcutFailed",
+        "Failed to register shortcut: {error}",
+    ),
+    (
+        "errors.unregisterShortcutFailed",
+        "Failed to unregister shortcut: {error}",
+    ),
+    (
+        "errors.showPaletteFailed",
+        "Failed to show palette: {error}",
+    ),
+    (
+        "errors.hidePaletteFailed",
+        "Failed to hide palette: {error}",
+    ),
+    (
+        "errors.databaseLocked",
+        "The SeekCode database is locked. Unlock it with your passphrase first",
+    ),
+    ("errors.incorrectPassphrase", "Incorrect passphrase"),
+    (
+        "errors.passphraseTooShort",
+        "Passphrase must be at least {min} characters",
+    ),
+    (
+        "errors.encryptionAlreadyEnabled",
+        "Encryption is already enabled",
+    ),
+    ("errors.encryptionNotEnabled", "Encryption is not enabled"),
+    (
+        "errors.encryptionInitializing",
+        "Encryption settings are still loading, please try again",
+    ),
+    ("errors.titleEmpty", "Title must not be empty"),
+    (
+        "errors.titleTooLong",
+        "Title is too long ({length} characters, maximum {max})",
+    ),
+    (
+        "errors.unsupportedLanguage",
+        "Unsupported language '{language}', expected one of: {expected}",
+    ),
+    ("errors.codeEmpty", "Code must not be empty"),
+    (
+        "errors.codeTooLarge",
+        "Code is too large ({size} bytes, maximum {max} bytes)",
+    ),
+    (
+        "errors.tagTooLong",
+        "Tag '{tag}' is too long (maximum {max} characters)",
+    ),
+    (
+        "errors.tooManyTags",
+        "Too many tags ({count}, maximum {max})",
+    ),
+    (
+        "errors.invalidTimeOfDay",
+        "Invalid time of day '{value}', expected HH:MM",
+    ),
+    (
+        "errors.invalidPattern",
+        "Invalid pattern '{pattern}': {error}",
+    ),
+    (
+        "errors.minLengthExceedsMax",
+        "min_length must not be greater than max_length",
+    ),
+    (
+        "errors.quietHoursIncomplete",
+        "quiet_start and quiet_end must be set together",
+    ),
+    (
+        "errors.pauseTooShort",
+        "Pause duration must be at least 1 minute",
+    ),
+    (
+        "errors.negativeRetention",
+        "Retention limits must not be negative",
+    ),
+    (
+        "errors.imageToSnippet",
+        "Image clipboard items cannot be converted to snippets",
+    ),
+    (
+        "errors.backupPathIsDatabase",
+        "Backup path must differ from the database path",
+    ),
+    (
+        "errors.keepAtLeastOneBackup",
+        "At least one backup must be kept",
+    ),
+    (
+        "errors.backupIntervalTooShort",
+        "Backup interval must be at least 1 hour",
+    ),
+    (
+        "errors.notSeekCodeDatabase",
+        "Not a SeekCode database: no migration history found",
+    ),
+    (
+        "errors.invalidBackup",
+        "Backup is not a valid database: {error}",
+    ),
+    (
+        "errors.backupIntegrityFailed",
+        "Backup failed the integrity check: {result}",
+    ),
+    (
+        "errors.backupTooNew",
+        "Backup was created by a newer version of SeekCode (schema {version} > {latest})",
+    ),
+    ("errors.backupNotFound", "Backup file not found: {path}"),
+    ("errors.databaseNotFound", "Database file not found: {path}"),
+    ("errors.invalidSnippet", "Invalid snippet: {error}"),
+    (
+        "errors.duplicateSnippet",
+        "A snippet with the same content already exists (id {id})",
+    ),
+    ("errors.databaseError", "Database error: {error}"),
+    (
+        "errors.codeContainsSecrets",
+        "code contains secrets ({kinds})",
+    ),
+    (
+        "errors.collectionNameEmpty",
+        "Collection name must not be empty",
+    ),
+    (
+        "errors.collectionNameHasSlash",
+        "Collection name must not contain '/'",
+    ),
+    (
+        "errors.collectionNameTooLong",
+        "Collection name is too long (maximum {max} characters)",
+    ),
+    ("errors.collectionNotFound", "Collection {id} not found"),
+    (
+        "errors.collectionNameExists",
+        "A collection named '{name}' already exists here",
+    ),
+    (
+        "errors.collectionMoveIntoSelf",
+        "Cannot move a collection into itself or one of its children",
+    ),
+    (
+        "errors.syncFolderNotConfigured",
+        "Sync folder is not configured",
+    ),
+    (
+        "errors.gitSyncNotConfigured",
+        "Git sync repository is not configured",
+    ),
+    (
+        "errors.repositoryPathEmpty",
+        "Repository path must not be empty",
+    ),
+    (
+        "errors.encryptedValueCorrupted",
+        "Encrypted value is corrupted",
+    ),
+    (
+        "errors.decryptFailed",
+        "Failed to decrypt value (wrong key or corrupted data)",
+    ),
+    (
+        "errors.decryptedNotUtf8",
+        "Decrypted value is not valid UTF-8",
+    ),
+    (
+        "errors.keyringUnsupported",
+        "The OS keyring is not supported on this platform",
+    ),
+    (
+        "errors.keyringSaveFailed",
+        "Failed to save the key to the OS keyring",
+    ),
+    ("errors.encryptionSaltMissing", "Encryption salt is missing"),
+    (
+        "errors.encryptionVerifierMissing",
+        "Encryption verifier is missing",
+    ),
+    (
+        "errors.keyDerivationFailed",
+        "Key derivation failed: {error}",
+    ),
+    (
+        "errors.invalidDate",
+        "Invalid date '{value}', expected YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS",
+    ),
+    (
+        "errors.invalidDateRange",
+        "'from' must be earlier than 'to'",
+    ),
+    (
+        "errors.deleteOldClipboardFailed",
+        "Failed to delete old clipboard items: {error}",
+    ),
+    (
+        "errors.trimClipboardFailed",
+        "Failed to trim clipboard history: {error}",
+    ),
+    ("maintenance.noProblems", "No problems found"),
+    (
+        "maintenance.problemsFound",
+        "Found {problems} integrity problem(s) and {foreignKeys} foreign key problem(s)",
+    ),
+    ("mcp.started", "MCP server started at {address}"),
+    ("mcp.startFailed", "Failed to start MCP server: {error}"),
+    ("mcp.stopped", "MCP server stopped"),
+    ("mcp.stopFailed", "Failed to stop MCP server: {error}"),
+];
+
+fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    let messages = match locale {
+        Locale::ZhCn => ZH_CN,
+        Locale::EnUs => EN_US,
+    };
+    messages
+        .iter()
+        .find(|(message_key, _)| *message_key == key)
+        .map(|(_, message)| *message)
+}
+
+/// 按当前语言翻译，缺少翻译时回退到英文，仍找不到时返回 key 本身
+pub fn t(key: &str) -> String {
+    lookup(current(), key)
+        .or_else(|| lookup(Locale::EnUs, key))
+        .unwrap_or(key)
+        .to_string()
+}
+
+/// 翻译并替换 {name} 形式的参数
+pub fn t_with(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(t(key), |message, (name, value)| {
+        message.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
@@ -0,0 +1,796 @@
+use crate::archive::{Archive, ArchiveSnippet, ARCHIVE_FORMAT, ARCHIVE_VERSION};
+use crate::db::now_timestamp;
+use crate::validation::{language_for_extension, normalize_language_or_text};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 目录导入时单个文件的最大字节数，超出的文件直接跳过
+const MAX_DIRECTORY_FILE_SIZE: u64 = 1024 * 1024;
+
+/// 外部数据来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+    /// VS Code .code-snippets / 语言代码片段 JSON
+    Vscode,
+    /// JetBrains 实时模板 XML
+    Jetbrains,
+    /// 源代码文件目录
+    Directory,
+    /// 导出的 GitHub Gist JSON（单个 gist 或 gist 数组）
+    Gist,
+}
+
+/// 读取外部来源并转换为归档，之后与普通导入走同一流程
+pub fn read_source(source: ImportSource, path: &str) -> Result<Archive, String> {
+    let path = Path::new(path);
+    let snippets = match source {
+        ImportSource::Vscode => parse_vscode(&read_file(path)?, path)?,
+        ImportSource::Jetbrains => parse_jetbrains(&read_file(path)?)?,
+        ImportSource::Directory => read_directory(path)?,
+        ImportSource::Gist => parse_gist(&read_file(path)?)?,
+    };
+
+    Ok(Archive {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        exported_at: now_timestamp(),
+        app_version: String::new(),
+        snippets,
+        settings: Vec::new(),
+        clipboard: None,
+    })
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+// ============================================================================
+// VS Code
+// ============================================================================
+
+/// VS Code 代码片段定义
+#[derive(Debug, Deserialize)]
+struct VscodeSnippet {
+    #[serde(default)]
+    prefix: Option<StringOrList>,
+    #[serde(default)]
+    body: Option<StringOrList>,
+    #[serde(default)]
+    scope: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StringOrList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl StringOrList {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            StringOrList::One(value) => vec![value],
+            StringOrList::Many(values) => values,
+        }
+    }
+}
+
+/// 解析 VS Code 代码片段文件（支持 JSONC 注释和尾随逗号）
+pub fn parse_vscode(contents: &str, path: &Path) -> Result<Vec<ArchiveSnippet>, String> {
+    let json = strip_jsonc(contents);
+    let definitions: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid VS Code snippets file: {}", e))?;
+
+    // 语言专属文件（如 javascript.json）的文件名即为语言
+    let file_language = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|_| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .map(normalize_language_or_text);
+
+    let mut snippets = Vec::new();
+    for (name, value) in definitions {
+        let Ok(definition) = serde_json::from_value::<VscodeSnippet>(value) else {
+            continue;
+        };
+        let Some(body) = definition.body else {
+            continue;
+        };
+
+        let language = definition
+            .scope
+            .as_deref()
+            .and_then(|scope| scope.split(',').next())
+            .map(normalize_language_or_text)
+            .or_else(|| file_language.clone())
+            .unwrap_or_else(|| "text".to_string());
+
+        let mut tags = vec!["vscode".to_string()];
+        if let Some(prefix) = definition.prefix {
+            tags.extend(prefix.into_vec());
+        }
+
+        snippets.push(ArchiveSnippet {
+            title: name,
+            language,
+            code: from_vscode_body(&body.into_vec().join("\n")),
+            tags,
+            created_at: None,
+            updated_at: None,
+        });
+    }
+    Ok(snippets)
+}
+
+/// 去除 JSONC 中的注释和尾随逗号
+fn strip_jsonc(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => {
+                    if let Some(next) = chars.next() {
+                        output.push(next);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                output.push(c);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = '\0';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            '}' | ']' => {
+                // 去除尾随逗号
+                let trimmed_len = output.trim_end().len();
+                if output[..trimmed_len].ends_with(',') {
+                    output.truncate(trimmed_len - 1);
+                }
+                output.push(c);
+            }
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+/// 作为占位符名称使用的文本：字母或下划线开头，只包含字母、数字和下划线
+fn is_placeholder_name(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 生成 SeekCode 占位符（语法见 exporters.rs）
+fn placeholder(name: &str, default: Option<&str>) -> String {
+    match default {
+        Some(default) if !default.is_empty() && default != name => {
+            format!("{{{{{}:{}}}}}", name, default)
+        }
+        _ => format!("{{{{{}}}}}", name),
+    }
+}
+
+/// 读取从 start 开始的标识符或数字
+fn take_while(chars: &[char], start: usize, pred: impl Fn(char) -> bool) -> usize {
+    let mut end = start;
+    while end < chars.len() && pred(chars[end]) {
+        end += 1;
+    }
+    end
+}
+
+/// 把 VS Code 代码片段语法转换为 SeekCode 占位符：
+/// $0 → {{cursor}}，${1:name} → {{name}}，${1:8080} → {{arg1:8080}}，
+/// ${1|a,b|} → {{arg1:a}}，$VAR / ${VAR:default} → {{VAR}} / {{VAR:default}}，\$ → $
+pub fn from_vscode_body(body: &str) -> String {
+    let mut names: Vec<(String, String)> = Vec::new();
+    convert_vscode(&body.chars().collect::<Vec<_>>(), &mut names)
+}
+
+fn convert_vscode(chars: &[char], names: &mut Vec<(String, String)>) -> String {
+    let mut output = String::with_capacity(chars.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' && matches!(chars.get(i + 1), Some('$' | '}' | '\\')) {
+            output.push(chars[i + 1]);
+            i += 2;
+            continue;
+        }
+        if c != '$' {
+            output.push(c);
+            i += 1;
+            continue;
+        }
+
+        // $1 / $VAR
+        let start = i + 1;
+        let end = take_while(chars, start, |c| c.is_ascii_alphanumeric() || c == '_');
+        if end > start {
+            let key: String = chars[start..end].iter().collect();
+            output.push_str(&vscode_placeholder(&key, None, names));
+            i = end;
+            continue;
+        }
+
+        // ${1}、${1:default}、${1|a,b|}、${VAR:default}
+        if chars.get(start) == Some(&'{') {
+            let key_end = take_while(chars, start + 1, |c| c.is_ascii_alphanumeric() || c == '_');
+            let key: String = chars[start + 1..key_end].iter().collect();
+            let close = matching_brace(chars, start);
+            if let (false, Some(close)) = (key.is_empty(), close) {
+                let default = match chars.get(key_end) {
+                    Some(':') => Some(vscode_text(&chars[key_end + 1..close], names)),
+                    Some('|') => Some(
+                        chars[key_end + 1..close]
+                            .iter()
+                            .collect::<String>()
+                            .trim_end_matches('|')
+                            .split(',')
+                            .next()
+                            .unwrap_or_default()
+                            .to_string(),
+                    ),
+                    _ => None,
+                };
+                output.push_str(&vscode_placeholder(&key, default.as_deref(), names));
+                i = close + 1;
+                continue;
+            }
+        }
+
+        output.push(c);
+        i += 1;
+    }
+    output
+}
+
+/// 与 open 处的 { 配对的 }（跳过转义字符）
+fn matching_brace(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = open;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// 默认值中嵌套的 tab-stop 只保留其文字（SeekCode 占位符不支持嵌套）
+fn vscode_text(chars: &[char], names: &mut Vec<(String, String)>) -> String {
+    let converted = convert_vscode(chars, names);
+    let mut text = String::with_capacity(converted.len());
+    let mut rest = converted.as_str();
+    while let Some(start) = rest.find("{{") {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let inner = &rest[start + 2..start + end];
+        text.push_str(inner.split_once(':').map_or(inner, |(_, default)| default));
+        rest = &rest[start + end + 2..];
+    }
+    text.push_str(rest);
+    text.replace('}', "")
+}
+
+fn vscode_placeholder(
+    key: &str,
+    default: Option<&str>,
+    names: &mut Vec<(String, String)>,
+) -> String {
+    if !key.chars().all(|c| c.is_ascii_digit()) {
+        // 编辑器变量（如 TM_SELECTED_TEXT）作为同名占位符保留
+        return placeholder(key, default);
+    }
+    if key.parse::<u32>() == Ok(0) {
+        return "{{cursor}}".to_string();
+    }
+    // 同一编号再次出现时引用同一个占位符
+    if let Some((_, name)) = names.iter().find(|(index, _)| index == key) {
+        return placeholder(name, None);
+    }
+    let name = match default {
+        Some(default) if is_placeholder_name(default) => default.to_string(),
+        _ => format!("arg{}", key),
+    };
+    names.push((key.to_string(), name.clone()));
+    placeholder(&name, default)
+}
+
+// ============================================================================
+// JetBrains
+// ============================================================================
+
+/// JetBrains 模板上下文到语言的映射
+const JETBRAINS_CONTEXTS: &[(&str, &str)] = &[
+    ("JAVA", "java"),
+    ("PYTHON", "python"),
+    ("Python", "python"),
+    ("JAVA_SCRIPT", "javascript"),
+    ("JAVASCRIPT", "javascript"),
+    ("JS", "javascript"),
+    ("TYPE_SCRIPT", "typescript"),
+    ("TypeScript", "typescript"),
+    ("GO", "go"),
+    ("RUST", "rust"),
+    ("SQL", "sql"),
+    ("HTML", "html"),
+    ("CSS", "css"),
+    ("PHP", "php"),
+    ("SHELL_SCRIPT", "shell"),
+    ("Bash", "shell"),
+    ("OC", "cpp"),
+    ("CPP", "cpp"),
+    ("XML", "xml"),
+    ("JSON", "json"),
+    ("MARKDOWN", "markdown"),
+    ("YAML", "yaml"),
+    ("VUE", "vue"),
+];
+
+fn jetbrains_language(context: &str) -> Option<&'static str> {
+    // 取最长的匹配前缀，避免 JAVA 误匹配 JAVA_SCRIPT
+    JETBRAINS_CONTEXTS
+        .iter()
+        .filter(|(prefix, _)| context == *prefix || context.starts_with(&format!("{}_", prefix)))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, language)| *language)
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == name.as_bytes())
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|value| value.into_owned())
+}
+
+/// 还原字符串字面量中的 \" 和 \\ 转义
+fn unescape_string_literal(literal: &str) -> String {
+    let mut text = String::with_capacity(literal.len());
+    let mut chars = literal.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some(next @ ('"' | '\\'))) => {
+                text.push(next);
+                chars.next();
+            }
+            _ => text.push(c),
+        }
+    }
+    text
+}
+
+/// 把 JetBrains 模板语法转换为 SeekCode 占位符：$END$ → {{cursor}}，$NAME$ → {{name}}
+/// （变量默认值为字符串字面量时保留为 {{name:default}}），$$ → $
+pub fn from_jetbrains_template(value: &str, variables: &[(String, String)]) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let mut output = String::with_capacity(value.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] != '$' {
+            output.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        if chars.get(i + 1) == Some(&'$') {
+            output.push('$');
+            i += 2;
+            continue;
+        }
+        let end = take_while(&chars, i + 1, |c| c.is_ascii_alphanumeric() || c == '_');
+        if end == i + 1 || chars.get(end) != Some(&'$') {
+            output.push('$');
+            i += 1;
+            continue;
+        }
+
+        let variable: String = chars[i + 1..end].iter().collect();
+        if variable == "END" {
+            output.push_str("{{cursor}}");
+        } else {
+            // 默认值是表达式，只保留 "text" 形式的字符串字面量
+            let default = variables
+                .iter()
+                .find(|(name, _)| *name == variable)
+                .and_then(|(_, default)| {
+                    default
+                        .strip_prefix('"')
+                        .and_then(|default| default.strip_suffix('"'))
+                })
+                .map(unescape_string_literal);
+            output.push_str(&placeholder(&variable.to_lowercase(), default.as_deref()));
+        }
+        i = end + 1;
+    }
+    output
+}
+
+/// 解析 JetBrains 实时模板 XML（templateSet）
+pub fn parse_jetbrains(contents: &str) -> Result<Vec<ArchiveSnippet>, String> {
+    let mut reader = Reader::from_str(contents);
+    reader.config_mut().trim_text(true);
+
+    let mut snippets = Vec::new();
+    let mut group: Option<String> = None;
+    let mut current: Option<ArchiveSnippet> = None;
+    let mut variables: Vec<(String, String)> = Vec::new();
+    let mut in_context = false;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Invalid JetBrains template XML: {}", e))?;
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let is_empty = matches!(event, Event::Empty(_));
+                match e.name().as_ref() {
+                    b"templateSet" => group = attribute(e, "group"),
+                    b"template" => {
+                        let name = attribute(e, "name").unwrap_or_default();
+                        let description = attribute(e, "description").unwrap_or_default();
+                        let mut tags = vec!["jetbrains".to_string(), name.clone()];
+                        if let Some(group) = &group {
+                            tags.push(group.clone());
+                        }
+                        let snippet = ArchiveSnippet {
+                            title: if description.trim().is_empty() {
+                                name
+                            } else {
+                                description
+                            },
+                            language: "text".to_string(),
+                            code: attribute(e, "value").unwrap_or_default(),
+                            tags,
+                            created_at: None,
+                            updated_at: None,
+                        };
+                        variables.clear();
+                        if is_empty {
+                            snippets.push(ArchiveSnippet {
+                                code: from_jetbrains_template(&snippet.code, &variables),
+                                ..snippet
+                            });
+                        } else {
+                            current = Some(snippet);
+                        }
+                    }
+                    b"variable" if current.is_some() => {
+                        if let Some(name) = attribute(e, "name") {
+                            variables
+                                .push((name, attribute(e, "defaultValue").unwrap_or_default()));
+                        }
+                    }
+                    b"context" => in_context = !is_empty,
+                    b"option" if in_context => {
+                        let enabled = attribute(e, "value").as_deref() == Some("true");
+                        let language = attribute(e, "name").as_deref().and_then(jetbrains_language);
+                        if let (true, Some(language), Some(snippet)) =
+                            (enabled, language, current.as_mut())
+                        {
+                            if snippet.language == "text" {
+                                snippet.language = language.to_string();
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(ref e) => match e.name().as_ref() {
+                b"context" => in_context = false,
+                b"template" => {
+                    if let Some(snippet) = current.take() {
+                        snippets.push(ArchiveSnippet {
+                            code: from_jetbrains_template(&snippet.code, &variables),
+                            ..snippet
+                        });
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(snippets)
+}
+
+// ============================================================================
+// 源代码目录
+// ============================================================================
+
+/// 读取目录中的源代码文件：语言由扩展名推断，标题取文件名，子目录名作为标签
+pub fn read_directory(root: &Path) -> Result<Vec<ArchiveSnippet>, String> {
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+
+    let mut snippets = Vec::new();
+    let walker = walkdir::WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+        });
+
+    for entry in walker.flatten() {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let too_large = entry
+            .metadata()
+            .map(|meta| meta.len() > MAX_DIRECTORY_FILE_SIZE)
+            .unwrap_or(true);
+        if too_large {
+            continue;
+        }
+        // 非 UTF-8 文件（通常是二进制文件）直接跳过
+        let Ok(code) = std::fs::read_to_string(path) else {
+            continue;
+        };
+
+        let language = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(language_for_extension)
+            .unwrap_or("text");
+        let title = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let tags = path
+            .parent()
+            .and_then(|parent| parent.strip_prefix(root).ok())
+            .map(|relative| {
+                relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        snippets.push(ArchiveSnippet {
+            title,
+            language: language.to_string(),
+            code,
+            tags,
+            created_at: None,
+            updated_at: None,
+        });
+    }
+    Ok(snippets)
+}
+
+// ============================================================================
+// GitHub Gist
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct Gist {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    files: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    updated_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GistFile {
+    #[serde(default)]
+    filename: Option<String>,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// 将 GitHub 返回的 RFC 3339 时间转换为本地时间格式
+fn gist_timestamp(value: Option<String>) -> Option<String> {
+    value
+        .and_then(|v| chrono::DateTime::parse_from_rfc3339(&v).ok())
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%Y-%m-%dT%H:%M:%S")
+                .to_string()
+        })
+}
+
+/// 解析导出的 Gist JSON，每个文件生成一个代码片段
+pub fn parse_gist(contents: &str) -> Result<Vec<ArchiveSnippet>, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(contents).map_err(|e| format!("Invalid gist JSON: {}", e))?;
+    let gists: Vec<Gist> = if value.is_array() {
+        serde_json::from_value(value)
+    } else {
+        serde_json::from_value(value).map(|gist| vec![gist])
+    }
+    .map_err(|e| format!("Invalid gist JSON: {}", e))?;
+
+    let mut snippets = Vec::new();
+    for gist in gists {
+        let description = gist.description.unwrap_or_default();
+        let single_file = gist.files.len() == 1;
+        let created_at = gist_timestamp(gist.created_at);
+        let updated_at = gist_timestamp(gist.updated_at);
+
+        for (key, value) in gist.files {
+            let Ok(file) = serde_json::from_value::<GistFile>(value) else {
+                continue;
+            };
+            let Some(code) = file.content else {
+                continue;
+            };
+            let filename = file.filename.unwrap_or(key);
+            let language = file
+                .language
+                .as_deref()
+                .map(normalize_language_or_text)
+                .filter(|language| language != "text")
+                .or_else(|| {
+                    Path::new(&filename)
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .and_then(language_for_extension)
+                        .map(str::to_string)
+                })
+                .unwrap_or_else(|| "text".to_string());
+            let title = if description.trim().is_empty() {
+                filename.clone()
+            } else if single_file {
+                description.clone()
+            } else {
+                format!("{} - {}", description, filename)
+            };
+
+            snippets.push(ArchiveSnippet {
+                title,
+                language,
+                code,
+                tags: vec!["gist".to_string()],
+                created_at: created_at.clone(),
+                updated_at: updated_at.clone(),
+            });
+        }
+    }
+    Ok(snippets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_vscode_tab_stops() {
+        assert_eq!(
+            from_vscode_body("fn ${1:main}() {\n\t$0\n}"),
+            "fn {{main}}() {\n\t{{cursor}}\n}"
+        );
+        assert_eq!(from_vscode_body("${1:8080} $1"), "{{arg1:8080}} {{arg1}}");
+        assert_eq!(from_vscode_body("${1|1,2|}"), "{{arg1:1}}");
+        assert_eq!(
+            from_vscode_body("$TM_FILENAME ${USER:me}"),
+            "{{TM_FILENAME}} {{USER:me}}"
+        );
+        assert_eq!(
+            from_vscode_body("${1:outer ${2:inner}}"),
+            "{{arg1:outer inner}}"
+        );
+    }
+
+    #[test]
+    fn unescapes_vscode_text() {
+        assert_eq!(from_vscode_body(r"\$HOME C:\\dir \}"), r"$HOME C:\dir }");
+        // 其他反斜杠按原样保留
+        assert_eq!(from_vscode_body(r"\d+\n"), r"\d+\n");
+        assert_eq!(from_vscode_body("price: $"), "price: $");
+    }
+
+    #[test]
+    fn parses_vscode_jsonc() {
+        let contents = r#"{
+            // 注释
+            "Print": {
+                "prefix": ["log", "print"],
+                "body": ["console.log(\"$1\");", "$0"], /* 块注释 */
+                "scope": "javascript,typescript",
+            },
+            "No body": { "prefix": "x" },
+        }"#;
+        let snippets = parse_vscode(contents, Path::new("snippets.code-snippets")).unwrap();
+        assert_eq!(snippets.len(), 1);
+        assert_eq!(snippets[0].title, "Print");
+        assert_eq!(snippets[0].language, "javascript");
+        assert_eq!(snippets[0].code, "console.log(\"{{arg1}}\");\n{{cursor}}");
+        assert_eq!(snippets[0].tags, vec!["vscode", "log", "print"]);
+    }
+
+    #[test]
+    fn vscode_language_falls_back_to_file_name() {
+        let contents = r#"{ "Main": { "body": "fn main() {}" } }"#;
+        let snippets = parse_vscode(contents, Path::new("rust.json")).unwrap();
+        assert_eq!(snippets[0].language, "rust");
+    }
+
+    #[test]
+    fn converts_jetbrains_templates() {
+        let variables = vec![
+            ("NAME".to_string(), "\"say \\\"hi\\\" \\\\o/\"".to_string()),
+            ("TYPE".to_string(), "className()".to_string()),
+        ];
+        assert_eq!(
+            from_jetbrains_template("$TYPE$ $NAME$ = $$5;$END$", &variables),
+            r#"{{type}} {{name:say "hi" \o/}} = $5;{{cursor}}"#
+        );
+        assert_eq!(from_jetbrains_template("cost $ 5", &[]), "cost $ 5");
+    }
+
+    #[test]
+    fn parses_jetbrains_xml() {
+        let contents = r#"<templateSet group="Custom">
+          <template name="sout" value="System.out.println($EXPR$);$END$" description="Print">
+            <variable name="EXPR" expression="" defaultValue="&quot;text&quot;" alwaysStopAt="true" />
+            <context><option name="JAVA_CODE" value="true" /></context>
+          </template>
+          <template name="empty" value="x" description="" />
+        </templateSet>"#;
+        let snippets = parse_jetbrains(contents).unwrap();
+        assert_eq!(snippets.len(), 2);
+        assert_eq!(snippets[0].title, "Print");
+        assert_eq!(snippets[0].language, "java");
+        assert_eq!(
+            snippets[0].code,
+            "System.out.println({{expr:text}});{{cursor}}"
+        );
+        assert_eq!(snippets[0].tags, vec!["jetbrains", "sout", "Custom"]);
+        assert_eq!(snippets[1].title, "empty");
+        assert_eq!(snippets[1].language, "text");
+    }
+}
@@ -0,0 +1,336 @@
+use crate::validation::{language_for_extension, normalize_language};
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+
+/// 表示"自动检测"的语言值
+pub const AUTO_LANGUAGE: &str = "auto";
+
+/// 低于该得分时视为纯文本
+const MIN_SCORE: f64 = 3.0;
+/// 达到该得分时认为特征充分
+const STRONG_SCORE: f64 = 8.0;
+/// 只检测开头部分，避免超长内容拖慢剪贴板记录
+const MAX_SCAN_BYTES: usize = 64 * 1024;
+
+const HINT_CONFIDENCE: f64 = 0.9;
+const SHEBANG_CONFIDENCE: f64 = 0.95;
+const JSON_CONFIDENCE: f64 = 0.99;
+
+/// 语言检测结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LanguageDetection {
+    pub language: String,
+    /// 置信度（0 - 1）
+    pub confidence: f64,
+}
+
+impl LanguageDetection {
+    fn new(language: &str, confidence: f64) -> Self {
+        Self {
+            language: language.to_string(),
+            confidence: (confidence.clamp(0.0, 1.0) * 100.0).round() / 100.0,
+        }
+    }
+}
+
+/// 语言为空或为 "auto" 时需要自动检测
+pub fn is_auto(language: &str) -> bool {
+    let language = language.trim();
+    language.is_empty() || language.eq_ignore_ascii_case(AUTO_LANGUAGE)
+}
+
+// ============================================================================
+// 规则
+// ============================================================================
+
+/// 一条语法特征：匹配时为对应语言加 weight 分（每条规则只计一次）
+struct Marker {
+    language: &'static str,
+    pattern: Regex,
+    weight: f64,
+}
+
+fn marker(language: &'static str, pattern: &str, weight: f64) -> Marker {
+    Marker {
+        language,
+        pattern: Regex::new(&format!("(?m){}", pattern)).expect("language marker is valid"),
+        weight,
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref MARKERS: Vec<Marker> = vec![
+        // Rust
+        marker("rust", r"\bfn\s+\w+\s*[(<]", 3.0),
+        marker("rust", r"\blet\s+mut\b", 3.0),
+        marker("rust", r"\bpub(\(crate\))?\s+(fn|struct|enum|mod|trait|use)\b", 3.0),
+        marker("rust", r"&(mut\s+)?self\b", 3.0),
+        marker("rust", r"#!?\[(derive|cfg|allow|test|tokio::main)\b", 3.0),
+        marker("rust", r"^\s*use\s+(std|crate|super|self)::", 3.0),
+        marker("rust", r"^\s*impl\b", 2.0),
+        marker("rust", r"\b(println|format|vec|assert_eq|panic)!\(", 2.0),
+        marker("rust", r"\b(Some|Ok|Err)\(|\bNone\b.*=>", 1.0),
+        marker("rust", r"->\s*(Result|Option|Self|&?str|String|i32|i64|u8|u32|u64|usize|bool)\b", 1.0),
+        // Python
+        marker("python", r"^\s*def\s+\w+\s*\(.*\)\s*(->\s*[^:]+)?:\s*$", 3.0),
+        marker("python", r"^\s*class\s+\w+(\(.*\))?:\s*$", 3.0),
+        marker("python", r"if\s+__name__\s*==\s*['\x22]__main__['\x22]", 4.0),
+        marker("python", r"^\s*(elif\b.*|else|try|except\b.*|finally):\s*$", 3.0),
+        marker("python", r"^\s*from\s+[\w.]+\s+import\s+[\w*]", 3.0),
+        marker("python", r"^\s*import\s+[\w.]+(\s+as\s+\w+)?\s*$", 1.0),
+        marker("python", r"\bself\.\w+", 1.0),
+        marker("python", r"\b(None|True|False)\b", 0.5),
+        marker("python", r"\bprint\(", 1.0),
+        // JavaScript（TypeScript 在此基础上加分）
+        marker("javascript", r"\bfunction\s*\w*\s*\(", 2.0),
+        marker("javascript", r"\b(const|let|var)\s+\w+\s*=", 1.0),
+        marker("javascript", r"=>\s*[{(\w]", 1.0),
+        marker("javascript", r"\bconsole\.\w+\(", 3.0),
+        marker("javascript", r"\bmodule\.exports\b|\brequire\(['\x22]", 3.0),
+        marker("javascript", r"^\s*(import|export)\b.*\bfrom\s+['\x22]", 2.0),
+        marker("javascript", r"\b(document|window)\.\w+", 2.0),
+        marker("javascript", r"===|!==", 2.0),
+        marker("javascript", r"\bawait\s+\w+", 0.5),
+        // TypeScript
+        marker("typescript", r":\s*(string|number|boolean|any|void|unknown|never)\b", 3.0),
+        marker("typescript", r"^\s*(export\s+)?(interface|type)\s+\w+(<[^>]*>)?\s*(=|\{|extends)", 3.0),
+        marker("typescript", r"\bas\s+(const|string|number|any|unknown)\b", 2.0),
+        marker("typescript", r"\b(private|public|protected|readonly)\s+\w+\s*[:;(?]", 1.0),
+        // Go
+        marker("go", r"^\s*package\s+\w+\s*$", 4.0),
+        marker("go", r"^\s*func\s+(\(\w+\s+\*?\w+\)\s*)?\w+\s*\(", 3.0),
+        marker("go", r"\berr\s*!=\s*nil\b", 4.0),
+        marker("go", r"^\s*import\s*\(", 3.0),
+        marker("go", r"\bfmt\.\w+\(", 3.0),
+        marker("go", r"\w\s*:=\s*", 1.0),
+        marker("go", r"\bgo\s+func\b|\bchan\s+\w+", 2.0),
+        // Java
+        marker("java", r"^\s*package\s+[\w.]+;\s*$", 4.0),
+        marker("java", r"^\s*import\s+(java|javax|org|com)\.[\w.*]+;", 4.0),
+        marker("java", r"\bSystem\.(out|err)\.print", 4.0),
+        marker("java", r"@Override\b", 3.0),
+        marker("java", r"\bpublic\s+(abstract\s+|final\s+)?(class|interface|enum)\s+\w+", 3.0),
+        marker("java", r"\b(public|private|protected)\s+(static\s+)?(final\s+)?[\w<>\[\]]+\s+\w+\s*\(", 2.0),
+        marker("java", r"\bString\[\]\s+\w+", 3.0),
+        // C
+        marker("c", r"^\s*#include\s*<[\w/]+\.h>", 3.0),
+        marker("c", r"\b(printf|scanf|malloc|calloc|free|strcpy|strlen)\s*\(", 2.0),
+        marker("c", r"\btypedef\s+struct\b", 3.0),
+        marker("c", r"\bint\s+main\s*\(", 2.0),
+        marker("c", r"\b(unsigned|char|int|float|double|void)\s+\*?\w+\s*[=;(\[]", 1.0),
+        // C++
+        marker("cpp", r"^\s*#include\s*<\w+>", 3.0),
+        marker("cpp", r"\bstd::\w+", 3.0),
+        marker("cpp", r"\b(cout|cerr|cin)\s*(<<|>>)", 4.0),
+        marker("cpp", r"\btemplate\s*<", 3.0),
+        marker("cpp", r"^\s*(public|private|protected):\s*$", 2.0),
+        marker("cpp", r"\bnullptr\b", 3.0),
+        marker("cpp", r"\bnamespace\s+\w+", 2.0),
+        marker("cpp", r"\bauto\s+\w+\s*=", 1.0),
+        // PHP
+        marker("php", r"<\?php", 6.0),
+        marker("php", r"\$this->", 3.0),
+        marker("php", r"\bfunction\s+\w+\s*\([^)]*\$\w+", 3.0),
+        marker("php", r"^\s*\$\w+\s*=", 2.0),
+        marker("php", r"\becho\s+[\x22'$]", 1.0),
+        // Shell
+        marker("shell", r"^\s*(sudo\s+)?(cd|ls|mkdir|rm|cp|mv|export|chmod|chown|curl|wget|apt|apt-get|brew|yum|pip|pip3|npm|npx|yarn|pnpm|cargo|git|docker|kubectl|make)\s", 2.0),
+        marker("shell", r"^\s*\$\s+\w", 2.0),
+        marker("shell", r"^\s*(if|while|for)\s.*;\s*(then|do)\b", 3.0),
+        marker("shell", r"^\s*(fi|done|esac)\s*$", 3.0),
+        marker("shell", r"\|\s*(grep|awk|sed|xargs|sort|uniq|head|tail|wc)\b", 2.0),
+        marker("shell", r"\s--?[a-zA-Z][\w-]*", 0.5),
+        marker("shell", r"\s&&\s+\w", 1.0),
+        // SQL
+        marker("sql", r"(?i)^\s*select\b[\s\S]*?\bfrom\b", 4.0),
+        marker("sql", r"(?i)^\s*insert\s+into\b", 4.0),
+        marker("sql", r"(?i)^\s*update\s+\w+\s+set\b", 4.0),
+        marker("sql", r"(?i)^\s*delete\s+from\b", 4.0),
+        marker("sql", r"(?i)^\s*(create|alter|drop)\s+(table|index|unique\s+index|view|database)\b", 4.0),
+        marker("sql", r"(?i)\b(where|inner\s+join|left\s+join|group\s+by|order\s+by)\b", 1.0),
+        // HTML
+        marker("html", r"(?i)<!doctype\s+html", 6.0),
+        marker("html", r"(?i)<html[\s>]", 5.0),
+        marker("html", r"(?i)<(div|span|body|head|p|a|ul|ol|li|table|form|input|button|img|section|nav|h[1-6])\b[^>]*>", 2.0),
+        marker("html", r"(?i)</(div|span|body|head|p|a|ul|ol|li|table|form|button|section|nav|h[1-6])>", 2.0),
+        // Vue
+        marker("vue", r"^\s*<template[\s>]", 5.0),
+        marker("vue", r"<script\s+setup\b", 5.0),
+        marker("vue", r"\bdefine(Props|Emits|Expose)\b", 4.0),
+        marker("vue", r"\s(v-(if|else|for|model|show|bind|on)|@click|:key)=", 2.0),
+        // XML
+        marker("xml", r"^\s*<\?xml\b", 6.0),
+        marker("xml", r"</\w+:\w+>", 2.0),
+        marker("xml", r"<\w+(\s+[\w:]+=\x22[^\x22]*\x22)*\s*/>", 1.0),
+        // CSS
+        marker("css", r"^\s*[.#]?[\w-]+([\s>+~,]+[.#:]?[\w-]+)*\s*\{\s*$", 2.0),
+        marker("css", r"^\s*(color|margin|padding|display|font-size|font-weight|background|border|width|height|position)\s*:\s*[^;{}]+;", 3.0),
+        marker("css", r"@(media|keyframes|font-face)\b|@import\s+url\(", 3.0),
+        // Markdown
+        marker("markdown", r"^#{1,6}\s+\S", 2.0),
+        marker("markdown", r"^```", 3.0),
+        marker("markdown", r"\[[^\]]+\]\([^)\s]+\)", 2.0),
+        marker("markdown", r"\*\*[^*\n]+\*\*", 1.0),
+        marker("markdown", r"^>\s", 1.0),
+        marker("markdown", r"^\s*[-*]\s+\S", 0.5),
+    ];
+
+    static ref YAML_LINE: Regex =
+        Regex::new(r"^\s*(-\s+)?[\w.\x22'-]+:(\s+\S.*)?$").expect("yaml pattern is valid");
+    static ref YAML_DOCUMENT: Regex = Regex::new(r"(?m)^---\s*$").expect("yaml pattern is valid");
+}
+
+// ============================================================================
+// 检测
+// ============================================================================
+
+/// 根据文件名或扩展名提示推断语言
+fn language_from_hint(hint: &str) -> Option<&'static str> {
+    let hint = hint.trim();
+    let extension = match Path::new(hint).extension() {
+        Some(extension) => extension.to_str()?,
+        None => hint.strip_prefix('.')?,
+    };
+    language_for_extension(extension)
+}
+
+/// 根据 shebang 推断语言
+fn language_from_shebang(content: &str) -> Option<&'static str> {
+    let first_line = content.lines().next()?.trim();
+    let interpreter = first_line.strip_prefix("#!")?;
+    let interpreter = interpreter
+        .split_whitespace()
+        .map(|part| part.rsplit('/').next().unwrap_or(part))
+        .find(|part| *part != "env" && !part.starts_with('-'))?;
+    let interpreter = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    match interpreter {
+        "sh" | "bash" | "zsh" | "dash" | "ksh" | "fish" => Some("shell"),
+        "python" => Some("python"),
+        "node" | "nodejs" => Some("javascript"),
+        "deno" | "ts-node" | "tsx" => Some("typescript"),
+        "php" => Some("php"),
+        _ => None,
+    }
+}
+
+/// JSON 对象或数组
+fn is_json(content: &str) -> bool {
+    let trimmed = content.trim_start();
+    (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(content).is_ok()
+}
+
+/// 大多数非空行都是 key: value 形式时视为 YAML
+fn yaml_score(content: &str) -> f64 {
+    let lines: Vec<&str> = content
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .collect();
+    if lines.len() < 2 {
+        return 0.0;
+    }
+    let matching = lines
+        .iter()
+        .filter(|line| YAML_LINE.is_match(line) || line.trim_start().starts_with("- "))
+        .count();
+    let mut score = if matching * 10 >= lines.len() * 8 {
+        4.0
+    } else {
+        0.0
+    };
+    if YAML_DOCUMENT.is_match(content) {
+        score += 2.0;
+    }
+    score
+}
+
+/// 截取前 MAX_SCAN_BYTES 字节（按字符边界）
+fn head(content: &str) -> &str {
+    if content.len() <= MAX_SCAN_BYTES {
+        return content;
+    }
+    let mut end = MAX_SCAN_BYTES;
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    &content[..end]
+}
+
+/// 检测内容的语言。hint 为可选的文件名或扩展名（如 "main.rs"、".py"）
+///
+/// 依次使用扩展名提示、shebang、JSON 解析和语法特征打分；特征不足时返回 text。
+pub fn detect(content: &str, hint: Option<&str>) -> LanguageDetection {
+    if content.trim().is_empty() {
+        return LanguageDetection::new("text", 0.0);
+    }
+    if let Some(language) = hint.and_then(language_from_hint) {
+        return LanguageDetection::new(language, HINT_CONFIDENCE);
+    }
+    if let Some(language) = language_from_shebang(content) {
+        return LanguageDetection::new(language, SHEBANG_CONFIDENCE);
+    }
+    if is_json(content) {
+        return LanguageDetection::new("json", JSON_CONFIDENCE);
+    }
+
+    let content = head(content);
+    let mut scores: Vec<(&'static str, f64)> = Vec::new();
+    let mut add = |language: &'static str, weight: f64| match scores
+        .iter_mut()
+        .find(|(name, _)| *name == language)
+    {
+        Some((_, score)) => *score += weight,
+        None => scores.push((language, weight)),
+    };
+    for marker in MARKERS.iter() {
+        if marker.pattern.is_match(content) {
+            add(marker.language, marker.weight);
+        }
+    }
+    add("yaml", yaml_score(content));
+
+    let score_of = |scores: &[(&str, f64)], language: &str| {
+        scores
+            .iter()
+            .find(|(name, _)| *name == language)
+            .map_or(0.0, |(_, score)| *score)
+    };
+    // TypeScript 是 JavaScript 的超集：有类型特征时叠加 JavaScript 得分
+    let typescript = score_of(&scores, "typescript");
+    if typescript > 0.0 {
+        let javascript = score_of(&scores, "javascript");
+        if let Some((_, score)) = scores.iter_mut().find(|(name, _)| *name == "typescript") {
+            *score += javascript;
+        }
+    }
+    // Vue 单文件组件包含 HTML 模板和脚本
+    if score_of(&scores, "vue") >= 5.0 {
+        for language in ["html", "javascript", "typescript"] {
+            if let Some((_, score)) = scores.iter_mut().find(|(name, _)| *name == language) {
+                *score = score.min(MIN_SCORE);
+            }
+        }
+    }
+
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let (best_language, best) = scores.first().copied().unwrap_or(("text", 0.0));
+    let second = scores.get(1).map_or(0.0, |(_, score)| *score);
+
+    if best < MIN_SCORE {
+        return LanguageDetection::new("text", 1.0 - best / MIN_SCORE);
+    }
+    let strength = (best / STRONG_SCORE).min(1.0);
+    let margin = (best - second) / best;
+    LanguageDetection::new(best_language, 0.5 * strength + 0.5 * margin)
+}
+
+/// 解析用户提供的语言：为空或 "auto" 时根据内容检测，否则规范化
+pub fn resolve(language: &str, content: &str, hint: Option<&str>) -> Result<String, String> {
+    if is_auto(language) {
+        Ok(detect(content, hint).language)
+    } else {
+        normalize_language(language)
+    }
+}
@@ -1,16 +1,32 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod archive;
+mod capture_rules;
+mod clipboard;
+mod collections;
 mod commands;
+mod db;
+mod encryption;
+mod exporters;
+mod git_sync;
+mod i18n;
+mod importers;
+mod language_detect;
+mod maintenance;
 mod mcp_server;
 mod models;
+mod palette;
+mod retention;
+mod secrets;
+mod snippets;
+mod stats;
+mod sync;
+mod tray;
+mod validation;
 
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconEvent};
-use tauri::{
-    menu::{Menu, MenuItem},
-    tray::TrayIconBuilder,
-    Manager, WindowEvent,
-};
+use tauri::{tray::TrayIconBuilder, Manager, WindowEvent};
 use tauri_plugin_aptabase::EventTracker;
 
 // 只在 macOS 上导入 RunEvent
@@ -30,6 +46,7 @@ pub async fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(
             tauri_plugin_aptabase::Builder::new("A-SH-1174248246")
                 .with_options(tauri_plugin_aptabase::InitOptions {
@@ -48,48 +65,36 @@ pub async fn run() {
                 None, // 不需要额外参数
             ))?;
 
-            // 创建系统托盘菜单
-            let show_item = MenuItem::with_id(app, "show", "显示窗口", true, None::<&str>)?;
-            let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
-
-            // 创建系统托盘
-            let _tray = TrayIconBuilder::with_id("tray")
+            // 创建系统托盘（菜单内容在数据变化时由 tray::refresh 重建）
+            let menu = tray::build_menu(app.handle(), &tray::TrayState::default())?;
+            let _tray = TrayIconBuilder::with_id(tray::TRAY_ID)
                 .icon(tauri::include_image!("icons/menubar/icon.png"))
                 .icon_as_template(true)
                 .menu(&menu)
                 .show_menu_on_left_click(false)
-                .on_menu_event(|app, event| match event.id.as_ref() {
-                    "show" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    "quit" => {
-                        // 追踪应用退出事件
-                        let _ = app.track_event("app_exited", None);
-                        app.flush_events_blocking();
-                        app.exit(0);
-                    }
-                    _ => {}
-                })
+                .on_menu_event(|app, event| tray::handle_menu_event(app, event.id.as_ref()))
                 .on_tray_icon_event(|tray, event| match event {
                     TrayIconEvent::Click {
                         button: MouseButton::Left,
                         button_state: MouseButtonState::Up,
                         ..
-                    } => {
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
+                    } => tray::show_main_window(tray.app_handle()),
                     _ => {}
                 })
                 .build(app)?;
 
+            // 先初始化加密状态并补齐旧数据，再根据设置恢复文件夹同步
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                commands::init_encryption(handle.clone()).await;
+                commands::init_data_backfill(handle.clone()).await;
+                commands::restore_folder_sync(handle.clone()).await;
+                tray::refresh(&handle);
+            });
+            tauri::async_runtime::spawn(commands::init_auto_backup(app.handle().clone()));
+            tauri::async_runtime::spawn(commands::init_clipboard_retention(app.handle().clone()));
+            tauri::async_runtime::spawn(commands::init_palette_shortcut(app.handle().clone()));
+
             // 监听窗口事件，关闭时隐藏而不是退出
             if let Some(window) = app.get_webview_window("main") {
                 let app_handle = app.app_handle().clone();
@@ -114,8 +119,83 @@ pub async fn run() {
         )
         .invoke_handler(tauri::generate_handler![
             commands::get_current_timestamp,
+            commands::set_language,
+            commands::create_snippet,
+            commands::update_snippet,
+            commands::get_snippet,
+            commands::search_snippets,
+            commands::list_snippets,
+            commands::record_snippet_use,
+            commands::set_snippets_pinned,
+            commands::get_top_snippets,
+            commands::find_duplicates,
+            commands::bulk_create_snippets,
+            commands::bulk_update_snippets,
+            commands::bulk_add_tags,
+            commands::bulk_remove_tags,
+            commands::bulk_delete_snippets,
+            commands::export_data,
+            commands::import_data,
+            commands::preview_external_import,
+            commands::import_external,
+            commands::export_vscode_snippets,
+            commands::export_jetbrains_templates,
+            commands::list_collections,
+            commands::create_collection,
+            commands::rename_collection,
+            commands::move_collection,
+            commands::delete_collection,
+            commands::move_snippets_to_collection,
+            commands::configure_folder_sync,
+            commands::sync_folder_now,
+            commands::get_folder_sync_status,
+            commands::resolve_folder_sync_conflict,
+            commands::configure_git_sync,
+            commands::sync_git_now,
+            commands::get_git_sync_status,
+            commands::resolve_git_sync_conflict,
+            commands::get_database_stats,
+            commands::backup_database,
+            commands::optimize_database,
+            commands::check_database_integrity,
+            commands::configure_auto_backup,
+            commands::get_auto_backup_status,
+            commands::create_backup_now,
+            commands::delete_auto_backups,
+            commands::restore_backup,
+            commands::get_encryption_status,
+            commands::enable_encryption,
+            commands::disable_encryption,
+            commands::unlock_database,
+            commands::lock_database,
             commands::get_clipboard_content,
             commands::set_clipboard_content,
+            commands::add_clipboard_item,
+            commands::get_clipboard_history,
+            commands::get_clipboard_item,
+            commands::search_clipboard,
+            commands::set_clipboard_items_pinned,
+            commands::delete_clipboard_items,
+            commands::clear_clipboard_history,
+            commands::get_clipboard_retention,
+            commands::set_clipboard_retention,
+            commands::run_clipboard_retention,
+            commands::get_palette_shortcut,
+            commands::set_palette_shortcut,
+            commands::search_palette,
+            commands::select_palette_item,
+            commands::show_palette,
+            commands::hide_palette,
+            commands::promote_clipboard_item,
+            commands::scan_secrets,
+            commands::detect_language,
+            commands::capture_clipboard_item,
+            commands::capture_system_clipboard,
+            commands::get_clipboard_capture_status,
+            commands::set_clipboard_capture_rules,
+            commands::pause_clipboard_capture,
+            commands::resume_clipboard_capture,
+            commands::set_clipboard_capture_enabled,
             commands::show_window,
             commands::hide_window,
             commands::is_window_visible,
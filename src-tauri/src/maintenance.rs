@@ -0,0 +1,596 @@
+use crate::db::{get_setting, now_timestamp};
+use crate::i18n::{t, t_with};
+use libsqlite3_sys as ffi;
+use serde::Serialize;
+use sqlx::migrate::{Migration as SqlxMigration, MigrationType, Migrator};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
+use sqlx::{Row, SqlitePool};
+use std::borrow::Cow;
+use std::ffi::{CStr, CString};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri_plugin_sql::{Migration, MigrationKind};
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+/// 每次 backup_step 复制的页数，分步复制以便其他连接在间隙写入
+const BACKUP_PAGES_PER_STEP: i32 = 256;
+/// 源数据库繁忙时的重试间隔
+const BACKUP_BUSY_WAIT: Duration = Duration::from_millis(50);
+/// 自动备份任务检查是否需要备份的间隔
+const AUTO_BACKUP_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// 自动备份文件名前缀
+const AUTO_BACKUP_PREFIX: &str = "seekcode-";
+
+pub const DEFAULT_AUTO_BACKUP_INTERVAL_HOURS: u64 = 24;
+pub const DEFAULT_AUTO_BACKUP_KEEP: usize = 7;
+
+// ============================================================================
+// 在线备份（SQLite backup API）
+// ============================================================================
+
+/// 备份文件信息
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    pub created_at: String,
+}
+
+/// 原生 SQLite 连接，离开作用域时关闭
+struct RawDb(*mut ffi::sqlite3);
+
+impl Drop for RawDb {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::sqlite3_close(self.0);
+        }
+    }
+}
+
+impl RawDb {
+    fn open(path: &Path, flags: i32) -> Result<Self, String> {
+        let c_path = CString::new(path.to_string_lossy().as_bytes())
+            .map_err(|_| format!("Invalid path: {}", path.display()))?;
+        let mut handle = std::ptr::null_mut();
+        let rc =
+            unsafe { ffi::sqlite3_open_v2(c_path.as_ptr(), &mut handle, flags, std::ptr::null()) };
+        // 即使打开失败也需要关闭句柄
+        let db = RawDb(handle);
+        if rc != ffi::SQLITE_OK {
+            return Err(format!("Failed to open {}: {}", path.display(), db.error()));
+        }
+        Ok(db)
+    }
+
+    fn error(&self) -> String {
+        if self.0.is_null() {
+            return "out of memory".to_string();
+        }
+        unsafe {
+            CStr::from_ptr(ffi::sqlite3_errmsg(self.0))
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+}
+
+/// 使用 SQLite 在线备份 API 将 source 复制到 dest（阻塞）
+///
+/// 先写入临时文件，完成后再重命名，避免留下不完整的备份文件。
+fn run_backup(source: &Path, dest: &Path) -> Result<(), String> {
+    let tmp = dest.with_extension("db-partial");
+    let _ = std::fs::remove_file(&tmp);
+
+    let result = (|| {
+        let src = RawDb::open(source, ffi::SQLITE_OPEN_READONLY)?;
+        let dst = RawDb::open(&tmp, ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE)?;
+        let main = c"main".as_ptr();
+
+        unsafe {
+            let backup = ffi::sqlite3_backup_init(dst.0, main, src.0, main);
+            if backup.is_null() {
+                return Err(format!("Failed to start backup: {}", dst.error()));
+            }
+            loop {
+                match ffi::sqlite3_backup_step(backup, BACKUP_PAGES_PER_STEP) {
+                    ffi::SQLITE_OK => {}
+                    ffi::SQLITE_DONE => break,
+                    ffi::SQLITE_BUSY | ffi::SQLITE_LOCKED => std::thread::sleep(BACKUP_BUSY_WAIT),
+                    _ => {
+                        ffi::sqlite3_backup_finish(backup);
+                        return Err(format!("Backup failed: {}", dst.error()));
+                    }
+                }
+            }
+            if ffi::sqlite3_backup_finish(backup) != ffi::SQLITE_OK {
+                return Err(format!("Backup failed: {}", dst.error()));
+            }
+        }
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => std::fs::rename(&tmp, dest)
+            .map_err(|e| format!("Failed to move backup into place: {}", e)),
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+/// 创建数据库的一致性在线备份
+pub async fn backup_database(source: &Path, dest: &Path) -> Result<BackupInfo, String> {
+    if source == dest {
+        return Err(t("errors.backupPathIsDatabase"));
+    }
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let (src, dst) = (source.to_path_buf(), dest.to_path_buf());
+    tokio::task::spawn_blocking(move || run_backup(&src, &dst))
+        .await
+        .map_err(|e| format!("Backup task failed: {}", e))??;
+
+    let size_bytes = std::fs::metadata(dest).map(|m| m.len()).unwrap_or(0);
+    Ok(BackupInfo {
+        path: dest.to_string_lossy().into_owned(),
+        size_bytes,
+        created_at: now_timestamp(),
+    })
+}
+
+// ============================================================================
+// VACUUM / ANALYZE / 完整性检查
+// ============================================================================
+
+/// 数据库优化结果
+#[derive(Debug, Serialize)]
+pub struct OptimizeReport {
+    pub size_before_bytes: i64,
+    pub size_after_bytes: i64,
+    pub reclaimed_bytes: i64,
+    pub duration_ms: u64,
+}
+
+async fn database_size(pool: &SqlitePool) -> Result<i64, String> {
+    let page_count: i64 = sqlx::query_scalar("PRAGMA page_count")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let page_size: i64 = sqlx::query_scalar("PRAGMA page_size")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(page_count * page_size)
+}
+
+/// 执行 VACUUM 回收空间并用 ANALYZE 更新查询统计
+pub async fn optimize_database(pool: &SqlitePool) -> Result<OptimizeReport, String> {
+    let started = Instant::now();
+    let size_before_bytes = database_size(pool).await?;
+
+    sqlx::query("VACUUM")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("VACUUM failed: {}", e))?;
+    sqlx::query("ANALYZE")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("ANALYZE failed: {}", e))?;
+
+    let size_after_bytes = database_size(pool).await?;
+    Ok(OptimizeReport {
+        size_before_bytes,
+        size_after_bytes,
+        reclaimed_bytes: (size_before_bytes - size_after_bytes).max(0),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// 完整性检查结果
+#[derive(Debug, Serialize)]
+pub struct IntegrityReport {
+    pub ok: bool,
+    /// 一句话总结，便于直接展示
+    pub summary: String,
+    /// integrity_check 报告的问题（正常时为空）
+    pub problems: Vec<String>,
+    /// foreign_key_check 报告的外键问题
+    pub foreign_key_problems: Vec<String>,
+    pub checked_at: String,
+    pub duration_ms: u64,
+}
+
+/// 执行 PRAGMA integrity_check 和 foreign_key_check
+pub async fn check_integrity(pool: &SqlitePool) -> Result<IntegrityReport, String> {
+    let started = Instant::now();
+    let rows: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("integrity_check failed: {}", e))?;
+    let problems: Vec<String> = rows.into_iter().filter(|row| row != "ok").collect();
+
+    let foreign_key_problems: Vec<String> = sqlx::query("PRAGMA foreign_key_check")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("foreign_key_check failed: {}", e))?
+        .into_iter()
+        .map(|row| {
+            let table: String = row.get(0);
+            let rowid: Option<i64> = row.get(1);
+            let parent: String = row.get(2);
+            format!(
+                "{} row {} references a missing row in {}",
+                table,
+                rowid
+                    .map(|id| id.to_string())
+                    .unwrap_or_else(|| "?".to_string()),
+                parent
+            )
+        })
+        .collect();
+
+    let ok = problems.is_empty() && foreign_key_problems.is_empty();
+    let summary = if ok {
+        t("maintenance.noProblems")
+    } else {
+        t_with(
+            "maintenance.problemsFound",
+            &[
+                ("problems", &problems.len()),
+                ("foreignKeys", &foreign_key_problems.len()),
+            ],
+        )
+    };
+
+    Ok(IntegrityReport {
+        ok,
+        summary,
+        problems,
+        foreign_key_problems,
+        checked_at: now_timestamp(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+// ============================================================================
+// 自动轮换备份
+// ============================================================================
+
+/// 自动备份配置，对应 user_settings 中的 auto_backup_* 设置
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoBackupConfig {
+    pub enabled: bool,
+    pub interval_hours: u64,
+    /// 保留的备份数量，超出时删除最旧的备份
+    pub keep: usize,
+    pub dir: PathBuf,
+}
+
+impl AutoBackupConfig {
+    /// 从 user_settings 读取配置，未设置目录时使用 default_dir
+    pub async fn load(pool: &SqlitePool, default_dir: PathBuf) -> Self {
+        let enabled = get_setting(pool, "auto_backup_enabled").await.as_deref() == Some("true");
+        let interval_hours = get_setting(pool, "auto_backup_interval_hours")
+            .await
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .filter(|hours| *hours > 0)
+            .unwrap_or(DEFAULT_AUTO_BACKUP_INTERVAL_HOURS);
+        let keep = get_setting(pool, "auto_backup_keep")
+            .await
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .filter(|keep| *keep > 0)
+            .unwrap_or(DEFAULT_AUTO_BACKUP_KEEP);
+        let dir = get_setting(pool, "auto_backup_dir")
+            .await
+            .filter(|dir| !dir.trim().is_empty())
+            .map(PathBuf::from)
+            .unwrap_or(default_dir);
+
+        Self {
+            enabled,
+            interval_hours,
+            keep,
+            dir,
+        }
+    }
+}
+
+/// 列出目录中的自动备份，按时间倒序（文件名包含时间戳）
+pub fn list_backups(dir: &Path) -> Vec<BackupInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut backups: Vec<BackupInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let stamp = name.strip_prefix(AUTO_BACKUP_PREFIX)?.strip_suffix(".db")?;
+            let created = chrono::NaiveDateTime::parse_from_str(stamp, "%Y%m%d-%H%M%S").ok()?;
+            Some(BackupInfo {
+                path: entry.path().to_string_lossy().into_owned(),
+                size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                created_at: created.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    backups
+}
+
+/// 删除超出保留数量的旧备份，返回删除的数量
+fn prune_backups(dir: &Path, keep: usize) -> usize {
+    list_backups(dir)
+        .into_iter()
+        .skip(keep)
+        .filter(|backup| std::fs::remove_file(&backup.path).is_ok())
+        .count()
+}
+
+/// 删除目录中的全部自动备份，返回删除的数量
+pub fn delete_backups(dir: &Path) -> usize {
+    prune_backups(dir, 0)
+}
+
+/// 在备份目录中创建一个带时间戳的备份，并轮换旧备份
+pub async fn create_rotating_backup(
+    source: &Path,
+    config: &AutoBackupConfig,
+) -> Result<BackupInfo, String> {
+    let file_name = format!(
+        "{}{}.db",
+        AUTO_BACKUP_PREFIX,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let backup = backup_database(source, &config.dir.join(file_name)).await?;
+    prune_backups(&config.dir, config.keep);
+    Ok(backup)
+}
+
+/// 距离最近一次备份是否已超过备份间隔
+fn backup_due(config: &AutoBackupConfig) -> bool {
+    let Some(latest) = list_backups(&config.dir).into_iter().next() else {
+        return true;
+    };
+    let Ok(latest) = chrono::NaiveDateTime::parse_from_str(&latest.created_at, "%Y-%m-%dT%H:%M:%S")
+    else {
+        return true;
+    };
+    let elapsed = chrono::Local::now().naive_local() - latest;
+    elapsed >= chrono::Duration::hours(config.interval_hours as i64)
+}
+
+lazy_static::lazy_static! {
+    static ref AUTO_BACKUP_TASK: Mutex<Option<CancellationToken>> = Mutex::new(None);
+}
+
+/// 启动自动备份任务：定期检查距上次备份的时间，到期时创建备份
+pub async fn start_auto_backup(source: PathBuf, config: AutoBackupConfig) {
+    stop_auto_backup().await;
+    if !config.enabled {
+        return;
+    }
+
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(AUTO_BACKUP_CHECK_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+            if !backup_due(&config) {
+                continue;
+            }
+            match create_rotating_backup(&source, &config).await {
+                Ok(backup) => tracing::info!("automatic backup created: {}", backup.path),
+                Err(e) => tracing::warn!("automatic backup failed: {}", e),
+            }
+        }
+    });
+
+    *AUTO_BACKUP_TASK.lock().await = Some(cancel);
+}
+
+/// 停止自动备份任务
+pub async fn stop_auto_backup() {
+    if let Some(cancel) = AUTO_BACKUP_TASK.lock().await.take() {
+        cancel.cancel();
+    }
+}
+
+pub async fn auto_backup_running() -> bool {
+    AUTO_BACKUP_TASK.lock().await.is_some()
+}
+
+// ============================================================================
+// 从备份恢复
+// ============================================================================
+
+/// 恢复结果
+#[derive(Debug, Serialize)]
+pub struct RestoreReport {
+    pub restored_from: String,
+    /// 备份文件中的迁移版本
+    pub backup_version: i64,
+    /// 恢复后的迁移版本
+    pub schema_version: i64,
+    /// 是否对备份执行了迁移
+    pub migrated: bool,
+    /// 恢复前数据库的安全副本
+    pub safety_copy: String,
+    pub restored_at: String,
+}
+
+lazy_static::lazy_static! {
+    static ref RESTORE_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// 同一时间只允许一个恢复操作
+pub async fn lock_restore() -> tokio::sync::MutexGuard<'static, ()> {
+    RESTORE_LOCK.lock().await
+}
+
+/// 读取数据库已成功应用的最新迁移版本（与 tauri-plugin-sql 共用 _sqlx_migrations 表）
+async fn schema_version(pool: &SqlitePool) -> Result<i64, String> {
+    let has_history: Option<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if has_history.is_none() {
+        return Err(t("errors.notSeekCodeDatabase"));
+    }
+
+    let version: Option<i64> =
+        sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(version.unwrap_or(0))
+}
+
+/// 按 tauri-plugin-sql 的方式构建迁移器，保证校验和与前端加载时一致
+fn migrator(migrations: Vec<Migration>) -> Migrator {
+    let migrations: Vec<SqlxMigration> = migrations
+        .into_iter()
+        .filter(|migration| matches!(migration.kind, MigrationKind::Up))
+        .map(|migration| {
+            SqlxMigration::new(
+                migration.version,
+                migration.description.into(),
+                MigrationType::ReversibleUp,
+                migration.sql.into(),
+                false,
+            )
+        })
+        .collect();
+    Migrator {
+        migrations: Cow::Owned(migrations),
+        ..Migrator::DEFAULT
+    }
+}
+
+/// 检查暂存副本并迁移到最新版本，返回（备份版本，最新版本）
+async fn migrate_staging(staging: &Path, migrator: &Migrator) -> Result<(i64, i64), String> {
+    let latest = migrator
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(0);
+    let options = SqliteConnectOptions::new()
+        .filename(staging)
+        .journal_mode(SqliteJournalMode::Delete);
+    let pool = SqlitePool::connect_with(options)
+        .await
+        .map_err(|e| format!("Failed to open backup: {}", e))?;
+
+    let result = async {
+        let check: String = sqlx::query_scalar("PRAGMA quick_check")
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| t_with("errors.invalidBackup", &[("error", &e)]))?;
+        if check != "ok" {
+            return Err(t_with(
+                "errors.backupIntegrityFailed",
+                &[("result", &check)],
+            ));
+        }
+
+        let version = schema_version(&pool).await?;
+        if version > latest {
+            return Err(t_with(
+                "errors.backupTooNew",
+                &[("version", &version), ("latest", &latest)],
+            ));
+        }
+        if version < latest {
+            migrator
+                .run(&pool)
+                .await
+                .map_err(|e| format!("Failed to migrate backup: {}", e))?;
+        }
+        Ok((version, latest))
+    }
+    .await;
+
+    pool.close().await;
+    result
+}
+
+/// 校验备份文件，并在 staging 生成已迁移到当前版本的副本，返回（备份版本，最新版本）
+///
+/// 备份文件本身不会被修改；失败时删除暂存副本。
+pub async fn prepare_restore(
+    backup: &Path,
+    staging: &Path,
+    migrations: Vec<Migration>,
+) -> Result<(i64, i64), String> {
+    if !backup.is_file() {
+        return Err(t_with(
+            "errors.backupNotFound",
+            &[("path", &backup.display())],
+        ));
+    }
+
+    let (src, dst) = (backup.to_path_buf(), staging.to_path_buf());
+    tokio::task::spawn_blocking(move || run_backup(&src, &dst))
+        .await
+        .map_err(|e| format!("Restore task failed: {}", e))?
+        .map_err(|e| format!("Failed to read backup: {}", e))?;
+
+    let result = migrate_staging(staging, &migrator(migrations)).await;
+    if result.is_err() {
+        let _ = std::fs::remove_file(staging);
+    }
+    result
+}
+
+/// 数据库文件的附属文件（WAL 与共享内存）
+fn sidecar_files(db: &Path) -> [PathBuf; 2] {
+    ["-wal", "-shm"].map(|suffix| {
+        let mut name = db.as_os_str().to_owned();
+        name.push(suffix);
+        PathBuf::from(name)
+    })
+}
+
+/// 用暂存副本替换数据库文件，替换前先把当前数据库备份到 safety_copy
+///
+/// 调用方需确保此时没有打开的数据库连接。
+pub async fn swap_database(live: &Path, staging: &Path, safety_copy: &Path) -> Result<(), String> {
+    if let Some(parent) = safety_copy.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let (live, staging, safety_copy) = (
+        live.to_path_buf(),
+        staging.to_path_buf(),
+        safety_copy.to_path_buf(),
+    );
+    tokio::task::spawn_blocking(move || {
+        if live.exists() {
+            run_backup(&live, &safety_copy)
+                .map_err(|e| format!("Failed to create safety copy: {}", e))?;
+        }
+        // 旧的 WAL 会被应用到新文件上，必须一并删除（内容已在安全副本中）
+        for sidecar in sidecar_files(&live) {
+            if sidecar.exists() {
+                std::fs::remove_file(&sidecar)
+                    .map_err(|e| format!("Failed to remove {}: {}", sidecar.display(), e))?;
+            }
+        }
+        std::fs::rename(&staging, &live)
+            .map_err(|e| format!("Failed to move restored database into place: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Restore task failed: {}", e))?
+}
@@ -1,5 +1,11 @@
+use crate::clipboard::{self, ClipboardQuery};
+use crate::collections::{self, SUBTREE_SQL};
+use crate::encryption;
+use crate::models::ClipboardItem;
+use crate::secrets::{self, SecretKind};
+use crate::snippets::{self, CreateOutcome, NewSnippet, SnippetError, SnippetSort};
+use crate::stats;
 use anyhow::{anyhow, Result};
-use chrono::Local;
 use futures::Future;
 use rmcp::{
     handler::server::{router::tool::ToolRouter, tool::Parameters},
@@ -26,13 +32,19 @@ pub struct CreateSnippetRequest {
     #[schemars(description = "Content of the code snippet")]
     pub code: String,
     #[schemars(
-        description = "Programming language type, e.g., 'javascript', 'python', 'rust', etc."
+        description = "Programming language type, e.g., 'javascript', 'python', 'rust', etc. Omit or use 'auto' to detect it from the code"
     )]
-    pub language: String,
+    pub language: Option<String>,
     #[schemars(description = "Optional list of tags for categorization and search")]
     pub tags: Option<Vec<String>>,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct BatchCreateSnippetRequest {
+    #[schemars(description = "List of code snippets to create in a single transaction")]
+    pub snippets: Vec<CreateSnippetRequest>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 #[allow(dead_code)]
 pub struct UpdateSnippetRequest {
@@ -59,6 +71,14 @@ pub struct SearchSnippetRequest {
         description = "Optional tags filter - only return snippets containing specified tags"
     )]
     pub tags: Option<Vec<String>>,
+    #[schemars(
+        description = "Optional collection name or path (e.g. 'Backend/SQL'); nested collections are included"
+    )]
+    pub collection: Option<String>,
+    #[schemars(
+        description = "Optional sort order: 'updated' (default), 'created', 'title', 'most_used' or 'frecency' (pinned first, then frequently and recently used)"
+    )]
+    pub sort: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -73,6 +93,158 @@ pub struct ListRequest {
     pub page: Option<u64>,
     #[schemars(description = "Number of items per page, defaults to 20")]
     pub limit: Option<u64>,
+    #[schemars(
+        description = "Optional collection name or path (e.g. 'Backend/SQL'); nested collections are included"
+    )]
+    pub collection: Option<String>,
+    #[schemars(
+        description = "Optional sort order: 'updated' (default), 'created', 'title', 'most_used' or 'frecency' (pinned first, then frequently and recently used)"
+    )]
+    pub sort: Option<String>,
+}
+
+/// list_clipboard 每页最大条数
+const MAX_CLIPBOARD_PAGE_SIZE: u64 = 100;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ListClipboardRequest {
+    #[schemars(description = "Page number, defaults to 1")]
+    pub page: Option<u64>,
+    #[schemars(description = "Number of items per page, defaults to 20, at most 100")]
+    pub limit: Option<u64>,
+    #[schemars(description = "Optional keyword to match in the clipboard content")]
+    pub query: Option<String>,
+    #[schemars(description = "Only return pinned items when true, unpinned items when false")]
+    pub pinned: Option<bool>,
+}
+
+/// 将集合名称或路径解析为集合 ID
+async fn resolve_collection(
+    pool: &SqlitePool,
+    collection: Option<&str>,
+) -> Result<Option<i64>, McpError> {
+    let Some(path) = collection.filter(|path| !path.trim().is_empty()) else {
+        return Ok(None);
+    };
+    match collections::resolve_path(pool, path).await {
+        Ok(Some(id)) => Ok(Some(id)),
+        Ok(None) => Err(McpError::invalid_params(
+            format!("Collection '{}' not found", path),
+            None,
+        )),
+        Err(e) => Err(McpError::internal_error(
+            format!("Failed to resolve collection: {}", e),
+            None,
+        )),
+    }
+}
+
+/// 解析排序参数，未提供时按更新时间排序
+fn parse_sort(sort: Option<&str>) -> Result<SnippetSort, McpError> {
+    match sort.filter(|sort| !sort.trim().is_empty()) {
+        None => Ok(SnippetSort::default()),
+        Some(value) => SnippetSort::parse(value).ok_or_else(|| {
+            McpError::invalid_params(
+                format!(
+                    "Unknown sort '{}', expected one of: updated, created, title, most_used, frecency",
+                    value
+                ),
+                None,
+            )
+        }),
+    }
+}
+
+/// 将代码片段写入错误转换为 MCP 错误
+fn snippet_error_to_mcp(e: SnippetError, context: &str) -> McpError {
+    match e {
+        SnippetError::Invalid(_) | SnippetError::NotFound(_) | SnippetError::Duplicate(_) => {
+            McpError::invalid_params(e.to_string(), None)
+        }
+        SnippetError::Encryption(_) => McpError::invalid_request(e.to_string(), None),
+        SnippetError::Database(_) => McpError::internal_error(format!("{}: {}", context, e), None),
+    }
+}
+
+/// 数据库已加密且未解锁时拒绝请求
+fn ensure_unlocked() -> Result<(), McpError> {
+    encryption::ensure_unlocked().map_err(|e| McpError::invalid_request(e, None))
+}
+
+/// 读取并解密 code 字段
+fn row_code(row: &sqlx::sqlite::SqliteRow) -> Result<String, McpError> {
+    encryption::open(row.get("code")).map_err(|e| McpError::invalid_request(e, None))
+}
+
+/// 读取并解密 code 字段，检测到密钥且未允许返回密钥时遮盖，同时返回密钥类型
+fn screened_code(
+    row: &sqlx::sqlite::SqliteRow,
+    allow_secrets: bool,
+) -> Result<(String, Vec<SecretKind>), McpError> {
+    let code = row_code(row)?;
+    match secrets::parse_flags(row.get::<Option<&str>, _>("secret_flags")) {
+        Some(flags) if flags.is_empty() || allow_secrets => Ok((code, flags)),
+        Some(flags) => Ok((secrets::redact_all(&code), flags)),
+        // 尚未补齐标记的旧数据按可能包含密钥处理
+        None if allow_secrets => Ok((code, Vec::new())),
+        None => Ok((secrets::redact_all(&code), Vec::new())),
+    }
+}
+
+/// 把查询结果行转换为 JSON（code 字段已解密，按权限遮盖密钥）
+fn row_to_json(
+    row: &sqlx::sqlite::SqliteRow,
+    allow_secrets: bool,
+) -> Result<serde_json::Value, McpError> {
+    let tags_str: String = row.get("tags");
+    let tags: Vec<String> = if tags_str.is_empty() {
+        Vec::new()
+    } else {
+        serde_json::from_str(&tags_str).unwrap_or_default()
+    };
+
+    let (code, secret_flags) = screened_code(row, allow_secrets)?;
+
+    Ok(json!({
+        "id": row.get::<i64, _>("id"),
+        "title": row.get::<String, _>("title"),
+        "code": code,
+        "secret_flags": secret_flags,
+        "language": row.get::<String, _>("language"),
+        "tags": tags,
+        "collection_id": row.get::<Option<i64>, _>("collection_id"),
+        "pinned": row.get::<bool, _>("pinned"),
+        "use_count": row.get::<i64, _>("use_count"),
+        "last_used_at": row.get::<Option<String>, _>("last_used_at"),
+        "created_at": row.get::<String, _>("created_at"),
+        "updated_at": row.get::<String, _>("updated_at"),
+    }))
+}
+
+/// 剪贴板条目返回给客户端的内容：检测到密钥且未允许返回密钥时遮盖
+fn clipboard_content(item: &ClipboardItem, allow_secrets: bool) -> String {
+    let flags = secrets::parse_flags(item.secret_flags.as_deref());
+    // 尚未补齐标记的旧数据按可能包含密钥处理
+    if allow_secrets || flags.is_some_and(|flags| flags.is_empty()) {
+        item.content.clone()
+    } else {
+        secrets::redact_all(&item.content)
+    }
+}
+
+/// 把剪贴板条目转换为 JSON（不含图片缩略图，按权限遮盖密钥）
+fn clipboard_item_to_json(item: ClipboardItem, allow_secrets: bool) -> serde_json::Value {
+    let content = clipboard_content(&item, allow_secrets);
+    let secret_flags = secrets::parse_flags(item.secret_flags.as_deref()).unwrap_or_default();
+    json!({
+        "id": item.id,
+        "kind": item.kind,
+        "content": content,
+        "secret_flags": secret_flags,
+        "language": item.language,
+        "pinned": item.pinned,
+        "created_at": item.created_at,
+    })
 }
 
 #[derive(Clone)]
@@ -89,6 +261,8 @@ pub struct ServerPermissions {
     pub allow_create: bool,
     pub allow_update: bool,
     pub allow_delete: bool,
+    /// 是否允许返回检测到密钥的代码原文（否则遮盖后返回）
+    pub allow_secrets: bool,
 }
 
 #[tool_router]
@@ -108,44 +282,81 @@ impl SnippetService {
         &self,
         Parameters(request): Parameters<CreateSnippetRequest>,
     ) -> Result<CallToolResult, McpError> {
-        let tags_json = request
-            .tags
-            .as_ref()
-            .map(|tags| serde_json::to_string(tags).unwrap_or_default())
-            .unwrap_or_default();
-
-        let now = Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
-
-        let result: std::result::Result<sqlx::sqlite::SqliteQueryResult, sqlx::Error> =
-            sqlx::query(
-                "INSERT INTO code_snippets (title, code, language, tags, created_at, updated_at) 
-             VALUES (?, ?, ?, ?, ?, ?)",
-            )
-            .bind(&request.title)
-            .bind(&request.code)
-            .bind(&request.language)
-            .bind(&tags_json)
-            .bind(&now)
-            .bind(&now)
-            .execute(&self.db_pool)
-            .await;
-
-        match result {
-            Ok(query_result) => {
-                let id = query_result.last_insert_rowid();
+        if !self.permissions.allow_create {
+            return Err(McpError::invalid_params("Create not allowed", None));
+        }
+        ensure_unlocked()?;
+        let input = NewSnippet {
+            title: request.title,
+            language: request.language.unwrap_or_default(),
+            code: request.code,
+            tags: request.tags.unwrap_or_default(),
+        };
+
+        match snippets::create_snippet(&self.db_pool, input).await {
+            Ok(CreateOutcome::Created(snippet)) => {
                 Ok(CallToolResult::success(vec![Content::text(
                     json!({
                         "success": true,
-                        "id": id,
+                        "id": snippet.id,
+                        "duplicate": false,
                         "message": "Snippet created successfully"
                     })
                     .to_string(),
                 )]))
             }
-            Err(e) => Err(McpError::internal_error(
-                format!("Failed to create snippet: {}", e),
-                None,
-            )),
+            Ok(CreateOutcome::Existing(snippet)) => {
+                Ok(CallToolResult::success(vec![Content::text(
+                    json!({
+                        "success": true,
+                        "id": snippet.id,
+                        "duplicate": true,
+                        "message": "A snippet with the same content already exists"
+                    })
+                    .to_string(),
+                )]))
+            }
+            Err(e) => Err(snippet_error_to_mcp(e, "Failed to create snippet")),
+        }
+    }
+
+    #[tool(
+        description = "Create multiple code snippets in SeekCode at once and return a result for each item (id, duplicate flag or error)"
+    )]
+    async fn batch_create_snippets(
+        &self,
+        Parameters(request): Parameters<BatchCreateSnippetRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        if !self.permissions.allow_create {
+            return Err(McpError::invalid_params("Create not allowed", None));
+        }
+        ensure_unlocked()?;
+
+        let inputs: Vec<NewSnippet> = request
+            .snippets
+            .into_iter()
+            .map(|item| NewSnippet {
+                title: item.title,
+                language: item.language.unwrap_or_default(),
+                code: item.code,
+                tags: item.tags.unwrap_or_default(),
+            })
+            .collect();
+
+        match snippets::bulk_create(&self.db_pool, inputs).await {
+            Ok(results) => {
+                let succeeded = results.iter().filter(|r| r.success).count();
+                Ok(CallToolResult::success(vec![Content::text(
+                    json!({
+                        "success": true,
+                        "succeeded": succeeded,
+                        "failed": results.len() - succeeded,
+                        "results": results
+                    })
+                    .to_string(),
+                )]))
+            }
+            Err(e) => Err(snippet_error_to_mcp(e, "Failed to create snippets")),
         }
     }
 
@@ -160,8 +371,10 @@ impl SnippetService {
             return Err(McpError::invalid_params("Query not allowed", None));
         }
 
+        ensure_unlocked()?;
+
         let result = sqlx::query(
-            "SELECT id, title, code, language, tags, created_at, updated_at 
+            "SELECT id, title, code, language, tags, collection_id, pinned, use_count, last_used_at, secret_flags, created_at, updated_at 
              FROM code_snippets WHERE id = ?",
         )
         .bind(request.id)
@@ -177,16 +390,26 @@ impl SnippetService {
                     serde_json::from_str(&tags_str).unwrap_or_default()
                 };
 
+                let (code, secret_flags) = screened_code(&row, self.permissions.allow_secrets)?;
                 let snippet = json!({
                     "id": row.get::<i64, _>("id"),
                     "title": row.get::<String, _>("title"),
-                    "code": row.get::<String, _>("code"),
+                    "code": code,
+                    "secret_flags": secret_flags,
                     "language": row.get::<String, _>("language"),
                     "tags": tags,
+                    "collection_id": row.get::<Option<i64>, _>("collection_id"),
+                    "pinned": row.get::<bool, _>("pinned"),
+                    "use_count": row.get::<i64, _>("use_count") + 1,
                     "created_at": row.get::<String, _>("created_at"),
                     "updated_at": row.get::<String, _>("updated_at"),
                 });
 
+                // 通过 MCP 获取视为一次使用
+                if let Err(e) = snippets::record_use(&self.db_pool, request.id).await {
+                    tracing::warn!("failed to record snippet use: {}", e);
+                }
+
                 Ok(CallToolResult::success(vec![Content::text(
                     snippet.to_string(),
                 )]))
@@ -200,7 +423,7 @@ impl SnippetService {
     }
 
     #[tool(
-        description = "Search code snippets in SeekCode with keyword search, language filtering and tag filtering"
+        description = "Search code snippets in SeekCode with keyword search, language filtering, tag filtering, collection filtering and sorting by usage (frecency)"
     )]
     async fn search_snippets(
         &self,
@@ -209,13 +432,26 @@ impl SnippetService {
         if !self.permissions.allow_query {
             return Err(McpError::invalid_params("Query not allowed", None));
         }
+        ensure_unlocked()?;
+        let collection_id =
+            resolve_collection(&self.db_pool, request.collection.as_deref()).await?;
+        let sort = parse_sort(request.sort.as_deref())?;
+        // 加密后的 code 无法在 SQL 中匹配；密钥被遮盖时也不能按原文匹配，否则可借搜索逐字试探。
+        // 这两种情况都改为在内存中按返回内容过滤
+        let filter_in_memory = !request.query.is_empty()
+            && (encryption::is_enabled() || !self.permissions.allow_secrets);
         let mut query_str = String::from(
-            "SELECT id, title, code, language, tags, created_at, updated_at 
+            "SELECT id, title, code, language, tags, collection_id, pinned, use_count, last_used_at, secret_flags, created_at, updated_at 
              FROM code_snippets WHERE 1=1",
         );
         let mut params = Vec::new();
 
-        if !request.query.is_empty() {
+        if let Some(collection_id) = collection_id {
+            query_str.push_str(&format!(" AND collection_id IN ({})", SUBTREE_SQL));
+            params.push(collection_id.to_string());
+        }
+
+        if !request.query.is_empty() && !filter_in_memory {
             query_str.push_str(" AND (title LIKE ? OR code LIKE ?)");
             let search_pattern = format!("%{}%", request.query);
             params.push(search_pattern.clone());
@@ -227,7 +463,15 @@ impl SnippetService {
             params.push(language.clone());
         }
 
-        query_str.push_str(" ORDER BY updated_at DESC LIMIT 50");
+        for tag in request.tags.iter().flatten() {
+            query_str.push_str(" AND LOWER(tags) LIKE ?");
+            params.push(format!("%\"{}\"%", tag.to_lowercase()));
+        }
+
+        query_str.push_str(&format!(" ORDER BY {}", sort.order_by()));
+        if !filter_in_memory {
+            query_str.push_str(" LIMIT 50");
+        }
 
         let mut query = sqlx::query(&query_str);
         for param in params {
@@ -238,27 +482,25 @@ impl SnippetService {
 
         match result {
             Ok(rows) => {
-                let snippets: Vec<serde_json::Value> = rows
-                    .iter()
-                    .map(|row| {
-                        let tags_str: String = row.get("tags");
-                        let tags: Vec<String> = if tags_str.is_empty() {
-                            Vec::new()
-                        } else {
-                            serde_json::from_str(&tags_str).unwrap_or_default()
-                        };
-
-                        json!({
-                            "id": row.get::<i64, _>("id"),
-                            "title": row.get::<String, _>("title"),
-                            "code": row.get::<String, _>("code"),
-                            "language": row.get::<String, _>("language"),
-                            "tags": tags,
-                            "created_at": row.get::<String, _>("created_at"),
-                            "updated_at": row.get::<String, _>("updated_at"),
-                        })
-                    })
-                    .collect();
+                let keyword = request.query.to_lowercase();
+                let mut snippets = Vec::new();
+                for row in &rows {
+                    let snippet = row_to_json(row, self.permissions.allow_secrets)?;
+                    if filter_in_memory {
+                        let matches = ["title", "code"].iter().any(|field| {
+                            snippet[field]
+                                .as_str()
+                                .is_some_and(|text| text.to_lowercase().contains(&keyword))
+                        });
+                        if !matches {
+                            continue;
+                        }
+                    }
+                    snippets.push(snippet);
+                    if snippets.len() == 50 {
+                        break;
+                    }
+                }
 
                 Ok(CallToolResult::success(vec![Content::text(
                     json!({
@@ -380,7 +622,7 @@ impl SnippetService {
     // }
 
     #[tool(
-        description = "List all code snippets from SeekCode with pagination, supporting custom page number and items per page"
+        description = "List all code snippets from SeekCode with pagination, supporting custom page number, items per page, collection filtering and sorting by usage (frecency)"
     )]
     async fn list_snippets(
         &self,
@@ -389,47 +631,51 @@ impl SnippetService {
         if !self.permissions.allow_query {
             return Err(McpError::invalid_params("Query not allowed", None));
         }
+        ensure_unlocked()?;
         let page = request.page.unwrap_or(1);
         let limit = request.limit.unwrap_or(20);
         let offset = (page - 1) * limit;
-
-        let result = sqlx::query(
-            "SELECT id, title, code, language, tags, created_at, updated_at 
-             FROM code_snippets ORDER BY updated_at DESC LIMIT ? OFFSET ?",
-        )
-        .bind(limit as i64)
-        .bind(offset as i64)
-        .fetch_all(&self.db_pool)
-        .await;
+        let collection_id =
+            resolve_collection(&self.db_pool, request.collection.as_deref()).await?;
+        let sort = parse_sort(request.sort.as_deref())?;
+        let where_clause = match collection_id {
+            Some(_) => format!("WHERE collection_id IN ({})", SUBTREE_SQL),
+            None => String::new(),
+        };
+
+        let query_str = format!(
+            "SELECT id, title, code, language, tags, collection_id, pinned, use_count, last_used_at, secret_flags, created_at, updated_at 
+             FROM code_snippets {} ORDER BY {} LIMIT ? OFFSET ?",
+            where_clause,
+            sort.order_by()
+        );
+        let mut query = sqlx::query(&query_str);
+        if let Some(collection_id) = collection_id {
+            query = query.bind(collection_id);
+        }
+        let result = query
+            .bind(limit as i64)
+            .bind(offset as i64)
+            .fetch_all(&self.db_pool)
+            .await;
 
         match result {
             Ok(rows) => {
-                let snippets: Vec<serde_json::Value> = rows
+                let snippets = rows
                     .iter()
-                    .map(|row| {
-                        let tags_str: String = row.get("tags");
-                        let tags: Vec<String> = if tags_str.is_empty() {
-                            Vec::new()
-                        } else {
-                            serde_json::from_str(&tags_str).unwrap_or_default()
-                        };
-
-                        json!({
-                            "id": row.get::<i64, _>("id"),
-                            "title": row.get::<String, _>("title"),
-                            "code": row.get::<String, _>("code"),
-                            "language": row.get::<String, _>("language"),
-                            "tags": tags,
-                            "created_at": row.get::<String, _>("created_at"),
-                            "updated_at": row.get::<String, _>("updated_at"),
-                        })
-                    })
-                    .collect();
+                    .map(|row| row_to_json(row, self.permissions.allow_secrets))
+                    .collect::<Result<Vec<_>, _>>()?;
 
                 // Get total count
-                let count_result = sqlx::query("SELECT COUNT(*) as count FROM code_snippets")
-                    .fetch_one(&self.db_pool)
-                    .await;
+                let count_str = format!(
+                    "SELECT COUNT(*) as count FROM code_snippets {}",
+                    where_clause
+                );
+                let mut count_query = sqlx::query(&count_str);
+                if let Some(collection_id) = collection_id {
+                    count_query = count_query.bind(collection_id);
+                }
+                let count_result = count_query.fetch_one(&self.db_pool).await;
 
                 let total = count_result
                     .map(|row| row.get::<i64, _>("count"))
@@ -452,6 +698,114 @@ impl SnippetService {
             )),
         }
     }
+
+    #[tool(
+        description = "List clipboard history items from SeekCode with pagination, pinned items first and the rest newest first, optionally filtered by keyword or pinned state"
+    )]
+    async fn list_clipboard(
+        &self,
+        Parameters(request): Parameters<ListClipboardRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        if !self.permissions.allow_query {
+            return Err(McpError::invalid_params("Query not allowed", None));
+        }
+        ensure_unlocked()?;
+        let page = request.page.unwrap_or(1).max(1);
+        let limit = request
+            .limit
+            .unwrap_or(20)
+            .clamp(1, MAX_CLIPBOARD_PAGE_SIZE);
+        let offset = i64::try_from((page - 1).saturating_mul(limit)).unwrap_or(i64::MAX);
+        let allow_secrets = self.permissions.allow_secrets;
+        let keyword = request
+            .query
+            .as_deref()
+            .map(str::trim)
+            .filter(|query| !query.is_empty())
+            .map(str::to_lowercase);
+        let query = ClipboardQuery {
+            text: request.query,
+            pinned: request.pinned,
+            offset: Some(offset),
+            limit: Some(limit as i64),
+            ..Default::default()
+        };
+
+        // 密钥被遮盖时不能按原文匹配，否则可借搜索结果数逐字试探，改为按遮盖后的内容匹配
+        let result = match keyword.filter(|_| !allow_secrets) {
+            Some(keyword) => {
+                clipboard::search_matching(&self.db_pool, &query, |item| {
+                    clipboard_content(item, allow_secrets)
+                        .to_lowercase()
+                        .contains(&keyword)
+                })
+                .await
+            }
+            None => clipboard::search(&self.db_pool, &query).await,
+        };
+
+        match result {
+            Ok(result) => {
+                let items: Vec<_> = result
+                    .items
+                    .into_iter()
+                    .map(|item| clipboard_item_to_json(item, allow_secrets))
+                    .collect();
+                Ok(CallToolResult::success(vec![Content::text(
+                    json!({
+                        "items": items,
+                        "page": page,
+                        "limit": result.limit,
+                        "total": result.total,
+                        "pages": (result.total as f64 / result.limit as f64).ceil() as u64
+                    })
+                    .to_string(),
+                )]))
+            }
+            Err(e) => Err(McpError::internal_error(
+                format!("Failed to list clipboard items: {}", e),
+                None,
+            )),
+        }
+    }
+
+    #[tool(
+        description = "Get SeekCode database statistics: snippet and clipboard totals, per-language and per-tag counts, database size, oldest/newest timestamps, growth over the last 30 days and the most used snippets"
+    )]
+    async fn get_stats(&self) -> Result<CallToolResult, McpError> {
+        if !self.permissions.allow_query {
+            return Err(McpError::invalid_params("Query not allowed", None));
+        }
+
+        match stats::collect_stats(&self.db_pool).await {
+            Ok(stats) => Ok(CallToolResult::success(vec![Content::text(
+                json!(stats).to_string(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Failed to get stats: {}", e),
+                None,
+            )),
+        }
+    }
+
+    #[tool(
+        description = "List the collection (folder) hierarchy in SeekCode with the number of snippets in each collection"
+    )]
+    async fn list_collections(&self) -> Result<CallToolResult, McpError> {
+        if !self.permissions.allow_query {
+            return Err(McpError::invalid_params("Query not allowed", None));
+        }
+
+        match collections::list_collections(&self.db_pool).await {
+            Ok(tree) => Ok(CallToolResult::success(vec![Content::text(
+                json!({ "collections": tree }).to_string(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Failed to list collections: {}", e),
+                None,
+            )),
+        }
+    }
 }
 
 #[tool_handler]
@@ -463,7 +817,7 @@ impl ServerHandler for SnippetService {
                 .enable_tools()
                 .build(),
             server_info: Implementation::from_build_env(),
-            instructions: Some("This is the SeekCode MCP server that provides code snippet management features:\n1. Create code snippets in SeekCode - supports title, code content, programming language and tags\n2. Query code snippets from SeekCode - get specific snippets by ID\n3. Search code snippets in SeekCode - supports keyword search, language and tag filtering\n4. List code snippets from SeekCode - supports pagination display\n\nAll tools support detailed parameter descriptions for better understanding and usage.".to_string()),
+            instructions: Some("This is the SeekCode MCP server that provides code snippet management features:\n1. Create code snippets in SeekCode - supports title, code content, programming language and tags, individually or in batches\n2. Query code snippets from SeekCode - get specific snippets by ID\n3. Search code snippets in SeekCode - supports keyword search, language, tag and collection filtering\n4. List code snippets from SeekCode - supports pagination display and collection filtering\n5. List collections in SeekCode - shows the nested collection (folder) hierarchy\n6. Get statistics from SeekCode - totals, language and tag distribution, growth and most used snippets\n7. List clipboard history from SeekCode - pinned items first, with keyword and pinned filtering\n\nWhen the SeekCode database is encrypted and locked, snippet and clipboard tools return an error until it is unlocked in the app.\n\nSnippets and clipboard items that contain detected secrets (API keys, tokens, private keys, passwords) are returned with those values replaced by [REDACTED:<kind>] and listed in secret_flags, unless the server was started with secret access allowed.\n\nAll tools support detailed parameter descriptions for better understanding and usage.".to_string()),
         }
     }
 
@@ -488,6 +842,7 @@ lazy_static::lazy_static! {
 
 pub struct ServerHandle {
     pub addr: SocketAddr,
+    pub permissions: ServerPermissions,
     pub shutdown_tx: oneshot::Sender<()>,
 }
 
@@ -498,6 +853,7 @@ pub async fn start_server_with_permissions(
     allow_create: bool,
     allow_update: bool,
     allow_delete: bool,
+    allow_secrets: bool,
 ) -> Result<SocketAddr> {
     let addr = SocketAddr::from(([127, 0, 0, 1], port));
     let permissions = ServerPermissions {
@@ -505,8 +861,9 @@ pub async fn start_server_with_permissions(
         allow_create,
         allow_update,
         allow_delete,
+        allow_secrets,
     };
-    let snippet_service = SnippetService::new(db_pool, permissions);
+    let snippet_service = SnippetService::new(db_pool, permissions.clone());
 
     // 创建 SSE 服务器配置
     let config = SseServerConfig {
@@ -533,7 +890,11 @@ pub async fn start_server_with_permissions(
     let server_ct = ct.child_token();
 
     // 存储服务器句柄
-    let server_handle = ServerHandle { addr, shutdown_tx };
+    let server_handle = ServerHandle {
+        addr,
+        permissions,
+        shutdown_tx,
+    };
 
     {
         let mut status = SERVER_STATUS.write().await;
@@ -551,9 +912,14 @@ pub async fn start_server_with_permissions(
             eprintln!("服务器错误: {}", e);
         }
 
-        // 清理状态
+        // 清理状态（已被 stop_server 取走并重新启动时，不能清掉新服务器的句柄）
         let mut status = SERVER_STATUS.write().await;
-        *status = None;
+        if status
+            .as_ref()
+            .is_some_and(|handle| handle.shutdown_tx.is_closed())
+        {
+            *status = None;
+        }
     });
 
     Ok(addr)
@@ -562,7 +928,7 @@ pub async fn start_server_with_permissions(
 // 保持向后兼容的原始启动函数
 #[allow(dead_code)]
 pub async fn start_server(db_pool: SqlitePool, port: u16) -> Result<SocketAddr> {
-    start_server_with_permissions(db_pool, port, true, true, false, false).await
+    start_server_with_permissions(db_pool, port, true, true, false, false, false).await
 }
 
 pub async fn stop_server() -> Result<()> {
@@ -584,3 +950,11 @@ pub async fn get_server_address() -> Option<SocketAddr> {
     let status = SERVER_STATUS.read().await;
     status.as_ref().map(|handle| handle.addr)
 }
+
+/// 正在运行的服务器地址与权限，用于暂停后按原配置重新启动
+pub async fn get_server_config() -> Option<(SocketAddr, ServerPermissions)> {
+    let status = SERVER_STATUS.read().await;
+    status
+        .as_ref()
+        .map(|handle| (handle.addr, handle.permissions.clone()))
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 /// 数据库中的代码片段模型
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
 pub struct CodeSnippet {
     pub id: i64,
     pub title: String,
@@ -10,6 +10,148 @@ pub struct CodeSnippet {
     pub tags: String, // JSON 字符串格式存储标签数组
     pub created_at: String,
     pub updated_at: String,
+    /// 所属集合，未分类时为空
+    #[serde(default)]
+    #[sqlx(default)]
+    pub collection_id: Option<i64>,
+    /// 是否置顶
+    #[serde(default)]
+    #[sqlx(default)]
+    pub pinned: bool,
+    /// 使用次数（复制、插入、通过 MCP 获取）
+    #[serde(default)]
+    #[sqlx(default)]
+    pub use_count: i64,
+    #[serde(default)]
+    #[sqlx(default)]
+    pub last_used_at: Option<String>,
+    /// 检测到的密钥类型（JSON 数组），为空表示尚未扫描
+    #[serde(default)]
+    #[sqlx(default)]
+    pub secret_flags: Option<String>,
+}
+
+/// 剪贴板历史条目
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClipboardItem {
+    pub id: i64,
+    /// 文本形式：文本、HTML 的纯文本、图片说明或文件路径列表（每行一个）
+    pub content: String,
+    pub created_at: String,
+    pub updated_at: String,
+    /// 内容类型：text、html、image、files
+    #[serde(default = "default_clipboard_kind")]
+    pub kind: String,
+    #[serde(default = "default_clipboard_mime_type")]
+    pub mime_type: String,
+    /// 图片缩略图（PNG data URL）
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+    /// 检测到的密钥类型（JSON 数组），为空表示尚未扫描
+    #[serde(default)]
+    pub secret_flags: Option<String>,
+    /// 自动检测的编程语言及置信度（仅文本和 HTML 条目）
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub language_confidence: Option<f64>,
+    /// 置顶的条目排在最前，且不受保留策略清理
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+fn default_clipboard_kind() -> String {
+    "text".to_string()
+}
+
+fn default_clipboard_mime_type() -> String {
+    "text/plain".to_string()
+}
+
+/// 代码片段使用统计
+#[derive(Debug, Serialize, Clone, sqlx::FromRow)]
+pub struct SnippetUsage {
+    pub id: i64,
+    pub title: String,
+    pub language: String,
+    pub pinned: bool,
+    pub use_count: i64,
+    pub last_used_at: Option<String>,
+}
+
+/// 代码片段集合（可嵌套的文件夹）
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct Collection {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub sort_order: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 集合树节点
+#[derive(Debug, Serialize)]
+pub struct CollectionNode {
+    #[serde(flatten)]
+    pub collection: Collection,
+    /// 直接属于该集合的代码片段数（不含子集合）
+    pub snippet_count: i64,
+    pub children: Vec<CollectionNode>,
+}
+
+/// 内容相同（忽略空白差异）的一组代码片段
+#[derive(Debug, Serialize)]
+pub struct DuplicateGroup {
+    pub content_hash: String,
+    pub snippets: Vec<CodeSnippet>,
+}
+
+/// 批量操作中单个条目的结果
+#[derive(Debug, Serialize)]
+pub struct BatchItemResult {
+    pub index: usize,
+    pub success: bool,
+    pub id: Option<i64>,
+    pub duplicate: bool,
+    pub error: Option<String>,
+}
+
+impl BatchItemResult {
+    pub fn ok(index: usize, id: i64, duplicate: bool) -> Self {
+        Self {
+            index,
+            success: true,
+            id: Some(id),
+            duplicate,
+            error: None,
+        }
+    }
+
+    pub fn failed(index: usize, error: String) -> Self {
+        Self {
+            index,
+            success: false,
+            id: None,
+            duplicate: false,
+            error: Some(error),
+        }
+    }
+}
+
+/// 按名称统计的数量（语言、标签）
+#[derive(Debug, Serialize)]
+pub struct NamedCount {
+    pub name: String,
+    pub count: i64,
+}
+
+/// 某一天新增的数据量
+#[derive(Debug, Serialize)]
+pub struct DailyGrowth {
+    pub date: String,
+    pub snippets: i64,
+    pub clipboard_items: i64,
 }
 
 /// 数据库统计信息
@@ -18,6 +160,24 @@ pub struct DatabaseStats {
     pub total_snippets: i64,
     pub total_clipboard_items: i64,
     pub languages: Vec<String>,
+    /// 各语言的代码片段数，按数量倒序
+    pub language_counts: Vec<NamedCount>,
+    /// 各标签的代码片段数，按数量倒序
+    pub tag_counts: Vec<NamedCount>,
+    /// 数据库文件大小（字节）
+    pub db_size_bytes: i64,
+    pub oldest_snippet_at: Option<String>,
+    pub newest_snippet_at: Option<String>,
+    pub oldest_clipboard_at: Option<String>,
+    pub newest_clipboard_at: Option<String>,
+    /// 最近 30 天新增的代码片段数
+    pub snippets_last_30_days: i64,
+    /// 最近 30 天新增的剪贴板条目数
+    pub clipboard_items_last_30_days: i64,
+    /// 最近 30 天每天的新增数量（按日期升序，包含没有新增的日期）
+    pub daily_growth: Vec<DailyGrowth>,
+    /// 使用次数最多的代码片段
+    pub top_snippets: Vec<SnippetUsage>,
 }
 
 /// MCP 服务器配置
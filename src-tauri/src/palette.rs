@@ -0,0 +1,236 @@
+use crate::clipboard::{self, ClipboardQuery};
+use crate::db::get_setting;
+use crate::i18n::t_with;
+use crate::snippets::{self, SnippetFilter, SnippetSort};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// 快速粘贴面板的窗口标签
+pub const PALETTE_WINDOW: &str = "palette";
+/// 默认的全局快捷键，对应 user_settings 中的 palette_shortcut（为空表示不注册）
+pub const DEFAULT_PALETTE_SHORTCUT: &str = "CommandOrControl+Shift+Space";
+/// 每类结果默认返回的条数
+const DEFAULT_RESULT_LIMIT: usize = 20;
+/// 预览文本的最大字符数
+const PREVIEW_LENGTH: usize = 120;
+
+lazy_static::lazy_static! {
+    /// 当前注册的快捷键
+    static ref REGISTERED: Mutex<Option<Shortcut>> = Mutex::new(None);
+}
+
+// ============================================================================
+// 快捷键
+// ============================================================================
+
+/// 读取配置的快捷键，未设置时使用默认值
+pub async fn load_shortcut(pool: &SqlitePool) -> String {
+    get_setting(pool, "palette_shortcut")
+        .await
+        .map(|value| value.trim().to_string())
+        .unwrap_or_else(|| DEFAULT_PALETTE_SHORTCUT.to_string())
+}
+
+/// 解析快捷键，如 "CommandOrControl+Shift+Space"
+pub fn parse_shortcut(value: &str) -> Result<Shortcut, String> {
+    value.trim().parse::<Shortcut>().map_err(|e| {
+        t_with(
+            "errors.invalidShortcut",
+            &[("shortcut", &value.trim()), ("error", &e)],
+        )
+    })
+}
+
+/// 注册全局快捷键（替换之前注册的快捷键），为空时只注销
+pub fn register_shortcut(app: &AppHandle, value: &str) -> Result<(), String> {
+    let shortcut = match value.trim() {
+        "" => None,
+        value => Some(parse_shortcut(value)?),
+    };
+
+    let mut registered = REGISTERED.lock().unwrap_or_else(|e| e.into_inner());
+    if *registered == shortcut {
+        return Ok(());
+    }
+    if let Some(previous) = registered.take() {
+        app.global_shortcut()
+            .unregister(previous)
+            .map_err(|e| t_with("errors.unregisterShortcutFailed", &[("error", &e)]))?;
+    }
+    if let Some(shortcut) = shortcut {
+        app.global_shortcut()
+            .on_shortcut(shortcut, |app, _, event| {
+                if event.state == ShortcutState::Pressed {
+                    if let Err(e) = toggle(app) {
+                        tracing::warn!("failed to open quick paste palette: {}", e);
+                    }
+                }
+            })
+            .map_err(|e| t_with("errors.registerShortcutFailed", &[("error", &e)]))?;
+        *registered = Some(shortcut);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// 面板窗口
+// ============================================================================
+
+/// 显示面板（首次使用时创建），并通知前端清空搜索框
+pub fn show(app: &AppHandle) -> Result<(), String> {
+    let window = match app.get_webview_window(PALETTE_WINDOW) {
+        Some(window) => window,
+        None => {
+            let window = WebviewWindowBuilder::new(
+                app,
+                PALETTE_WINDOW,
+                WebviewUrl::App("index.html?window=palette".into()),
+            )
+            .title("SeekCode")
+            .inner_size(640.0, 420.0)
+            .decorations(false)
+            .resizable(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .visible(false)
+            .center()
+            .build()
+            .map_err(|e| t_with("errors.showPaletteFailed", &[("error", &e)]))?;
+
+            // 失去焦点时隐藏
+            let handle = window.clone();
+            window.on_window_event(move |event| {
+                if let WindowEvent::Focused(false) = event {
+                    let _ = handle.hide();
+                }
+            });
+            window
+        }
+    };
+
+    window
+        .center()
+        .and_then(|_| window.show())
+        .and_then(|_| window.set_focus())
+        .map_err(|e| t_with("errors.showPaletteFailed", &[("error", &e)]))?;
+    let _ = window.emit("palette://shown", ());
+    Ok(())
+}
+
+/// 隐藏面板
+pub fn hide(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(PALETTE_WINDOW) {
+        window
+            .hide()
+            .map_err(|e| t_with("errors.hidePaletteFailed", &[("error", &e)]))?;
+    }
+    Ok(())
+}
+
+/// 面板可见时隐藏，否则显示
+pub fn toggle(app: &AppHandle) -> Result<(), String> {
+    let visible = app
+        .get_webview_window(PALETTE_WINDOW)
+        .and_then(|window| window.is_visible().ok())
+        .unwrap_or(false);
+    if visible {
+        hide(app)
+    } else {
+        show(app)
+    }
+}
+
+// ============================================================================
+// 搜索
+// ============================================================================
+
+/// 面板结果来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaletteSource {
+    Snippet,
+    Clipboard,
+}
+
+/// 面板中的一条结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PaletteItem {
+    pub source: PaletteSource,
+    pub id: i64,
+    /// 代码片段标题，剪贴板条目为内容的第一行
+    pub title: String,
+    pub preview: String,
+    pub language: Option<String>,
+    /// 剪贴板内容类型，代码片段为 None
+    pub kind: Option<String>,
+    pub pinned: bool,
+}
+
+fn preview(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(PREVIEW_LENGTH) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+/// 搜索代码片段（置顶和常用的在前）和剪贴板历史（置顶和最新的在前）
+pub async fn search(
+    pool: &SqlitePool,
+    text: &str,
+    limit: Option<usize>,
+) -> Result<Vec<PaletteItem>, String> {
+    let limit = limit.unwrap_or(DEFAULT_RESULT_LIMIT).max(1);
+    let keyword = text.trim().to_lowercase();
+
+    let filter = SnippetFilter {
+        sort: SnippetSort::Frecency,
+        ..Default::default()
+    };
+    let mut items: Vec<PaletteItem> = snippets::query_snippets(pool, &filter)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|snippet| {
+            keyword.is_empty()
+                || [&snippet.title, &snippet.code, &snippet.tags]
+                    .iter()
+                    .any(|text| text.to_lowercase().contains(&keyword))
+        })
+        .take(limit)
+        .map(|snippet| PaletteItem {
+            source: PaletteSource::Snippet,
+            id: snippet.id,
+            preview: preview(&snippet.code),
+            title: snippet.title,
+            language: Some(snippet.language),
+            kind: None,
+            pinned: snippet.pinned,
+        })
+        .collect();
+
+    let query = ClipboardQuery {
+        text: Some(keyword).filter(|keyword| !keyword.is_empty()),
+        limit: Some(limit as i64),
+        ..Default::default()
+    };
+    items.extend(
+        clipboard::search(pool, &query)
+            .await?
+            .items
+            .into_iter()
+            .map(|item| PaletteItem {
+                source: PaletteSource::Clipboard,
+                id: item.id,
+                title: preview(item.content.lines().next().unwrap_or_default()),
+                preview: preview(&item.content),
+                language: item.language,
+                kind: Some(item.kind),
+                pinned: item.pinned,
+            }),
+    );
+    Ok(items)
+}
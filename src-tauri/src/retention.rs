@@ -0,0 +1,249 @@
+use crate::clipboard::DEFAULT_MAX_CLIPBOARD_ITEMS;
+use crate::db::{get_setting, now_timestamp, set_setting};
+use crate::i18n::{t, t_with};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+/// 后台清理任务的执行间隔
+const RETENTION_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// 单条剪贴板条目占用的空间（内容、原始数据和缩略图）
+const ITEM_SIZE_SQL: &str =
+    "length(CAST(content AS BLOB)) + IFNULL(length(data), 0) + IFNULL(length(thumbnail), 0)";
+
+// ============================================================================
+// 保留策略
+// ============================================================================
+
+/// 剪贴板历史保留策略，各项分别存储在 user_settings 中；为 0 表示不限制
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// 最多保留的条数（max_clipboard_items）
+    pub max_items: i64,
+    /// 最长保留天数（clipboard_max_age_days）
+    #[serde(default)]
+    pub max_age_days: i64,
+    /// 历史占用空间上限，单位 MB（clipboard_max_total_mb）
+    #[serde(default)]
+    pub max_total_mb: i64,
+    /// 置顶的条目不受以上限制，也不计入条数和空间（clipboard_keep_pinned）
+    #[serde(default = "default_keep_pinned")]
+    pub keep_pinned: bool,
+}
+
+fn default_keep_pinned() -> bool {
+    true
+}
+
+fn parse_limit(value: Option<String>) -> Option<i64> {
+    value
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .filter(|limit| *limit >= 0)
+}
+
+impl RetentionPolicy {
+    /// 从 user_settings 读取策略
+    pub async fn load(pool: &SqlitePool) -> Self {
+        Self {
+            max_items: parse_limit(get_setting(pool, "max_clipboard_items").await)
+                .unwrap_or(DEFAULT_MAX_CLIPBOARD_ITEMS),
+            max_age_days: parse_limit(get_setting(pool, "clipboard_max_age_days").await)
+                .unwrap_or(0),
+            max_total_mb: parse_limit(get_setting(pool, "clipboard_max_total_mb").await)
+                .unwrap_or(0),
+            keep_pinned: get_setting(pool, "clipboard_keep_pinned").await.as_deref()
+                != Some("false"),
+        }
+    }
+
+    /// 校验后写入 user_settings（单个事务）
+    pub async fn save(self, pool: &SqlitePool) -> Result<Self, String> {
+        if self.max_items < 0 || self.max_age_days < 0 || self.max_total_mb < 0 {
+            return Err(t("errors.negativeRetention"));
+        }
+
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        set_setting(&mut *tx, "max_clipboard_items", &self.max_items.to_string()).await?;
+        set_setting(
+            &mut *tx,
+            "clipboard_max_age_days",
+            &self.max_age_days.to_string(),
+        )
+        .await?;
+        set_setting(
+            &mut *tx,
+            "clipboard_max_total_mb",
+            &self.max_total_mb.to_string(),
+        )
+        .await?;
+        set_setting(
+            &mut *tx,
+            "clipboard_keep_pinned",
+            if self.keep_pinned { "true" } else { "false" },
+        )
+        .await?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+        Ok(self)
+    }
+}
+
+// ============================================================================
+// 清理
+// ============================================================================
+
+/// 一次清理的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionReport {
+    pub deleted_by_age: u64,
+    pub deleted_by_count: u64,
+    pub deleted_by_size: u64,
+    /// 清理后剩余的条数
+    pub remaining: i64,
+    pub ran_at: String,
+}
+
+lazy_static::lazy_static! {
+    static ref RETENTION_TASK: Mutex<Option<CancellationToken>> = Mutex::new(None);
+    static ref LAST_REPORT: Mutex<Option<RetentionReport>> = Mutex::new(None);
+}
+
+/// 按策略删除剪贴板历史：依次按保留天数、条数和占用空间删除最旧的条目
+pub async fn enforce(
+    pool: &SqlitePool,
+    policy: &RetentionPolicy,
+) -> Result<RetentionReport, String> {
+    let candidates = if policy.keep_pinned {
+        "pinned = 0"
+    } else {
+        "1 = 1"
+    };
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let deleted_by_age = if policy.max_age_days > 0 {
+        let cutoff = (chrono::Local::now() - chrono::Duration::days(policy.max_age_days))
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string();
+        sqlx::query(&format!(
+            "DELETE FROM clipboard_items WHERE {} AND created_at < ?",
+            candidates
+        ))
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| t_with("errors.deleteOldClipboardFailed", &[("error", &e)]))?
+        .rows_affected()
+    } else {
+        0
+    };
+
+    let deleted_by_count = if policy.max_items > 0 {
+        sqlx::query(&format!(
+            "DELETE FROM clipboard_items WHERE {0} AND id NOT IN
+             (SELECT id FROM clipboard_items WHERE {0} ORDER BY created_at DESC, id DESC LIMIT ?)",
+            candidates
+        ))
+        .bind(policy.max_items)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| t_with("errors.trimClipboardFailed", &[("error", &e)]))?
+        .rows_affected()
+    } else {
+        0
+    };
+
+    // 从最新的条目开始累计空间，删除超出上限的部分
+    let deleted_by_size = if policy.max_total_mb > 0 {
+        sqlx::query(&format!(
+            "DELETE FROM clipboard_items WHERE id IN (
+                 SELECT id FROM (
+                     SELECT id, SUM({1}) OVER (ORDER BY created_at DESC, id DESC) AS running
+                     FROM clipboard_items WHERE {0}
+                 ) WHERE running > ?
+             )",
+            candidates, ITEM_SIZE_SQL
+        ))
+        .bind(policy.max_total_mb * 1024 * 1024)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| t_with("errors.trimClipboardFailed", &[("error", &e)]))?
+        .rows_affected()
+    } else {
+        0
+    };
+
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM clipboard_items")
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let report = RetentionReport {
+        deleted_by_age,
+        deleted_by_count,
+        deleted_by_size,
+        remaining,
+        ran_at: now_timestamp(),
+    };
+    *LAST_REPORT.lock().await = Some(report.clone());
+    Ok(report)
+}
+
+/// 读取当前策略并清理
+pub async fn run(pool: &SqlitePool) -> Result<RetentionReport, String> {
+    let policy = RetentionPolicy::load(pool).await;
+    enforce(pool, &policy).await
+}
+
+/// 最近一次清理的结果
+pub async fn last_report() -> Option<RetentionReport> {
+    LAST_REPORT.lock().await.clone()
+}
+
+// ============================================================================
+// 后台任务
+// ============================================================================
+
+/// 启动后台清理任务：启动时立即清理一次，之后定期按最新策略清理
+///
+/// 删除了条目时调用 on_change（用于刷新托盘菜单等）。
+pub async fn start(pool: SqlitePool, on_change: impl Fn() + Send + 'static) {
+    stop().await;
+
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RETENTION_CHECK_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+            match run(&pool).await {
+                Ok(report) => {
+                    tracing::info!(
+                        "clipboard retention: removed {} by age, {} by count, {} by size",
+                        report.deleted_by_age,
+                        report.deleted_by_count,
+                        report.deleted_by_size
+                    );
+                    if report.deleted_by_age + report.deleted_by_count + report.deleted_by_size > 0
+                    {
+                        on_change();
+                    }
+                }
+                Err(e) => tracing::warn!("clipboard retention failed: {}", e),
+            }
+        }
+    });
+
+    *RETENTION_TASK.lock().await = Some(cancel);
+}
+
+/// 停止后台清理任务
+pub async fn stop() {
+    if let Some(cancel) = RETENTION_TASK.lock().await.take() {
+        cancel.cancel();
+    }
+}
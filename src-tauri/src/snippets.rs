@@ -0,0 +1,784 @@
+use crate::collections::SUBTREE_SQL;
+use crate::db::{get_setting, now_timestamp};
+use crate::encryption;
+use crate::i18n::t_with;
+use crate::language_detect;
+use crate::models::{BatchItemResult, CodeSnippet, DuplicateGroup, SnippetUsage};
+use crate::secrets::{self, SecretAction};
+use crate::validation::{normalize_tags, normalize_title, validate_code, SnippetLimits};
+use serde::Deserialize;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// 代码片段写入错误
+#[derive(Debug)]
+pub enum SnippetError {
+    /// 参数校验失败
+    Invalid(String),
+    /// 代码片段不存在
+    NotFound(i64),
+    /// 已存在相同内容的代码片段
+    Duplicate(i64),
+    /// 数据库已加密但未解锁，或解密失败
+    Encryption(String),
+    /// 数据库错误
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for SnippetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnippetError::Invalid(msg) => {
+                write!(f, "{}", t_with("errors.invalidSnippet", &[("error", msg)]))
+            }
+            SnippetError::NotFound(id) => {
+                write!(f, "{}", t_with("errors.snippetNotFound", &[("id", id)]))
+            }
+            SnippetError::Duplicate(id) => {
+                write!(f, "{}", t_with("errors.duplicateSnippet", &[("id", id)]))
+            }
+            SnippetError::Encryption(msg) => write!(f, "{}", msg),
+            SnippetError::Database(e) => {
+                write!(f, "{}", t_with("errors.databaseError", &[("error", e)]))
+            }
+        }
+    }
+}
+
+impl From<sqlx::Error> for SnippetError {
+    fn from(e: sqlx::Error) -> Self {
+        SnippetError::Database(e)
+    }
+}
+
+/// 新建代码片段的输入
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewSnippet {
+    pub title: String,
+    /// 为空或 "auto" 时根据代码内容检测
+    #[serde(default)]
+    pub language: String,
+    pub code: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// 更新代码片段的输入，未提供的字段保持不变
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SnippetPatch {
+    pub title: Option<String>,
+    /// "auto" 表示根据（更新后的）代码重新检测
+    pub language: Option<String>,
+    pub code: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+/// 创建代码片段的结果
+#[derive(Debug)]
+pub enum CreateOutcome {
+    /// 新建了代码片段
+    Created(CodeSnippet),
+    /// 已存在相同内容的代码片段，返回已有记录
+    Existing(CodeSnippet),
+}
+
+impl CreateOutcome {
+    pub fn into_snippet(self) -> CodeSnippet {
+        match self {
+            CreateOutcome::Created(snippet) | CreateOutcome::Existing(snippet) => snippet,
+        }
+    }
+}
+
+/// 重复内容的处理策略，对应 user_settings 中的 duplicate_snippet_policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// 返回已有的代码片段（默认）
+    ReturnExisting,
+    /// 返回错误
+    Error,
+}
+
+impl DuplicatePolicy {
+    pub async fn load(pool: &SqlitePool) -> Self {
+        match get_setting(pool, "duplicate_snippet_policy")
+            .await
+            .as_deref()
+        {
+            Some("error") => DuplicatePolicy::Error,
+            _ => DuplicatePolicy::ReturnExisting,
+        }
+    }
+}
+
+/// 计算哈希前的规范化：空白字符统一折叠
+pub fn normalize_for_hash(code: &str) -> String {
+    code.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 计算代码内容哈希：规范化后取 SHA-256（启用加密时为 HMAC）
+pub fn content_hash(code: &str) -> String {
+    encryption::content_digest(code)
+}
+
+/// 解密从数据库读出的代码片段
+pub fn decrypt_snippet(mut snippet: CodeSnippet) -> Result<CodeSnippet, SnippetError> {
+    snippet.code = encryption::open(&snippet.code).map_err(SnippetError::Encryption)?;
+    Ok(snippet)
+}
+
+fn seal_code(code: &str) -> Result<String, SnippetError> {
+    encryption::seal(code).map_err(SnippetError::Encryption)
+}
+
+/// 按处理方式筛查代码中的密钥：跳过时拒绝写入，遮盖时返回遮盖后的代码
+pub fn screen_code(code: String, action: SecretAction) -> Result<String, SnippetError> {
+    let findings = secrets::scan(&code);
+    if findings.is_empty() {
+        return Ok(code);
+    }
+    match action {
+        SecretAction::Skip => {
+            let kinds: Vec<&str> = secrets::kinds(&findings)
+                .into_iter()
+                .map(|kind| kind.as_str())
+                .collect();
+            Err(SnippetError::Invalid(t_with(
+                "errors.codeContainsSecrets",
+                &[("kinds", &kinds.join(", "))],
+            )))
+        }
+        SecretAction::Redact => Ok(secrets::redact(&code, &findings)),
+        SecretAction::Flag => Ok(code),
+    }
+}
+
+/// 校验并规范化新建输入
+pub fn validate_new(input: NewSnippet, limits: &SnippetLimits) -> Result<NewSnippet, SnippetError> {
+    let title = normalize_title(&input.title).map_err(SnippetError::Invalid)?;
+    validate_code(&input.code, limits).map_err(SnippetError::Invalid)?;
+    // 标题可能是文件名（如 main.rs），作为扩展名提示
+    let language = language_detect::resolve(&input.language, &input.code, Some(&title))
+        .map_err(SnippetError::Invalid)?;
+    let tags = normalize_tags(&input.tags).map_err(SnippetError::Invalid)?;
+
+    Ok(NewSnippet {
+        title,
+        language,
+        code: input.code,
+        tags,
+    })
+}
+
+/// 根据 ID 获取代码片段
+pub async fn get_snippet(
+    conn: &mut SqliteConnection,
+    id: i64,
+) -> Result<Option<CodeSnippet>, SnippetError> {
+    let snippet = sqlx::query_as::<_, CodeSnippet>(
+        "SELECT id, title, language, code, tags, created_at, updated_at, collection_id,
+                pinned, use_count, last_used_at, secret_flags
+         FROM code_snippets WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(conn)
+    .await?;
+    snippet.map(decrypt_snippet).transpose()
+}
+
+/// 代码片段查询条件
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SnippetFilter {
+    /// 只返回指定语言
+    pub language: Option<String>,
+    /// 只返回包含全部指定标签的代码片段
+    pub tags: Option<Vec<String>>,
+    /// 只返回指定集合（含子集合）中的代码片段
+    pub collection_id: Option<i64>,
+    /// 只返回置顶（true）或未置顶（false）的代码片段
+    pub pinned: Option<bool>,
+    /// 排序方式，默认按更新时间倒序
+    #[serde(default)]
+    pub sort: SnippetSort,
+}
+
+/// 按使用频率和最近使用时间计算的得分：使用次数 / (1 + 距上次使用的天数 / 7)
+pub const FRECENCY_SCORE_SQL: &str = "use_count * 1.0 / (1.0 + (julianday('now', 'localtime') - julianday(COALESCE(last_used_at, created_at))) / 7.0)";
+
+/// 代码片段排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnippetSort {
+    /// 按更新时间倒序
+    #[default]
+    Updated,
+    /// 按创建时间倒序
+    Created,
+    /// 按标题排序
+    Title,
+    /// 按使用次数倒序
+    MostUsed,
+    /// 置顶优先，其余按 frecency 得分倒序
+    Frecency,
+}
+
+impl SnippetSort {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "updated" => Some(SnippetSort::Updated),
+            "created" => Some(SnippetSort::Created),
+            "title" => Some(SnippetSort::Title),
+            "most_used" => Some(SnippetSort::MostUsed),
+            "frecency" => Some(SnippetSort::Frecency),
+            _ => None,
+        }
+    }
+
+    /// ORDER BY 子句（不含 ORDER BY 关键字）
+    pub fn order_by(self) -> String {
+        match self {
+            SnippetSort::Updated => "updated_at DESC".to_string(),
+            SnippetSort::Created => "created_at DESC".to_string(),
+            SnippetSort::Title => "title COLLATE NOCASE ASC".to_string(),
+            SnippetSort::MostUsed => "use_count DESC, last_used_at DESC".to_string(),
+            SnippetSort::Frecency => {
+                format!("pinned DESC, {} DESC, updated_at DESC", FRECENCY_SCORE_SQL)
+            }
+        }
+    }
+}
+
+/// 按条件查询代码片段
+pub async fn query_snippets(
+    pool: &SqlitePool,
+    filter: &SnippetFilter,
+) -> Result<Vec<CodeSnippet>, SnippetError> {
+    let mut query_str = String::from(
+        "SELECT id, title, language, code, tags, created_at, updated_at, collection_id,
+                pinned, use_count, last_used_at, secret_flags
+         FROM code_snippets WHERE 1=1",
+    );
+    let mut params = Vec::new();
+
+    if let Some(collection_id) = filter.collection_id {
+        query_str.push_str(&format!(" AND collection_id IN ({})", SUBTREE_SQL));
+        params.push(collection_id.to_string());
+    }
+    if let Some(language) = &filter.language {
+        query_str.push_str(" AND language = ?");
+        params.push(language.clone());
+    }
+    for tag in filter.tags.iter().flatten() {
+        query_str.push_str(" AND LOWER(tags) LIKE ?");
+        params.push(format!("%\"{}\"%", tag.to_lowercase()));
+    }
+    if let Some(pinned) = filter.pinned {
+        query_str.push_str(if pinned {
+            " AND pinned = 1"
+        } else {
+            " AND pinned = 0"
+        });
+    }
+    query_str.push_str(&format!(" ORDER BY {}", filter.sort.order_by()));
+
+    let mut query = sqlx::query_as::<_, CodeSnippet>(&query_str);
+    for param in params {
+        query = query.bind(param);
+    }
+    query
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(decrypt_snippet)
+        .collect()
+}
+
+/// 按关键词搜索标题、代码和标签（不区分大小写），按创建时间倒序
+///
+/// 代码可能已加密，因此解密后在内存中匹配。
+pub async fn search_snippets(
+    pool: &SqlitePool,
+    query: &str,
+) -> Result<Vec<CodeSnippet>, SnippetError> {
+    let filter = SnippetFilter {
+        sort: SnippetSort::Created,
+        ..Default::default()
+    };
+    let keyword = query.trim().to_lowercase();
+    Ok(query_snippets(pool, &filter)
+        .await?
+        .into_iter()
+        .filter(|snippet| {
+            [&snippet.title, &snippet.code, &snippet.tags]
+                .iter()
+                .any(|text| text.to_lowercase().contains(&keyword))
+        })
+        .collect())
+}
+
+/// 插入已校验的代码片段
+pub async fn insert_snippet(
+    conn: &mut SqliteConnection,
+    input: &NewSnippet,
+) -> Result<CodeSnippet, SnippetError> {
+    let now = now_timestamp();
+    insert_snippet_at(conn, input, &now, &now).await
+}
+
+/// 插入已校验的代码片段，使用指定的创建/更新时间（用于导入等保留原始时间的场景）
+pub async fn insert_snippet_at(
+    conn: &mut SqliteConnection,
+    input: &NewSnippet,
+    created_at: &str,
+    updated_at: &str,
+) -> Result<CodeSnippet, SnippetError> {
+    let tags_json = serde_json::to_string(&input.tags).unwrap_or_else(|_| "[]".to_string());
+    let stored_code = seal_code(&input.code)?;
+    let secret_flags = secrets::flags_json(&input.code);
+
+    let result = sqlx::query(
+        "INSERT INTO code_snippets (title, language, code, tags, content_hash, secret_flags, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&input.title)
+    .bind(&input.language)
+    .bind(&stored_code)
+    .bind(&tags_json)
+    .bind(content_hash(&input.code))
+    .bind(&secret_flags)
+    .bind(created_at)
+    .bind(updated_at)
+    .execute(conn)
+    .await?;
+
+    Ok(CodeSnippet {
+        id: result.last_insert_rowid(),
+        title: input.title.clone(),
+        language: input.language.clone(),
+        code: input.code.clone(),
+        tags: tags_json,
+        created_at: created_at.to_string(),
+        updated_at: updated_at.to_string(),
+        collection_id: None,
+        pinned: false,
+        use_count: 0,
+        last_used_at: None,
+        secret_flags: Some(secret_flags),
+    })
+}
+
+/// 将更新应用到已有代码片段并写回数据库
+pub async fn apply_patch(
+    conn: &mut SqliteConnection,
+    id: i64,
+    patch: SnippetPatch,
+    limits: &SnippetLimits,
+) -> Result<CodeSnippet, SnippetError> {
+    let current = get_snippet(&mut *conn, id)
+        .await?
+        .ok_or(SnippetError::NotFound(id))?;
+
+    let title = match &patch.title {
+        Some(title) => normalize_title(title).map_err(SnippetError::Invalid)?,
+        None => current.title,
+    };
+    let code = match patch.code {
+        Some(code) => {
+            validate_code(&code, limits).map_err(SnippetError::Invalid)?;
+            code
+        }
+        None => current.code,
+    };
+    let language = match &patch.language {
+        Some(language) => language_detect::resolve(language, &code, Some(&title))
+            .map_err(SnippetError::Invalid)?,
+        None => current.language,
+    };
+    let tags = match &patch.tags {
+        Some(tags) => {
+            let tags = normalize_tags(tags).map_err(SnippetError::Invalid)?;
+            serde_json::to_string(&tags).unwrap_or_else(|_| "[]".to_string())
+        }
+        None => current.tags,
+    };
+    let now = now_timestamp();
+    let stored_code = seal_code(&code)?;
+    let secret_flags = secrets::flags_json(&code);
+
+    sqlx::query(
+        "UPDATE code_snippets
+         SET title = ?, language = ?, code = ?, tags = ?, content_hash = ?, secret_flags = ?,
+             updated_at = ?
+         WHERE id = ?",
+    )
+    .bind(&title)
+    .bind(&language)
+    .bind(&stored_code)
+    .bind(&tags)
+    .bind(content_hash(&code))
+    .bind(&secret_flags)
+    .bind(&now)
+    .bind(id)
+    .execute(conn)
+    .await?;
+
+    Ok(CodeSnippet {
+        id,
+        title,
+        language,
+        code,
+        tags,
+        created_at: current.created_at,
+        updated_at: now,
+        collection_id: current.collection_id,
+        pinned: current.pinned,
+        use_count: current.use_count,
+        last_used_at: current.last_used_at,
+        secret_flags: Some(secret_flags),
+    })
+}
+
+/// 查找与给定哈希相同内容的代码片段
+pub async fn find_by_hash(
+    conn: &mut SqliteConnection,
+    hash: &str,
+) -> Result<Option<CodeSnippet>, SnippetError> {
+    let snippet = sqlx::query_as::<_, CodeSnippet>(
+        "SELECT id, title, language, code, tags, created_at, updated_at, collection_id,
+                pinned, use_count, last_used_at, secret_flags
+         FROM code_snippets WHERE content_hash = ? ORDER BY id LIMIT 1",
+    )
+    .bind(hash)
+    .fetch_optional(conn)
+    .await?;
+    snippet.map(decrypt_snippet).transpose()
+}
+
+/// 为尚未计算哈希的旧数据补齐 content_hash（启动时执行一次，之后所有写入都会计算哈希）
+pub async fn backfill_content_hashes(conn: &mut SqliteConnection) -> Result<u64, SnippetError> {
+    let rows = sqlx::query("SELECT id, code FROM code_snippets WHERE content_hash IS NULL")
+        .fetch_all(&mut *conn)
+        .await?;
+
+    for row in &rows {
+        let id: i64 = row.get("id");
+        let code = encryption::open(row.get("code")).map_err(SnippetError::Encryption)?;
+        sqlx::query("UPDATE code_snippets SET content_hash = ? WHERE id = ?")
+            .bind(content_hash(&code))
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(rows.len() as u64)
+}
+
+/// 按重复策略插入：存在相同内容时返回已有记录或报错
+pub async fn insert_or_existing(
+    conn: &mut SqliteConnection,
+    input: &NewSnippet,
+    policy: DuplicatePolicy,
+) -> Result<CreateOutcome, SnippetError> {
+    let now = now_timestamp();
+    insert_or_existing_at(conn, input, policy, &now, &now).await
+}
+
+/// 按重复策略插入，使用指定的创建/更新时间
+pub async fn insert_or_existing_at(
+    conn: &mut SqliteConnection,
+    input: &NewSnippet,
+    policy: DuplicatePolicy,
+    created_at: &str,
+    updated_at: &str,
+) -> Result<CreateOutcome, SnippetError> {
+    if let Some(existing) = find_by_hash(&mut *conn, &content_hash(&input.code)).await? {
+        return match policy {
+            DuplicatePolicy::ReturnExisting => Ok(CreateOutcome::Existing(existing)),
+            DuplicatePolicy::Error => Err(SnippetError::Duplicate(existing.id)),
+        };
+    }
+    Ok(CreateOutcome::Created(
+        insert_snippet_at(conn, input, created_at, updated_at).await?,
+    ))
+}
+
+/// 校验并创建代码片段
+pub async fn create_snippet(
+    pool: &SqlitePool,
+    input: NewSnippet,
+) -> Result<CreateOutcome, SnippetError> {
+    let now = now_timestamp();
+    create_snippet_at(pool, input, &now, &now).await
+}
+
+/// 校验并创建代码片段，使用指定的创建/更新时间（如由剪贴板条目转换时保留原始时间）
+pub async fn create_snippet_at(
+    pool: &SqlitePool,
+    input: NewSnippet,
+    created_at: &str,
+    updated_at: &str,
+) -> Result<CreateOutcome, SnippetError> {
+    let limits = SnippetLimits::load(pool).await;
+    let policy = DuplicatePolicy::load(pool).await;
+    let action = SecretAction::for_snippets(pool).await;
+    let input = validate_new(
+        NewSnippet {
+            code: screen_code(input.code, action)?,
+            ..input
+        },
+        &limits,
+    )?;
+    // 立即获取写锁，避免并发创建时两边都查不到重复再各自插入
+    let mut tx = pool.begin_with("BEGIN IMMEDIATE").await?;
+    let outcome = insert_or_existing_at(&mut tx, &input, policy, created_at, updated_at).await?;
+    tx.commit().await?;
+    Ok(outcome)
+}
+
+/// 校验并更新代码片段
+pub async fn update_snippet(
+    pool: &SqlitePool,
+    id: i64,
+    patch: SnippetPatch,
+) -> Result<CodeSnippet, SnippetError> {
+    let limits = SnippetLimits::load(pool).await;
+    let patch = screen_patch(patch, SecretAction::for_snippets(pool).await)?;
+    // 在写事务中加密，与启用/关闭加密的重写事务串行
+    let mut tx = pool.begin_with("BEGIN IMMEDIATE").await?;
+    let snippet = apply_patch(&mut tx, id, patch, &limits).await?;
+    tx.commit().await?;
+    Ok(snippet)
+}
+
+/// 对更新中的新代码应用密钥处理方式
+fn screen_patch(patch: SnippetPatch, action: SecretAction) -> Result<SnippetPatch, SnippetError> {
+    Ok(SnippetPatch {
+        code: patch
+            .code
+            .map(|code| screen_code(code, action))
+            .transpose()?,
+        ..patch
+    })
+}
+
+/// 按内容哈希分组，返回包含多个代码片段的重复组
+pub async fn find_duplicates(pool: &SqlitePool) -> Result<Vec<DuplicateGroup>, SnippetError> {
+    let mut conn = pool.acquire().await?;
+    let rows = sqlx::query(
+        "SELECT id, title, language, code, tags, created_at, updated_at, collection_id,
+                pinned, use_count, last_used_at, secret_flags, content_hash
+         FROM code_snippets
+         WHERE content_hash IN (
+             SELECT content_hash FROM code_snippets
+             GROUP BY content_hash HAVING COUNT(*) > 1
+         )
+         ORDER BY content_hash, created_at",
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut groups: BTreeMap<String, Vec<CodeSnippet>> = BTreeMap::new();
+    for row in rows {
+        let hash: String = row.get("content_hash");
+        groups.entry(hash).or_default().push(CodeSnippet {
+            id: row.get("id"),
+            title: row.get("title"),
+            language: row.get("language"),
+            code: encryption::open(row.get("code")).map_err(SnippetError::Encryption)?,
+            tags: row.get("tags"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            collection_id: row.get("collection_id"),
+            pinned: row.get("pinned"),
+            use_count: row.get("use_count"),
+            last_used_at: row.get("last_used_at"),
+            secret_flags: row.get("secret_flags"),
+        });
+    }
+
+    Ok(groups
+        .into_iter()
+        .map(|(content_hash, snippets)| DuplicateGroup {
+            content_hash,
+            snippets,
+        })
+        .collect())
+}
+
+// ============================================================================
+// 批量操作（均在单个事务中执行）
+// ============================================================================
+
+/// 批量创建：逐条校验，校验失败的条目单独报告，其余条目在同一事务中写入
+pub async fn bulk_create(
+    pool: &SqlitePool,
+    items: Vec<NewSnippet>,
+) -> Result<Vec<BatchItemResult>, SnippetError> {
+    let limits = SnippetLimits::load(pool).await;
+    let policy = DuplicatePolicy::load(pool).await;
+    let action = SecretAction::for_snippets(pool).await;
+
+    let mut tx = pool.begin_with("BEGIN IMMEDIATE").await?;
+
+    let mut results = Vec::with_capacity(items.len());
+    for (index, item) in items.into_iter().enumerate() {
+        let screened = screen_code(item.code, action)
+            .and_then(|code| validate_new(NewSnippet { code, ..item }, &limits));
+        let outcome = match screened {
+            Ok(input) => insert_or_existing(&mut tx, &input, policy).await,
+            Err(e) => Err(e),
+        };
+        results.push(match outcome {
+            Ok(CreateOutcome::Created(snippet)) => BatchItemResult::ok(index, snippet.id, false),
+            Ok(CreateOutcome::Existing(snippet)) => BatchItemResult::ok(index, snippet.id, true),
+            Err(e @ (SnippetError::Database(_) | SnippetError::Encryption(_))) => return Err(e),
+            Err(e) => BatchItemResult::failed(index, e.to_string()),
+        });
+    }
+
+    tx.commit().await?;
+    Ok(results)
+}
+
+/// 批量更新字段：任一条目不存在或校验失败时整体回滚
+pub async fn bulk_update(
+    pool: &SqlitePool,
+    ids: &[i64],
+    patch: SnippetPatch,
+) -> Result<u64, SnippetError> {
+    let limits = SnippetLimits::load(pool).await;
+    let patch = screen_patch(patch, SecretAction::for_snippets(pool).await)?;
+    // 先读后写，立即获取写锁，避免与并发修改交错导致读到的内容过期
+    let mut tx = pool.begin_with("BEGIN IMMEDIATE").await?;
+    for id in ids {
+        apply_patch(&mut tx, *id, patch.clone(), &limits).await?;
+    }
+    tx.commit().await?;
+    Ok(ids.len() as u64)
+}
+
+/// 批量添加标签
+pub async fn bulk_add_tags(
+    pool: &SqlitePool,
+    ids: &[i64],
+    tags: &[String],
+) -> Result<u64, SnippetError> {
+    bulk_edit_tags(pool, ids, |current| {
+        let mut merged = current;
+        merged.extend(tags.iter().cloned());
+        merged
+    })
+    .await
+}
+
+/// 批量移除标签
+pub async fn bulk_remove_tags(
+    pool: &SqlitePool,
+    ids: &[i64],
+    tags: &[String],
+) -> Result<u64, SnippetError> {
+    bulk_edit_tags(pool, ids, |current| {
+        current
+            .into_iter()
+            .filter(|tag| !tags.iter().any(|t| t.trim() == tag.trim()))
+            .collect()
+    })
+    .await
+}
+
+/// 对每个代码片段的标签列表应用修改，只写回实际发生变化的条目
+async fn bulk_edit_tags<F>(pool: &SqlitePool, ids: &[i64], edit: F) -> Result<u64, SnippetError>
+where
+    F: Fn(Vec<String>) -> Vec<String>,
+{
+    let limits = SnippetLimits::load(pool).await;
+    // 读出标签后再写回，立即获取写锁，避免并发编辑互相覆盖
+    let mut tx = pool.begin_with("BEGIN IMMEDIATE").await?;
+    let mut changed = 0;
+
+    for id in ids {
+        let current = get_snippet(&mut tx, *id)
+            .await?
+            .ok_or(SnippetError::NotFound(*id))?;
+        let current_tags: Vec<String> = serde_json::from_str(&current.tags).unwrap_or_default();
+        let new_tags =
+            normalize_tags(&edit(current_tags.clone())).map_err(SnippetError::Invalid)?;
+        if new_tags == current_tags {
+            continue;
+        }
+
+        let patch = SnippetPatch {
+            tags: Some(new_tags),
+            ..Default::default()
+        };
+        apply_patch(&mut tx, *id, patch, &limits).await?;
+        changed += 1;
+    }
+
+    tx.commit().await?;
+    Ok(changed)
+}
+
+/// 批量删除，返回实际删除的条数
+pub async fn bulk_delete(pool: &SqlitePool, ids: &[i64]) -> Result<u64, SnippetError> {
+    let mut tx = pool.begin().await?;
+    let mut deleted = 0;
+    for id in ids {
+        deleted += sqlx::query("DELETE FROM code_snippets WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    }
+    tx.commit().await?;
+    Ok(deleted)
+}
+
+// ============================================================================
+// 置顶与使用统计
+// ============================================================================
+
+/// 记录一次使用：使用次数加一并更新最近使用时间（不修改 updated_at，避免触发同步）
+pub async fn record_use(pool: &SqlitePool, id: i64) -> Result<(), SnippetError> {
+    let result = sqlx::query(
+        "UPDATE code_snippets SET use_count = use_count + 1, last_used_at = ? WHERE id = ?",
+    )
+    .bind(now_timestamp())
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(SnippetError::NotFound(id));
+    }
+    Ok(())
+}
+
+/// 批量设置或取消置顶，返回更新的条数
+pub async fn set_pinned(pool: &SqlitePool, ids: &[i64], pinned: bool) -> Result<u64, SnippetError> {
+    let mut tx = pool.begin().await?;
+    let mut updated = 0;
+    for id in ids {
+        updated += sqlx::query("UPDATE code_snippets SET pinned = ? WHERE id = ?")
+            .bind(pinned)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    }
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// 使用次数最多的代码片段
+pub async fn top_used(pool: &SqlitePool, limit: i64) -> Result<Vec<SnippetUsage>, SnippetError> {
+    let usage = sqlx::query_as::<_, SnippetUsage>(
+        "SELECT id, title, language, pinned, use_count, last_used_at
+         FROM code_snippets WHERE use_count > 0
+         ORDER BY use_count DESC, last_used_at DESC LIMIT ?",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(usage)
+}
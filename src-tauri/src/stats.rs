@@ -0,0 +1,140 @@
+use crate::models::{DailyGrowth, DatabaseStats, NamedCount};
+use crate::snippets;
+use sqlx::{Row, SqlitePool};
+use std::collections::{BTreeMap, HashMap};
+
+/// 统计增长趋势的天数
+const GROWTH_DAYS: i64 = 30;
+/// 统计中返回的常用代码片段数量
+const TOP_SNIPPETS: i64 = 10;
+
+/// 某张表的数量及最早/最新创建时间
+async fn table_summary(
+    pool: &SqlitePool,
+    table: &str,
+) -> Result<(i64, Option<String>, Option<String>), String> {
+    let row = sqlx::query(&format!(
+        "SELECT COUNT(*) AS total, MIN(created_at) AS oldest, MAX(created_at) AS newest FROM {}",
+        table
+    ))
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok((row.get("total"), row.get("oldest"), row.get("newest")))
+}
+
+/// 某张表自 since（YYYY-MM-DD）起每天新增的数量
+async fn daily_counts(
+    pool: &SqlitePool,
+    table: &str,
+    since: &str,
+) -> Result<HashMap<String, i64>, String> {
+    let rows = sqlx::query(&format!(
+        "SELECT substr(created_at, 1, 10) AS day, COUNT(*) AS count FROM {}
+         WHERE created_at >= ? GROUP BY day",
+        table
+    ))
+    .bind(since)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.get("day"), row.get("count")))
+        .collect())
+}
+
+/// 统计标签使用次数（tags 为 JSON 数组字符串）
+async fn tag_counts(pool: &SqlitePool) -> Result<Vec<NamedCount>, String> {
+    let rows = sqlx::query("SELECT tags FROM code_snippets WHERE tags != '[]' AND tags != ''")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut counts: BTreeMap<String, i64> = BTreeMap::new();
+    for row in rows {
+        let tags: Vec<String> = serde_json::from_str(row.get("tags")).unwrap_or_default();
+        for tag in tags {
+            *counts.entry(tag).or_default() += 1;
+        }
+    }
+
+    let mut counts: Vec<NamedCount> = counts
+        .into_iter()
+        .map(|(name, count)| NamedCount { name, count })
+        .collect();
+    counts.sort_by_key(|c| std::cmp::Reverse(c.count));
+    Ok(counts)
+}
+
+/// 收集数据库统计信息
+pub async fn collect_stats(pool: &SqlitePool) -> Result<DatabaseStats, String> {
+    let (total_snippets, oldest_snippet_at, newest_snippet_at) =
+        table_summary(pool, "code_snippets").await?;
+    let (total_clipboard_items, oldest_clipboard_at, newest_clipboard_at) =
+        table_summary(pool, "clipboard_items").await?;
+
+    let language_counts: Vec<NamedCount> = sqlx::query(
+        "SELECT language, COUNT(*) AS count FROM code_snippets
+         GROUP BY language ORDER BY count DESC, language",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .map(|row| NamedCount {
+        name: row.get("language"),
+        count: row.get("count"),
+    })
+    .collect();
+    let mut languages: Vec<String> = language_counts.iter().map(|c| c.name.clone()).collect();
+    languages.sort();
+
+    // 使用页数计算文件大小，不依赖数据库文件路径（MCP 服务器中同样可用）
+    let page_count: i64 = sqlx::query_scalar("PRAGMA page_count")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let page_size: i64 = sqlx::query_scalar("PRAGMA page_size")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let today = chrono::Local::now().date_naive();
+    let first_day = today - chrono::Duration::days(GROWTH_DAYS - 1);
+    let since = first_day.format("%Y-%m-%d").to_string();
+    let snippet_days = daily_counts(pool, "code_snippets", &since).await?;
+    let clipboard_days = daily_counts(pool, "clipboard_items", &since).await?;
+    let daily_growth: Vec<DailyGrowth> = first_day
+        .iter_days()
+        .take(GROWTH_DAYS as usize)
+        .map(|day| {
+            let date = day.format("%Y-%m-%d").to_string();
+            DailyGrowth {
+                snippets: snippet_days.get(&date).copied().unwrap_or(0),
+                clipboard_items: clipboard_days.get(&date).copied().unwrap_or(0),
+                date,
+            }
+        })
+        .collect();
+
+    Ok(DatabaseStats {
+        total_snippets,
+        total_clipboard_items,
+        languages,
+        language_counts,
+        tag_counts: tag_counts(pool).await?,
+        db_size_bytes: page_count * page_size,
+        oldest_snippet_at,
+        newest_snippet_at,
+        oldest_clipboard_at,
+        newest_clipboard_at,
+        snippets_last_30_days: daily_growth.iter().map(|d| d.snippets).sum(),
+        clipboard_items_last_30_days: daily_growth.iter().map(|d| d.clipboard_items).sum(),
+        daily_growth,
+        top_snippets: snippets::top_used(pool, TOP_SNIPPETS)
+            .await
+            .map_err(|e| e.to_string())?,
+    })
+}
@@ -0,0 +1,880 @@
+use crate::db::now_timestamp;
+use crate::encryption;
+use crate::models::CodeSnippet;
+use crate::secrets::SecretAction;
+use crate::snippets::{
+    apply_patch, insert_snippet, screen_code, validate_new, NewSnippet, SnippetPatch,
+};
+use crate::validation::{extension_for_language, language_for_extension, SnippetLimits};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Connection, Row, SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tokio_util::sync::CancellationToken;
+
+/// 普通文件夹同步在 sync_state 中使用的目标名称
+pub const FOLDER_TARGET: &str = "folder";
+
+/// 文件夹定期全量同步的间隔（用于把应用内的修改写到文件夹）
+const PERIODIC_SYNC_INTERVAL: Duration = Duration::from_secs(30);
+/// 文件变化事件的去抖时间
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+// ============================================================================
+// 文件格式
+// ============================================================================
+//
+// 每个代码片段一个文件，文件开头为 front matter：
+//
+//   ---
+//   id: 6f1c...（sync_id）
+//   title: Common Git Commands
+//   language: shell
+//   tags: ["git","shell"]
+//   ---
+//   <代码内容>
+
+/// 从文件中解析出的代码片段
+#[derive(Debug, Clone, Default)]
+pub struct SnippetFile {
+    pub sync_id: Option<String>,
+    pub title: Option<String>,
+    pub language: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub code: String,
+}
+
+/// 生成代码片段文件内容
+pub fn render_file(snippet: &CodeSnippet, sync_id: &str) -> String {
+    let tags: Vec<String> = serde_json::from_str(&snippet.tags).unwrap_or_default();
+    format!(
+        "---\nid: {}\ntitle: {}\nlanguage: {}\ntags: {}\n---\n{}",
+        sync_id,
+        snippet.title.replace('\n', " "),
+        snippet.language,
+        serde_json::to_string(&tags).unwrap_or_else(|_| "[]".to_string()),
+        snippet.code
+    )
+}
+
+/// 解析代码片段文件；没有 front matter 时整个文件视为代码
+pub fn parse_file(contents: &str) -> SnippetFile {
+    let normalized = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+    let Some(rest) = normalized
+        .strip_prefix("---\n")
+        .or_else(|| normalized.strip_prefix("---\r\n"))
+    else {
+        return SnippetFile {
+            code: contents.to_string(),
+            ..Default::default()
+        };
+    };
+
+    let mut file = SnippetFile::default();
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line == "---" {
+            file.code = rest[offset..].to_string();
+            return file;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "id" if !value.is_empty() => file.sync_id = Some(value.to_string()),
+            "title" => file.title = Some(value.to_string()),
+            "language" => file.language = Some(value.to_string()),
+            "tags" => file.tags = serde_json::from_str(value).ok(),
+            _ => {}
+        }
+    }
+
+    // front matter 没有结束标记，按普通文件处理
+    SnippetFile {
+        code: contents.to_string(),
+        ..Default::default()
+    }
+}
+
+/// 生成文件名：<标题>-<sync_id 前 8 位>.<扩展名>
+pub fn file_name_for(snippet: &CodeSnippet, sync_id: &str) -> String {
+    let slug = snippet
+        .title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug: String = slug.chars().take(60).collect();
+    let short_id: String = sync_id.chars().take(8).collect();
+    let ext = extension_for_language(&snippet.language);
+    if slug.is_empty() {
+        format!("{}.{}", short_id, ext)
+    } else {
+        format!("{}-{}.{}", slug, short_id, ext)
+    }
+}
+
+/// 文件内容哈希（不做空白规范化，任何修改都会被识别）
+pub fn file_hash(contents: &str) -> String {
+    Sha256::digest(contents.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// ============================================================================
+// 同步状态
+// ============================================================================
+
+/// 上一次同步时的状态，用于判断哪一侧发生了修改
+#[derive(Debug, Clone)]
+struct SyncState {
+    file_hash: String,
+    db_updated_at: String,
+}
+
+/// 同步冲突：应用和文件两侧在上次同步后都被修改
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncConflict {
+    pub sync_id: String,
+    pub snippet_id: Option<i64>,
+    pub file_name: String,
+    pub app_title: Option<String>,
+    pub app_code: Option<String>,
+    pub app_updated_at: Option<String>,
+    pub file_title: Option<String>,
+    pub file_code: Option<String>,
+}
+
+/// 一次同步的结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncReport {
+    pub target: String,
+    pub started_at: String,
+    /// 写入文件的代码片段数
+    pub exported: usize,
+    /// 从文件更新到数据库的代码片段数
+    pub imported: usize,
+    /// 由新文件创建的代码片段数
+    pub created: usize,
+    /// 因文件被删除而从数据库删除的代码片段数
+    pub deleted_snippets: usize,
+    /// 因代码片段被删除而删除的文件数
+    pub deleted_files: usize,
+    pub conflicts: Vec<SyncConflict>,
+    pub errors: Vec<String>,
+}
+
+impl SyncReport {
+    /// 同步是否修改了数据库中的代码片段
+    pub fn changed_snippets(&self) -> bool {
+        self.imported + self.created + self.deleted_snippets > 0
+    }
+}
+
+/// 冲突处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    /// 保留应用内的版本，覆盖文件
+    KeepApp,
+    /// 保留文件中的版本，覆盖数据库
+    KeepFile,
+}
+
+async fn load_states(
+    conn: &mut SqliteConnection,
+    target: &str,
+) -> Result<HashMap<String, SyncState>, String> {
+    let rows =
+        sqlx::query("SELECT sync_id, file_hash, db_updated_at FROM sync_state WHERE target = ?")
+            .bind(target)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.get("sync_id"),
+                SyncState {
+                    file_hash: row.get("file_hash"),
+                    db_updated_at: row.get("db_updated_at"),
+                },
+            )
+        })
+        .collect())
+}
+
+async fn save_state(
+    conn: &mut SqliteConnection,
+    target: &str,
+    sync_id: &str,
+    file_name: &str,
+    contents: &str,
+    db_updated_at: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT OR REPLACE INTO sync_state
+         (target, sync_id, file_name, file_hash, db_updated_at, synced_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(target)
+    .bind(sync_id)
+    .bind(file_name)
+    .bind(file_hash(contents))
+    .bind(db_updated_at)
+    .bind(now_timestamp())
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn delete_state(
+    conn: &mut SqliteConnection,
+    target: &str,
+    sync_id: &str,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM sync_state WHERE target = ? AND sync_id = ?")
+        .bind(target)
+        .bind(sync_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 为尚未分配 sync_id 的代码片段生成 UUID
+pub async fn ensure_sync_ids(conn: &mut SqliteConnection) -> Result<(), String> {
+    let ids: Vec<i64> = sqlx::query("SELECT id FROM code_snippets WHERE sync_id IS NULL")
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|row| row.get("id"))
+        .collect();
+
+    for id in ids {
+        sqlx::query("UPDATE code_snippets SET sync_id = ? WHERE id = ?")
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// 读取所有代码片段及其 sync_id
+async fn load_snippets(conn: &mut SqliteConnection) -> Result<Vec<(CodeSnippet, String)>, String> {
+    let rows = sqlx::query(
+        "SELECT id, title, language, code, tags, created_at, updated_at, collection_id,
+                pinned, use_count, last_used_at, secret_flags, sync_id
+         FROM code_snippets ORDER BY id",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    rows.into_iter()
+        .map(|row| {
+            Ok((
+                CodeSnippet {
+                    id: row.get("id"),
+                    title: row.get("title"),
+                    language: row.get("language"),
+                    code: encryption::open(row.get("code"))?,
+                    tags: row.get("tags"),
+                    created_at: row.get("created_at"),
+                    updated_at: row.get("updated_at"),
+                    collection_id: row.get("collection_id"),
+                    pinned: row.get("pinned"),
+                    use_count: row.get("use_count"),
+                    last_used_at: row.get("last_used_at"),
+                    secret_flags: row.get("secret_flags"),
+                },
+                row.get("sync_id"),
+            ))
+        })
+        .collect()
+}
+
+/// 文件夹中的一个代码片段文件
+#[derive(Debug, Clone)]
+struct FolderEntry {
+    file_name: String,
+    contents: String,
+    parsed: SnippetFile,
+}
+
+/// 读取文件夹顶层的代码片段文件（忽略隐藏文件和非 UTF-8 文件）
+fn scan_folder(dir: &Path) -> Result<Vec<FolderEntry>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if file_name.starts_with('.') || !entry.path().is_file() {
+            continue;
+        }
+        let Ok(contents) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let parsed = parse_file(&contents);
+        files.push(FolderEntry {
+            file_name,
+            contents,
+            parsed,
+        });
+    }
+    files.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    Ok(files)
+}
+
+/// 判断文件内容与数据库中的代码片段是否一致
+fn file_matches(file: &SnippetFile, snippet: &CodeSnippet) -> bool {
+    let tags: Vec<String> = serde_json::from_str(&snippet.tags).unwrap_or_default();
+    file.code == snippet.code
+        && file.title.as_deref().map(str::trim) == Some(snippet.title.as_str())
+        && file.language.as_deref() == Some(snippet.language.as_str())
+        && file.tags.as_ref().unwrap_or(&Vec::new()) == &tags
+}
+
+/// 写入代码片段文件，文件名变化时删除旧文件；返回 (文件名, 文件内容)
+fn write_snippet_file(
+    dir: &Path,
+    snippet: &CodeSnippet,
+    sync_id: &str,
+    old_file_name: Option<&str>,
+) -> Result<(String, String), String> {
+    let file_name = file_name_for(snippet, sync_id);
+    let contents = render_file(snippet, sync_id);
+    std::fs::write(dir.join(&file_name), &contents)
+        .map_err(|e| format!("Failed to write {}: {}", file_name, e))?;
+    if let Some(old) = old_file_name.filter(|old| *old != file_name) {
+        let _ = std::fs::remove_file(dir.join(old));
+    }
+    Ok((file_name, contents))
+}
+
+/// 将文件中的修改写入数据库（按 snippet_secret_action 处理其中的密钥）
+async fn apply_file(
+    conn: &mut SqliteConnection,
+    snippet_id: i64,
+    file: &SnippetFile,
+    limits: &SnippetLimits,
+    action: SecretAction,
+) -> Result<CodeSnippet, String> {
+    let patch = SnippetPatch {
+        title: file.title.clone(),
+        language: file.language.clone(),
+        code: Some(screen_code(file.code.clone(), action).map_err(|e| e.to_string())?),
+        tags: file.tags.clone(),
+    };
+    apply_patch(conn, snippet_id, patch, limits)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 由文件创建新的代码片段（按 snippet_secret_action 处理其中的密钥），并绑定 sync_id
+async fn create_from_file(
+    conn: &mut SqliteConnection,
+    entry: &FolderEntry,
+    sync_id: &str,
+    limits: &SnippetLimits,
+    action: SecretAction,
+) -> Result<CodeSnippet, String> {
+    let path = Path::new(&entry.file_name);
+    let title = entry.parsed.title.clone().unwrap_or_else(|| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    let language = entry.parsed.language.clone().unwrap_or_else(|| {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(language_for_extension)
+            .unwrap_or("text")
+            .to_string()
+    });
+    let input = validate_new(
+        NewSnippet {
+            title,
+            language,
+            code: screen_code(entry.parsed.code.clone(), action).map_err(|e| e.to_string())?,
+            tags: entry.parsed.tags.clone().unwrap_or_default(),
+        },
+        limits,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let snippet = insert_snippet(&mut *conn, &input)
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query("UPDATE code_snippets SET sync_id = ? WHERE id = ?")
+        .bind(sync_id)
+        .bind(snippet.id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(snippet)
+}
+
+fn conflict(
+    sync_id: &str,
+    snippet: Option<&CodeSnippet>,
+    entry: Option<&FolderEntry>,
+    file_name: &str,
+) -> SyncConflict {
+    SyncConflict {
+        sync_id: sync_id.to_string(),
+        snippet_id: snippet.map(|s| s.id),
+        file_name: file_name.to_string(),
+        app_title: snippet.map(|s| s.title.clone()),
+        app_code: snippet.map(|s| s.code.clone()),
+        app_updated_at: snippet.map(|s| s.updated_at.clone()),
+        file_title: entry.and_then(|e| e.parsed.title.clone()),
+        file_code: entry.map(|e| e.parsed.code.clone()),
+    }
+}
+
+// 同一时间只允许一个同步任务运行
+lazy_static::lazy_static! {
+    static ref SYNC_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// 双向同步数据库与文件夹
+///
+/// 以 sync_state 中记录的上次同步状态为基准：只有一侧修改时应用该修改，
+/// 两侧都修改且内容不同时记为冲突，两侧都保持不变。
+pub async fn reconcile(pool: &SqlitePool, dir: &Path, target: &str) -> Result<SyncReport, String> {
+    let _guard = SYNC_LOCK.lock().await;
+
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    let limits = SnippetLimits::load(pool).await;
+    let action = SecretAction::for_snippets(pool).await;
+    // 数据库一侧的修改在同一个事务中完成，每个条目再使用一个保存点，
+    // 避免单个条目失败时代码片段与 sync_state 不一致。
+    // 立即获取写锁：文件会在提交前写出，延迟获取写锁时并发写入（如剪贴板记录）
+    // 会使后续写入因快照过期而失败，留下已修改的文件和未更新的同步状态
+    let mut conn = pool
+        .begin_with("BEGIN IMMEDIATE")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    ensure_sync_ids(&mut conn).await?;
+    let snippets = load_snippets(&mut conn).await?;
+    let mut states = load_states(&mut conn, target).await?;
+
+    let mut report = SyncReport {
+        target: target.to_string(),
+        started_at: now_timestamp(),
+        ..Default::default()
+    };
+
+    let entries = scan_folder(dir)?;
+    if entries.is_empty() && !states.is_empty() {
+        // 文件夹为空但存在同步记录，多半是目录未挂载或路径错误，拒绝同步以免误删数据
+        return Err(format!(
+            "{} is empty but was synced before, refusing to delete snippets",
+            dir.display()
+        ));
+    }
+
+    let mut files: HashMap<String, FolderEntry> = HashMap::new();
+    let mut new_files: Vec<FolderEntry> = Vec::new();
+    for entry in entries {
+        match entry.parsed.sync_id.clone() {
+            Some(sync_id) if !files.contains_key(&sync_id) => {
+                files.insert(sync_id, entry);
+            }
+            _ => new_files.push(entry),
+        }
+    }
+
+    // 1. 遍历数据库中的代码片段
+    for (snippet, sync_id) in &snippets {
+        let state = states.remove(sync_id);
+        let entry = files.remove(sync_id);
+
+        let result: Result<(), String> = async {
+            let mut item = conn.begin().await.map_err(|e| e.to_string())?;
+            match (state, entry) {
+                (None, None) => {
+                    let (name, contents) = write_snippet_file(dir, snippet, sync_id, None)?;
+                    save_state(
+                        &mut item,
+                        target,
+                        sync_id,
+                        &name,
+                        &contents,
+                        &snippet.updated_at,
+                    )
+                    .await?;
+                    report.exported += 1;
+                }
+                (None, Some(entry)) => {
+                    // 没有同步基准：内容一致则直接建立基准，否则视为冲突
+                    if file_matches(&entry.parsed, snippet) {
+                        save_state(
+                            &mut item,
+                            target,
+                            sync_id,
+                            &entry.file_name,
+                            &entry.contents,
+                            &snippet.updated_at,
+                        )
+                        .await?;
+                    } else {
+                        report.conflicts.push(conflict(
+                            sync_id,
+                            Some(snippet),
+                            Some(&entry),
+                            &entry.file_name,
+                        ));
+                    }
+                }
+                (Some(state), None) => {
+                    if snippet.updated_at == state.db_updated_at {
+                        // 文件被外部删除，同步删除代码片段
+                        sqlx::query("DELETE FROM code_snippets WHERE id = ?")
+                            .bind(snippet.id)
+                            .execute(&mut *item)
+                            .await
+                            .map_err(|e| e.to_string())?;
+                        delete_state(&mut item, target, sync_id).await?;
+                        report.deleted_snippets += 1;
+                    } else {
+                        // 文件被删除但应用内又有修改，以修改为准重新写出文件
+                        let (name, contents) = write_snippet_file(dir, snippet, sync_id, None)?;
+                        save_state(
+                            &mut item,
+                            target,
+                            sync_id,
+                            &name,
+                            &contents,
+                            &snippet.updated_at,
+                        )
+                        .await?;
+                        report.exported += 1;
+                    }
+                }
+                (Some(state), Some(entry)) => {
+                    let db_changed = snippet.updated_at != state.db_updated_at;
+                    let file_changed = file_hash(&entry.contents) != state.file_hash;
+                    match (db_changed, file_changed) {
+                        (false, false) => {}
+                        (true, false) => {
+                            let (name, contents) =
+                                write_snippet_file(dir, snippet, sync_id, Some(&entry.file_name))?;
+                            save_state(
+                                &mut item,
+                                target,
+                                sync_id,
+                                &name,
+                                &contents,
+                                &snippet.updated_at,
+                            )
+                            .await?;
+                            report.exported += 1;
+                        }
+                        (false, true) => {
+                            let updated =
+                                apply_file(&mut item, snippet.id, &entry.parsed, &limits, action)
+                                    .await?;
+                            save_state(
+                                &mut item,
+                                target,
+                                sync_id,
+                                &entry.file_name,
+                                &entry.contents,
+                                &updated.updated_at,
+                            )
+                            .await?;
+                            report.imported += 1;
+                        }
+                        (true, true) => {
+                            if file_matches(&entry.parsed, snippet) {
+                                save_state(
+                                    &mut item,
+                                    target,
+                                    sync_id,
+                                    &entry.file_name,
+                                    &entry.contents,
+                                    &snippet.updated_at,
+                                )
+                                .await?;
+                            } else {
+                                report.conflicts.push(conflict(
+                                    sync_id,
+                                    Some(snippet),
+                                    Some(&entry),
+                                    &entry.file_name,
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+            item.commit().await.map_err(|e| e.to_string())?;
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            report.errors.push(format!("{}: {}", snippet.title, e));
+        }
+    }
+
+    // 2. 带 id 但数据库中不存在的文件
+    for (sync_id, entry) in files {
+        let result: Result<(), String> = async {
+            let mut item = conn.begin().await.map_err(|e| e.to_string())?;
+            match states.remove(&sync_id) {
+                Some(state) if file_hash(&entry.contents) == state.file_hash => {
+                    // 代码片段已在应用内删除，文件未修改：删除文件
+                    std::fs::remove_file(dir.join(&entry.file_name))
+                        .map_err(|e| format!("Failed to remove {}: {}", entry.file_name, e))?;
+                    delete_state(&mut item, target, &sync_id).await?;
+                    report.deleted_files += 1;
+                }
+                _ => {
+                    // 新文件（例如来自其他设备）或删除后又被修改的文件：重新创建代码片段
+                    let snippet =
+                        create_from_file(&mut item, &entry, &sync_id, &limits, action).await?;
+                    save_state(
+                        &mut item,
+                        target,
+                        &sync_id,
+                        &entry.file_name,
+                        &entry.contents,
+                        &snippet.updated_at,
+                    )
+                    .await?;
+                    report.created += 1;
+                }
+            }
+            item.commit().await.map_err(|e| e.to_string())?;
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            report.errors.push(format!("{}: {}", entry.file_name, e));
+        }
+    }
+
+    // 3. 没有 id 的新文件：创建代码片段并写回带 id 的文件
+    for entry in new_files {
+        let result: Result<(), String> = async {
+            let mut item = conn.begin().await.map_err(|e| e.to_string())?;
+            let sync_id = uuid::Uuid::new_v4().to_string();
+            let snippet = create_from_file(&mut item, &entry, &sync_id, &limits, action).await?;
+            let (name, contents) =
+                write_snippet_file(dir, &snippet, &sync_id, Some(&entry.file_name))?;
+            save_state(
+                &mut item,
+                target,
+                &sync_id,
+                &name,
+                &contents,
+                &snippet.updated_at,
+            )
+            .await?;
+            report.created += 1;
+            item.commit().await.map_err(|e| e.to_string())?;
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            report.errors.push(format!("{}: {}", entry.file_name, e));
+        }
+    }
+
+    // 4. 两侧都已不存在的同步记录
+    for sync_id in states.keys() {
+        delete_state(&mut conn, target, sync_id).await?;
+    }
+
+    conn.commit().await.map_err(|e| e.to_string())?;
+    Ok(report)
+}
+
+/// 按指定方式解决单个冲突
+pub async fn resolve_conflict(
+    pool: &SqlitePool,
+    dir: &Path,
+    target: &str,
+    sync_id: &str,
+    resolution: ConflictResolution,
+) -> Result<(), String> {
+    let _guard = SYNC_LOCK.lock().await;
+    let limits = SnippetLimits::load(pool).await;
+    let action = SecretAction::for_snippets(pool).await;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let snippet = load_snippets(&mut conn)
+        .await?
+        .into_iter()
+        .find(|(_, id)| id == sync_id)
+        .map(|(snippet, _)| snippet);
+    let entry = scan_folder(dir)?
+        .into_iter()
+        .find(|entry| entry.parsed.sync_id.as_deref() == Some(sync_id));
+
+    match (resolution, snippet, entry) {
+        (ConflictResolution::KeepApp, Some(snippet), entry) => {
+            let old_name = entry.as_ref().map(|e| e.file_name.as_str());
+            let (name, contents) = write_snippet_file(dir, &snippet, sync_id, old_name)?;
+            save_state(
+                &mut conn,
+                target,
+                sync_id,
+                &name,
+                &contents,
+                &snippet.updated_at,
+            )
+            .await
+        }
+        (ConflictResolution::KeepFile, Some(snippet), Some(entry)) => {
+            let updated = apply_file(&mut conn, snippet.id, &entry.parsed, &limits, action).await?;
+            save_state(
+                &mut conn,
+                target,
+                sync_id,
+                &entry.file_name,
+                &entry.contents,
+                &updated.updated_at,
+            )
+            .await
+        }
+        (ConflictResolution::KeepFile, None, Some(entry)) => {
+            let snippet = create_from_file(&mut conn, &entry, sync_id, &limits, action).await?;
+            save_state(
+                &mut conn,
+                target,
+                sync_id,
+                &entry.file_name,
+                &entry.contents,
+                &snippet.updated_at,
+            )
+            .await
+        }
+        _ => Err(format!("Nothing to resolve for {}", sync_id)),
+    }
+}
+
+// ============================================================================
+// 文件夹监听
+// ============================================================================
+
+struct FolderWatch {
+    path: PathBuf,
+    cancel: CancellationToken,
+    _watcher: RecommendedWatcher,
+}
+
+lazy_static::lazy_static! {
+    static ref FOLDER_WATCH: Mutex<Option<FolderWatch>> = Mutex::new(None);
+    static ref LAST_REPORT: RwLock<Option<SyncReport>> = RwLock::new(None);
+}
+
+/// 文件夹同步状态
+#[derive(Debug, Serialize)]
+pub struct FolderSyncStatus {
+    pub watching: bool,
+    pub path: Option<String>,
+    pub last_report: Option<SyncReport>,
+}
+
+/// 执行一次文件夹同步并记录结果
+pub async fn sync_folder(pool: &SqlitePool, dir: &Path) -> Result<SyncReport, String> {
+    let report = reconcile(pool, dir, FOLDER_TARGET).await?;
+    *LAST_REPORT.write().await = Some(report.clone());
+    Ok(report)
+}
+
+/// 开始监听文件夹：文件变化时（去抖后）同步，并定期同步应用内的修改
+///
+/// 同步修改了代码片段时调用 on_change（用于刷新托盘菜单等）。
+pub async fn start_watch(
+    pool: SqlitePool,
+    dir: PathBuf,
+    on_change: impl Fn() + Send + 'static,
+) -> Result<(), String> {
+    stop_watch().await;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if res.is_ok() {
+            let _ = tx.send(());
+        }
+    })
+    .map_err(|e| format!("Failed to create folder watcher: {}", e))?;
+    watcher
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch {}: {}", dir.display(), e))?;
+
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+    let task_dir = dir.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PERIODIC_SYNC_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => break,
+                _ = interval.tick() => {}
+                Some(()) = rx.recv() => {
+                    tokio::time::sleep(WATCH_DEBOUNCE).await;
+                    while rx.try_recv().is_ok() {}
+                }
+            }
+
+            match sync_folder(&pool, &task_dir).await {
+                Ok(report) if report.changed_snippets() => on_change(),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("folder sync failed: {}", e),
+            }
+            // 忽略同步过程中自身写文件产生的事件
+            while rx.try_recv().is_ok() {}
+        }
+    });
+
+    *FOLDER_WATCH.lock().await = Some(FolderWatch {
+        path: dir,
+        cancel,
+        _watcher: watcher,
+    });
+    Ok(())
+}
+
+/// 停止监听文件夹
+pub async fn stop_watch() {
+    if let Some(watch) = FOLDER_WATCH.lock().await.take() {
+        watch.cancel.cancel();
+    }
+}
+
+pub async fn folder_status() -> FolderSyncStatus {
+    let watch = FOLDER_WATCH.lock().await;
+    FolderSyncStatus {
+        watching: watch.is_some(),
+        path: watch
+            .as_ref()
+            .map(|w| w.path.to_string_lossy().into_owned()),
+        last_report: LAST_REPORT.read().await.clone(),
+    }
+}
@@ -0,0 +1,418 @@
+use crate::capture_rules;
+use crate::clipboard;
+use crate::commands;
+use crate::db::get_pool;
+use crate::encryption;
+use crate::i18n::{self, t, t_with};
+use crate::mcp_server::is_server_running;
+use crate::palette::PaletteSource;
+use crate::secrets;
+use crate::snippets::{self, SnippetFilter, SnippetSort};
+use sqlx::SqlitePool;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_aptabase::EventTracker;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+/// 系统托盘 ID
+pub const TRAY_ID: &str = "tray";
+/// 托盘菜单中显示的最近剪贴板条目数
+const RECENT_CLIPBOARD_ITEMS: i64 = 8;
+/// 托盘菜单中显示的置顶代码片段数
+const PINNED_SNIPPETS: usize = 10;
+/// 菜单项文字的最大字符数
+const LABEL_LENGTH: usize = 40;
+
+const CLIPBOARD_ITEM_PREFIX: &str = "clipboard_item_";
+const SNIPPET_PREFIX: &str = "snippet_";
+const PAUSE_CAPTURE_PREFIX: &str = "pause_capture_";
+
+lazy_static::lazy_static! {
+    /// 暂停到期时刷新菜单的定时任务
+    static ref RESUME_REFRESH: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+}
+
+// ============================================================================
+// 菜单内容
+// ============================================================================
+
+/// 构建托盘菜单所需的数据
+#[derive(Debug, Clone)]
+pub struct TrayState {
+    /// 最近的剪贴板条目（ID、文字）
+    pub recent_clipboard: Vec<(i64, String)>,
+    /// 置顶的代码片段（ID、标题）
+    pub pinned_snippets: Vec<(i64, String)>,
+    /// 数据库已加密且未解锁，两个列表均为空
+    pub locked: bool,
+    pub capture_enabled: bool,
+    pub capture_paused: bool,
+    /// 暂停结束前的剩余时间，到时重新刷新菜单
+    pub resume_in: Option<Duration>,
+    pub mcp_running: bool,
+}
+
+impl Default for TrayState {
+    fn default() -> Self {
+        Self {
+            recent_clipboard: Vec::new(),
+            pinned_snippets: Vec::new(),
+            locked: false,
+            capture_enabled: true,
+            capture_paused: false,
+            resume_in: None,
+            mcp_running: false,
+        }
+    }
+}
+
+/// 菜单项文字：合并空白、截断，并转义 Windows 菜单中的助记符 &
+fn menu_label(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let text = match text.char_indices().nth(LABEL_LENGTH) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    };
+    text.replace('&', "&&")
+}
+
+impl TrayState {
+    /// 从数据库读取界面语言、最近的剪贴板条目、置顶的代码片段和各项开关状态
+    ///
+    /// 数据库锁定时不读取内容，两个列表显示为已锁定，避免菜单中保留解密后的旧内容。
+    pub async fn load(app: &AppHandle) -> Result<Self, String> {
+        let pool = get_pool(app).await?;
+        i18n::load(&pool).await;
+        let locked = encryption::ensure_unlocked().is_err();
+        let (recent_clipboard, pinned_snippets) = if locked {
+            (Vec::new(), Vec::new())
+        } else {
+            (
+                recent_clipboard(&pool).await?,
+                pinned_snippets(&pool).await?,
+            )
+        };
+        let resume_in = capture_rules::pause_remaining(&pool).await;
+        Ok(Self {
+            recent_clipboard,
+            pinned_snippets,
+            locked,
+            capture_enabled: capture_rules::is_enabled(&pool).await,
+            capture_paused: resume_in.is_some(),
+            resume_in,
+            mcp_running: is_server_running().await,
+        })
+    }
+}
+
+/// 最近的剪贴板条目；标记了密钥的条目遮盖后显示
+async fn recent_clipboard(pool: &SqlitePool) -> Result<Vec<(i64, String)>, String> {
+    Ok(clipboard::history(pool, RECENT_CLIPBOARD_ITEMS)
+        .await?
+        .into_iter()
+        .map(|item| {
+            let flagged = secrets::parse_flags(item.secret_flags.as_deref())
+                .is_none_or(|flags| !flags.is_empty());
+            let text = if flagged {
+                secrets::redact_all(&item.content)
+            } else {
+                item.content
+            };
+            (item.id, menu_label(&text))
+        })
+        .collect())
+}
+
+/// 按使用频率排序的置顶代码片段
+async fn pinned_snippets(pool: &SqlitePool) -> Result<Vec<(i64, String)>, String> {
+    let filter = SnippetFilter {
+        pinned: Some(true),
+        sort: SnippetSort::Frecency,
+        ..Default::default()
+    };
+    Ok(snippets::query_snippets(pool, &filter)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .take(PINNED_SNIPPETS)
+        .map(|snippet| (snippet.id, menu_label(&snippet.title)))
+        .collect())
+}
+
+/// 由若干条目构成的子菜单，没有条目（或数据库已锁定）时显示一个不可点击的占位项
+fn items_submenu(
+    app: &AppHandle,
+    title: &str,
+    prefix: &str,
+    items: &[(i64, String)],
+    locked: bool,
+) -> tauri::Result<Submenu<Wry>> {
+    let entries = if items.is_empty() {
+        vec![MenuItem::with_id(
+            app,
+            format!("{}empty", prefix),
+            if locked {
+                t("tray.locked")
+            } else {
+                t("tray.empty")
+            },
+            false,
+            None::<&str>,
+        )?]
+    } else {
+        items
+            .iter()
+            .map(|(id, label)| {
+                MenuItem::with_id(app, format!("{}{}", prefix, id), label, true, None::<&str>)
+            })
+            .collect::<tauri::Result<Vec<_>>>()?
+    };
+    let refs: Vec<&dyn IsMenuItem<Wry>> = entries
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    Submenu::with_items(app, title, true, &refs)
+}
+
+/// 构建托盘菜单
+pub fn build_menu(app: &AppHandle, state: &TrayState) -> tauri::Result<Menu<Wry>> {
+    let show_item = MenuItem::with_id(app, "show", t("tray.show"), true, None::<&str>)?;
+    let palette_item = MenuItem::with_id(app, "palette", t("tray.palette"), true, None::<&str>)?;
+    let clipboard_menu = items_submenu(
+        app,
+        &t("tray.recentClipboard"),
+        CLIPBOARD_ITEM_PREFIX,
+        &state.recent_clipboard,
+        state.locked,
+    )?;
+    let snippet_menu = items_submenu(
+        app,
+        &t("tray.pinnedSnippets"),
+        SNIPPET_PREFIX,
+        &state.pinned_snippets,
+        state.locked,
+    )?;
+
+    let capture_item = CheckMenuItem::with_id(
+        app,
+        "toggle_capture",
+        t("tray.captureClipboard"),
+        true,
+        state.capture_enabled,
+        None::<&str>,
+    )?;
+    let pause_items = [
+        (15, "tray.pause15Minutes"),
+        (60, "tray.pause1Hour"),
+        (480, "tray.pause8Hours"),
+    ]
+    .iter()
+    .map(|(minutes, key)| {
+        MenuItem::with_id(
+            app,
+            format!("{}{}", PAUSE_CAPTURE_PREFIX, minutes),
+            t(key),
+            true,
+            None::<&str>,
+        )
+    })
+    .collect::<tauri::Result<Vec<_>>>()?;
+    let pause_refs: Vec<&dyn IsMenuItem<Wry>> = pause_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let pause_menu = Submenu::with_items(
+        app,
+        t("tray.pauseCapture"),
+        state.capture_enabled && !state.capture_paused,
+        &pause_refs,
+    )?;
+    let resume_item = MenuItem::with_id(
+        app,
+        "resume_capture",
+        t("tray.resumeCapture"),
+        state.capture_enabled && state.capture_paused,
+        None::<&str>,
+    )?;
+    let mcp_item = CheckMenuItem::with_id(
+        app,
+        "toggle_mcp",
+        t("tray.mcpServer"),
+        true,
+        state.mcp_running,
+        None::<&str>,
+    )?;
+    let quit_item = MenuItem::with_id(app, "quit", t("tray.quit"), true, None::<&str>)?;
+
+    Menu::with_items(
+        app,
+        &[
+            &show_item,
+            &palette_item,
+            &PredefinedMenuItem::separator(app)?,
+            &clipboard_menu,
+            &snippet_menu,
+            &PredefinedMenuItem::separator(app)?,
+            &capture_item,
+            &pause_menu,
+            &resume_item,
+            &mcp_item,
+            &PredefinedMenuItem::separator(app)?,
+            &quit_item,
+        ],
+    )
+}
+
+/// 按最新数据重建托盘菜单（在后台执行，失败时保留原菜单）
+pub fn refresh(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = match TrayState::load(&app).await {
+            Ok(state) => {
+                schedule_resume_refresh(&app, state.resume_in);
+                build_menu(&app, &state).map_err(|e| e.to_string())
+            }
+            Err(e) => Err(e),
+        }
+        .and_then(|menu| match app.tray_by_id(TRAY_ID) {
+            Some(tray) => tray.set_menu(Some(menu)).map_err(|e| e.to_string()),
+            None => Ok(()),
+        });
+        if let Err(e) = result {
+            tracing::warn!("failed to update tray menu: {}", e);
+        }
+    });
+}
+
+/// 暂停到期时刷新菜单，让“暂停”“恢复”菜单项回到未暂停的状态；
+/// 每次刷新都会替换之前的定时任务
+fn schedule_resume_refresh(app: &AppHandle, resume_in: Option<Duration>) {
+    let mut task = RESUME_REFRESH.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(task) = task.take() {
+        task.abort();
+    }
+    if let Some(delay) = resume_in {
+        let app = app.clone();
+        *task = Some(tauri::async_runtime::spawn(async move {
+            // 多等一秒，确保刷新时暂停已经过期
+            tokio::time::sleep(delay + Duration::from_secs(1)).await;
+            refresh(&app);
+        }));
+    }
+}
+
+// ============================================================================
+// 菜单事件
+// ============================================================================
+
+/// 显示并聚焦主窗口
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// 托盘操作没有界面可以显示错误，用原生对话框提示
+fn show_error(app: &AppHandle, key: &str, error: &str) {
+    let message = t_with(key, &[("error", &error)]);
+    tracing::warn!("{}", message);
+    app.dialog()
+        .message(message)
+        .title(t("dialog.errorTitle"))
+        .kind(MessageDialogKind::Error)
+        .show(|_| {});
+}
+
+/// 处理托盘菜单点击
+pub fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        "show" => show_main_window(app),
+        "palette" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = commands::show_palette(app.clone()).await {
+                    show_error(&app, "dialog.showPaletteFailed", &e);
+                }
+            });
+        }
+        "toggle_capture" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let result = match get_pool(&app).await {
+                    Ok(pool) => {
+                        let enabled = capture_rules::is_enabled(&pool).await;
+                        commands::set_clipboard_capture_enabled(app.clone(), !enabled)
+                            .await
+                            .map(|_| ())
+                    }
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    show_error(&app, "dialog.toggleCaptureFailed", &e);
+                    refresh(&app);
+                }
+            });
+        }
+        "resume_capture" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = commands::resume_clipboard_capture(app.clone()).await {
+                    show_error(&app, "dialog.resumeCaptureFailed", &e);
+                }
+            });
+        }
+        "toggle_mcp" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = commands::toggle_mcp_server(&app).await {
+                    show_error(&app, "dialog.toggleMcpFailed", &e);
+                    refresh(&app);
+                }
+            });
+        }
+        "quit" => {
+            // 追踪应用退出事件
+            let _ = app.track_event("app_exited", None);
+            app.flush_events_blocking();
+            app.exit(0);
+        }
+        id => {
+            if let Some(minutes) = id.strip_prefix(PAUSE_CAPTURE_PREFIX) {
+                let minutes = minutes.parse().unwrap_or(15);
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = commands::pause_clipboard_capture(app.clone(), minutes).await {
+                        show_error(&app, "dialog.pauseCaptureFailed", &e);
+                    }
+                });
+                return;
+            }
+
+            let target = if let Some(item_id) = id.strip_prefix(CLIPBOARD_ITEM_PREFIX) {
+                item_id
+                    .parse()
+                    .ok()
+                    .map(|id| (PaletteSource::Clipboard, id))
+            } else if let Some(snippet_id) = id.strip_prefix(SNIPPET_PREFIX) {
+                snippet_id
+                    .parse()
+                    .ok()
+                    .map(|id| (PaletteSource::Snippet, id))
+            } else {
+                None
+            };
+            if let Some((source, id)) = target {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = commands::copy_item_to_clipboard(&app, source, id).await {
+                        show_error(&app, "dialog.copyFailed", &e);
+                    }
+                });
+            }
+        }
+    }
+}
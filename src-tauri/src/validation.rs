@@ -0,0 +1,232 @@
+use crate::db::get_setting;
+use crate::i18n::{t, t_with};
+use sqlx::SqlitePool;
+
+/// 支持的语言列表，与前端 utils/language.ts 保持一致
+pub const KNOWN_LANGUAGES: &[&str] = &[
+    "c",
+    "cpp",
+    "css",
+    "go",
+    "html",
+    "java",
+    "javascript",
+    "json",
+    "markdown",
+    "php",
+    "python",
+    "rust",
+    "shell",
+    "sql",
+    "typescript",
+    "vue",
+    "xml",
+    "yaml",
+    "text",
+];
+
+/// 常见的语言别名
+const LANGUAGE_ALIASES: &[(&str, &str)] = &[
+    ("js", "javascript"),
+    ("jsx", "javascript"),
+    ("node", "javascript"),
+    ("ts", "typescript"),
+    ("tsx", "typescript"),
+    ("py", "python"),
+    ("python3", "python"),
+    ("rs", "rust"),
+    ("sh", "shell"),
+    ("bash", "shell"),
+    ("zsh", "shell"),
+    ("shellscript", "shell"),
+    ("golang", "go"),
+    ("c++", "cpp"),
+    ("cc", "cpp"),
+    ("cxx", "cpp"),
+    ("h", "c"),
+    ("hpp", "cpp"),
+    ("md", "markdown"),
+    ("yml", "yaml"),
+    ("htm", "html"),
+    ("txt", "text"),
+    ("plaintext", "text"),
+    ("plain", "text"),
+];
+
+/// 文件扩展名到语言的映射
+const EXTENSION_LANGUAGES: &[(&str, &str)] = &[
+    ("c", "c"),
+    ("h", "c"),
+    ("cpp", "cpp"),
+    ("cc", "cpp"),
+    ("cxx", "cpp"),
+    ("hpp", "cpp"),
+    ("hh", "cpp"),
+    ("css", "css"),
+    ("go", "go"),
+    ("html", "html"),
+    ("htm", "html"),
+    ("java", "java"),
+    ("js", "javascript"),
+    ("mjs", "javascript"),
+    ("cjs", "javascript"),
+    ("jsx", "javascript"),
+    ("json", "json"),
+    ("md", "markdown"),
+    ("markdown", "markdown"),
+    ("php", "php"),
+    ("py", "python"),
+    ("rs", "rust"),
+    ("sh", "shell"),
+    ("bash", "shell"),
+    ("zsh", "shell"),
+    ("shellscript", "shell"),
+    ("sql", "sql"),
+    ("ts", "typescript"),
+    ("tsx", "typescript"),
+    ("vue", "vue"),
+    ("xml", "xml"),
+    ("yaml", "yaml"),
+    ("yml", "yaml"),
+    ("txt", "text"),
+];
+
+pub const MAX_TITLE_LENGTH: usize = 200;
+pub const MAX_TAGS: usize = 20;
+pub const MAX_TAG_LENGTH: usize = 50;
+/// 默认代码最大字节数（256 KB），可通过 user_settings 中的 max_snippet_code_size 调整
+pub const DEFAULT_MAX_CODE_SIZE: usize = 256 * 1024;
+
+/// 代码片段写入限制
+#[derive(Debug, Clone)]
+pub struct SnippetLimits {
+    pub max_code_size: usize,
+}
+
+impl Default for SnippetLimits {
+    fn default() -> Self {
+        Self {
+            max_code_size: DEFAULT_MAX_CODE_SIZE,
+        }
+    }
+}
+
+impl SnippetLimits {
+    /// 从 user_settings 读取限制，读取失败时使用默认值
+    pub async fn load(pool: &SqlitePool) -> Self {
+        let max_code_size = get_setting(pool, "max_snippet_code_size")
+            .await
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .filter(|size| *size > 0)
+            .unwrap_or(DEFAULT_MAX_CODE_SIZE);
+
+        Self { max_code_size }
+    }
+}
+
+/// 规范化标题：去除首尾空白，不允许为空
+pub fn normalize_title(title: &str) -> Result<String, String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(t("errors.titleEmpty"));
+    }
+    if title.chars().count() > MAX_TITLE_LENGTH {
+        return Err(t_with(
+            "errors.titleTooLong",
+            &[
+                ("length", &title.chars().count()),
+                ("max", &MAX_TITLE_LENGTH),
+            ],
+        ));
+    }
+    Ok(title.to_string())
+}
+
+/// 规范化语言：转小写、解析别名，并校验是否在支持列表中
+pub fn normalize_language(language: &str) -> Result<String, String> {
+    let language = language.trim().to_lowercase();
+    if language.is_empty() {
+        return Ok("text".to_string());
+    }
+
+    let language = LANGUAGE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == language)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or(language);
+
+    if KNOWN_LANGUAGES.contains(&language.as_str()) {
+        Ok(language)
+    } else {
+        Err(t_with(
+            "errors.unsupportedLanguage",
+            &[
+                ("language", &language),
+                ("expected", &KNOWN_LANGUAGES.join(", ")),
+            ],
+        ))
+    }
+}
+
+/// 根据文件扩展名推断语言
+pub fn language_for_extension(extension: &str) -> Option<&'static str> {
+    let extension = extension.trim_start_matches('.').to_lowercase();
+    EXTENSION_LANGUAGES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, language)| *language)
+}
+
+/// 语言对应的默认文件扩展名
+pub fn extension_for_language(language: &str) -> &'static str {
+    EXTENSION_LANGUAGES
+        .iter()
+        .find(|(_, lang)| *lang == language)
+        .map(|(ext, _)| *ext)
+        .unwrap_or("txt")
+}
+
+/// 宽松的语言规范化：无法识别时回退为 text（用于导入外部数据）
+pub fn normalize_language_or_text(language: &str) -> String {
+    normalize_language(language).unwrap_or_else(|_| "text".to_string())
+}
+
+/// 校验代码内容大小
+pub fn validate_code(code: &str, limits: &SnippetLimits) -> Result<(), String> {
+    if code.trim().is_empty() {
+        return Err(t("errors.codeEmpty"));
+    }
+    if code.len() > limits.max_code_size {
+        return Err(t_with(
+            "errors.codeTooLarge",
+            &[("size", &code.len()), ("max", &limits.max_code_size)],
+        ));
+    }
+    Ok(())
+}
+
+/// 规范化标签：去除空白、去重，并限制数量和长度
+pub fn normalize_tags(tags: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if tag.is_empty() || normalized.iter().any(|t| t == tag) {
+            continue;
+        }
+        if tag.chars().count() > MAX_TAG_LENGTH {
+            return Err(t_with(
+                "errors.tagTooLong",
+                &[("tag", &tag), ("max", &MAX_TAG_LENGTH)],
+            ));
+        }
+        normalized.push(tag.to_string());
+    }
+
+    if normalized.len() > MAX_TAGS {
+        return Err(t_with(
+            "errors.tooManyTags",
+            &[("count", &normalized.len()), ("max", &MAX_TAGS)],
+        ));
+    }
+    Ok(normalized)
+}
@@ -1,6 +1,6 @@
 import { ref, computed, readonly } from "vue";
 import { useI18n } from "vue-i18n";
-import type { ClipboardItem } from "../types";
+import type { ClipboardItem, ClipboardKind } from "../types";
 import {
   clipboardApi,
   clipboardManagerApi,
@@ -13,7 +13,6 @@ const error = ref<string | null>(null);
 
 // 监听状态
 const isMonitoring = ref(false);
-const lastClipboardContent = ref<string>("");
 let monitoringInterval: number | null = null;
 
 // 计算属性
@@ -22,31 +21,16 @@ const recentItems = computed(() => clipboardItems.value.slice(0, 10));
 
 export function useClipboard() {
   const { t } = useI18n();
-  // 检查剪贴板内容变化
+  // 检查剪贴板内容变化（读取、去重和记录规则均由 Rust 端处理）
   const checkClipboardChange = async () => {
     try {
-      const currentContent = await clipboardManagerApi.readText();
-
-      // 检查内容是否有变化
-      if (
-        currentContent &&
-        currentContent.trim() &&
-        currentContent !== lastClipboardContent.value &&
-        currentContent.trim().length >= 3
-      ) {
-        // 检查是否已存在相同内容（避免重复）
-        const existing = clipboardItems.value.find(
-          (item) => item.content === currentContent
+      const newItem = await clipboardApi.captureSystem();
+      if (newItem) {
+        console.log(
+          "New clipboard content detected:",
+          newItem.content.substring(0, 50) + "..."
         );
-        if (!existing) {
-          console.log(
-            "New clipboard content detected:",
-            currentContent.substring(0, 50) + "..."
-          );
-          await addClipboardItem(currentContent);
-        }
-
-        lastClipboardContent.value = currentContent;
+        clipboardItems.value = await clipboardApi.getHistory();
       }
     } catch (err) {
       // 静默处理错误，避免频繁报错
@@ -64,16 +48,6 @@ export function useClipboard() {
 
       // 获取剪贴板历史
       clipboardItems.value = await clipboardApi.getHistory();
-
-      // 获取当前剪贴板内容作为初始值
-      try {
-        const currentContent = await clipboardManagerApi.readText();
-        if (currentContent) {
-          lastClipboardContent.value = currentContent;
-        }
-      } catch (err) {
-        console.warn("Failed to get initial clipboard content:", err);
-      }
     } catch (err) {
       console.error("Failed to initialize clipboard:", err);
       error.value =
@@ -120,8 +94,7 @@ export function useClipboard() {
     try {
       const newItem = await clipboardApi.add(content);
 
-      // 重新从数据库获取最新列表，确保与数据库同步
-      // 因为添加新项时可能已经删除了超出限制的旧数据
+      // 重新从数据库获取最新列表，确保与数据库同步（置顶的条目排在最前）
       const updatedItems = await clipboardApi.getHistory();
       clipboardItems.value = updatedItems;
 
@@ -134,6 +107,48 @@ export function useClipboard() {
     }
   };
 
+  // 把剪贴板项转换为代码片段（由 Rust 端检测语言并建议标题）
+  const promoteClipboardItem = async (
+    id: number,
+    options: { title?: string; tags?: string[]; deleteItem?: boolean } = {}
+  ) => {
+    error.value = null;
+
+    try {
+      const snippet = await clipboardApi.promote(id, options);
+      if (options.deleteItem) {
+        clipboardItems.value = await clipboardApi.getHistory();
+        if (selectedClipboardItem.value?.id === id) {
+          selectedClipboardItem.value = null;
+        }
+      }
+      return snippet;
+    } catch (err) {
+      console.error("Failed to promote clipboard item:", err);
+      error.value = err instanceof Error ? err.message : String(err);
+      throw err;
+    }
+  };
+
+  // 置顶或取消置顶剪贴板项（置顶的条目排在最前，且不会被自动清理）
+  const toggleClipboardItemPinned = async (item: ClipboardItem) => {
+    if (item.id === undefined) return;
+    error.value = null;
+
+    try {
+      const pinned = !item.pinned;
+      await clipboardApi.setPinned([item.id], pinned);
+      clipboardItems.value = await clipboardApi.getHistory();
+      if (selectedClipboardItem.value?.id === item.id) {
+        selectedClipboardItem.value = { ...selectedClipboardItem.value, pinned };
+      }
+    } catch (err) {
+      console.error("Failed to update clipboard pinned state:", err);
+      error.value = err instanceof Error ? err.message : String(err);
+      throw err;
+    }
+  };
+
   // 删除剪贴板项
   const deleteClipboardItem = async (id: number) => {
     error.value = null;
@@ -184,12 +199,21 @@ export function useClipboard() {
   // 复制到系统剪贴板
   const copyToSystemClipboard = async (content: string) => {
     const { copyText } = await import("../utils/clipboard");
-    const success = await copyText(content);
-    if (success) {
-      // 更新最后的剪贴板内容，避免重复检测
-      lastClipboardContent.value = content;
+    return await copyText(content);
+  };
+
+  // 按原类型恢复剪贴板历史项到系统剪贴板
+  const restoreToSystemClipboard = async (item: ClipboardItem) => {
+    if (item.id === undefined || !item.kind || item.kind === "text") {
+      return await copyToSystemClipboard(item.content);
+    }
+    try {
+      await clipboardManagerApi.restoreItem(item.id);
+      return true;
+    } catch (err) {
+      console.error("Failed to restore clipboard item:", err);
+      return false;
     }
-    return success;
   };
 
   // 获取单个剪贴板项
@@ -205,17 +229,21 @@ export function useClipboard() {
     }
   };
 
-  // 搜索剪贴板历史
+  // 搜索剪贴板历史（由 Rust 端查询，可搜索超出当前列表的历史）
   const searchClipboard = async (query: string) => {
     if (!query.trim()) {
       await initializeClipboard();
       return;
     }
 
-    const searchTerm = query.toLowerCase();
-    clipboardItems.value = clipboardItems.value.filter((item) =>
-      item.content.toLowerCase().includes(searchTerm)
-    );
+    error.value = null;
+    try {
+      const page = await clipboardApi.search({ text: query });
+      clipboardItems.value = page.items;
+    } catch (err) {
+      console.error("Failed to search clipboard history:", err);
+      error.value = err instanceof Error ? err.message : String(err);
+    }
   };
 
   // 按类型过滤
@@ -225,11 +253,16 @@ export function useClipboard() {
       return;
     }
 
-    // 由于移除了 content_type 字段，这里可以简化为基于内容的过滤
-    const query = contentType.toLowerCase();
-    clipboardItems.value = clipboardItems.value.filter((item) =>
-      item.content.toLowerCase().includes(query)
-    );
+    error.value = null;
+    try {
+      const page = await clipboardApi.search({
+        kinds: [contentType as ClipboardKind],
+      });
+      clipboardItems.value = page.items;
+    } catch (err) {
+      console.error("Failed to filter clipboard history:", err);
+      error.value = err instanceof Error ? err.message : String(err);
+    }
   };
 
   // 清除错误
@@ -256,10 +289,13 @@ export function useClipboard() {
     // 方法
     initializeClipboard,
     addClipboardItem,
+    promoteClipboardItem,
+    toggleClipboardItemPinned,
     deleteClipboardItem,
     clearClipboardHistory,
     selectClipboardItem,
     copyToSystemClipboard,
+    restoreToSystemClipboard,
     getClipboardItemById,
     startClipboardMonitoring,
     stopClipboardMonitoring,
@@ -1,26 +1,34 @@
 import { ref, computed } from "vue";
 import { useI18n } from "vue-i18n";
-import { useSettingsDatabase } from "./useSettingsDatabase";
+import { clipboardApi } from "../services/tauri";
 import { toast } from "./useToast";
+import type { RetentionPolicy, RetentionReport } from "../types";
 
-// 剪贴板设置状态
-const maxClipboardItems = ref(100);
+// 剪贴板设置状态（保留策略由 Rust 端后台任务定期执行）
+const retentionPolicy = ref<RetentionPolicy>({
+  max_items: 100,
+  max_age_days: 0,
+  max_total_mb: 0,
+  keep_pinned: true,
+});
+const lastReport = ref<RetentionReport | null>(null);
 const error = ref<string | null>(null);
 const loading = ref(false);
 
 export function useClipboardSettings() {
   const { t } = useI18n();
-  const { saveSetting, getSetting } = useSettingsDatabase();
 
-  // 保存剪贴板设置
-  const saveClipboardSettings = async (maxItems: number): Promise<boolean> => {
+  // 保存剪贴板保留策略（保存后立即清理一次）
+  const saveClipboardSettings = async (
+    policy: RetentionPolicy
+  ): Promise<boolean> => {
     loading.value = true;
     error.value = null;
 
     try {
-      // 保存设置到数据库
-      await saveSetting("max_clipboard_items", maxItems.toString());
-      maxClipboardItems.value = maxItems;
+      const status = await clipboardApi.setRetention(policy);
+      retentionPolicy.value = status.policy;
+      lastReport.value = status.last_report;
 
       toast.success(t("settings.clipboardSettingsSaved"));
       return true;
@@ -37,13 +45,12 @@ export function useClipboardSettings() {
     }
   };
 
-  // 加载剪贴板设置
+  // 加载剪贴板保留策略
   const loadClipboardSettings = async (): Promise<void> => {
     try {
-      const setting = await getSetting("max_clipboard_items");
-      if (setting) {
-        maxClipboardItems.value = parseInt(setting) || 100;
-      }
+      const status = await clipboardApi.getRetention();
+      retentionPolicy.value = status.policy;
+      lastReport.value = status.last_report;
     } catch (err) {
       console.error("Failed to load clipboard settings:", err);
       error.value =
@@ -65,7 +72,9 @@ export function useClipboardSettings() {
 
   return {
     // 状态
-    maxClipboardItems: computed(() => maxClipboardItems.value),
+    retentionPolicy: computed(() => retentionPolicy.value),
+    maxClipboardItems: computed(() => retentionPolicy.value.max_items),
+    lastReport: computed(() => lastReport.value),
     error: computed(() => error.value),
     loading: computed(() => loading.value),
 
@@ -0,0 +1,134 @@
+import { ref, computed } from "vue";
+import { useI18n } from "vue-i18n";
+import { confirm } from "@tauri-apps/plugin-dialog";
+import { encryptionApi, maintenanceApi } from "../services/tauri";
+import { toast } from "./useToast";
+import type { EncryptionStatus } from "../types";
+
+// 数据库加密状态（全局共享，锁定后由解锁弹窗提示输入口令）
+const status = ref<EncryptionStatus>({
+  enabled: false,
+  unlocked: true,
+  remembered: false,
+});
+const loading = ref(false);
+
+// 解锁、启用或关闭加密后通知首页重新加载数据
+const refreshData = () => {
+  for (const type of ["snippets", "clipboard"]) {
+    window.dispatchEvent(
+      new CustomEvent("seekcode:data-refresh", { detail: { type } })
+    );
+  }
+};
+
+const errorMessage = (err: unknown) =>
+  err instanceof Error ? err.message : String(err);
+
+export function useEncryption() {
+  const { t } = useI18n();
+
+  // 加载加密状态
+  const loadStatus = async (): Promise<void> => {
+    try {
+      status.value = await encryptionApi.getStatus();
+    } catch (err) {
+      console.error("Failed to load encryption status:", err);
+    }
+  };
+
+  // 执行加密操作，统一处理加载状态和错误提示
+  const run = async (
+    action: () => Promise<EncryptionStatus>,
+    successKey: string
+  ): Promise<boolean> => {
+    loading.value = true;
+    try {
+      status.value = await action();
+      toast.success(t(successKey));
+      return true;
+    } catch (err) {
+      toast.error(errorMessage(err));
+      return false;
+    } finally {
+      loading.value = false;
+    }
+  };
+
+  // 启用加密前创建的自动备份仍是明文，提示用户删除
+  const warnPlaintextBackups = async (): Promise<void> => {
+    try {
+      const { backups } = await maintenanceApi.getAutoBackupStatus();
+      if (backups.length === 0) return;
+
+      const confirmed = await confirm(
+        t("encryption.plaintextBackups", { count: backups.length }),
+        { title: t("encryption.plaintextBackupsTitle"), kind: "warning" }
+      );
+      if (!confirmed) return;
+
+      const deleted = await maintenanceApi.deleteAutoBackups();
+      toast.success(t("encryption.backupsDeleted", { count: deleted }));
+    } catch (err) {
+      console.error("Failed to check plaintext backups:", err);
+      toast.error(errorMessage(err));
+    }
+  };
+
+  // 启用加密并加密现有数据
+  const enable = async (
+    passphrase: string,
+    remember: boolean
+  ): Promise<boolean> => {
+    const enabled = await run(
+      () => encryptionApi.enable(passphrase, remember),
+      "encryption.enabledSuccess"
+    );
+    if (enabled) {
+      refreshData();
+      await warnPlaintextBackups();
+    }
+    return enabled;
+  };
+
+  // 关闭加密并解密全部数据
+  const disable = async (passphrase: string): Promise<boolean> => {
+    const disabled = await run(
+      () => encryptionApi.disable(passphrase),
+      "encryption.disabledSuccess"
+    );
+    if (disabled) refreshData();
+    return disabled;
+  };
+
+  // 用口令解锁；remember 同时更新是否把密钥保存在系统钥匙串中
+  const unlock = async (
+    passphrase: string,
+    remember: boolean
+  ): Promise<boolean> => {
+    const unlocked = await run(
+      () => encryptionApi.unlock(passphrase, remember),
+      "encryption.unlockedSuccess"
+    );
+    if (unlocked) refreshData();
+    return unlocked;
+  };
+
+  // 锁定数据库
+  const lock = async (): Promise<boolean> =>
+    run(() => encryptionApi.lock(), "encryption.lockedSuccess");
+
+  return {
+    // 状态
+    status: computed(() => status.value),
+    locked: computed(() => status.value.enabled && !status.value.unlocked),
+    loading: computed(() => loading.value),
+
+    // 方法
+    loadStatus,
+    enable,
+    disable,
+    unlock,
+    lock,
+  };
+}
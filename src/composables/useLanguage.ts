@@ -2,6 +2,7 @@ import { ref, computed } from "vue";
 import { useI18n } from "vue-i18n";
 import { useSettingsDatabase } from "./useSettingsDatabase";
 import { getSystemLocale } from "../i18n";
+import { localeApi } from "../services/tauri";
 
 // 可用语言列表
 export type SupportedLocale = "zh-CN" | "en-US" | "system";
@@ -38,7 +39,7 @@ export function getLanguageOptions(
 // 语言设置管理
 export function useLanguage() {
   const { locale, t } = useI18n();
-  const { saveSetting, getSetting } = useSettingsDatabase();
+  const { getSetting } = useSettingsDatabase();
 
   // 当前选择的语言设置（可能是 system）
   const currentLanguageSetting = ref<SupportedLocale>("system");
@@ -93,7 +94,8 @@ export function useLanguage() {
     error.value = null;
 
     try {
-      await saveSetting("language", language);
+      // 由 Rust 端保存，同时切换托盘菜单等原生界面的语言
+      await localeApi.setLanguage(language);
       currentLanguageSetting.value = language;
       locale.value = actualLocale.value;
       return true;
@@ -215,7 +215,20 @@ export function useSnippets() {
   // 复制代码到剪贴板
   const copySnippetCode = async (snippet: CodeSnippet) => {
     const { copyText } = await import("../utils/clipboard");
-    return await copyText(snippet.code);
+    const success = await copyText(snippet.code);
+    if (success && snippet.id) {
+      await recordSnippetUse(snippet.id);
+    }
+    return success;
+  };
+
+  // 记录代码片段使用（复制、插入），用于置顶和 frecency 排序
+  const recordSnippetUse = async (id: number) => {
+    await snippetApi.recordUse(id);
+    const snippet = snippets.value.find((s) => s.id === id);
+    if (snippet) {
+      snippet.use_count = (snippet.use_count ?? 0) + 1;
+    }
   };
 
   // 清除错误
@@ -248,6 +261,7 @@ export function useSnippets() {
     selectSnippet,
     getSnippetById,
     copySnippetCode,
+    recordSnippetUse,
     clearError,
     refresh,
   };
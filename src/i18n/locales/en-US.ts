@@ -122,6 +122,9 @@ export default {
     selectItem: "Select a clipboard item",
     viewHistory: "View clipboard history content",
     placeholder: "Clipboard content...",
+    pin: "Pin",
+    unpin: "Unpin",
+    pinned: "Pinned",
   },
 
   // Toast notifications
@@ -175,6 +178,10 @@ export default {
     // Language settings
     languageMode: "Language",
     languageDesc: "Select application interface language",
+    paletteShortcut: "Quick Paste Shortcut",
+    paletteShortcutDesc:
+      "Global shortcut that opens the quick paste palette, e.g. CommandOrControl+Shift+Space. Leave empty to disable.",
+    paletteShortcutSaved: "Shortcut saved",
     chinese: "中文",
     english: "English",
     systemLanguage: "Follow System",
@@ -268,7 +275,11 @@ export default {
     maxClipboardItems: "Max Retention Count",
     maxClipboardItemsPlaceholder: "Enter max clipboard items to retain",
     maxClipboardItemsDesc:
-      "Set the maximum number of clipboard history records to retain. Old records beyond this limit will be automatically deleted.",
+      "Old records beyond the count, age or size limits are deleted periodically in the background. Use 0 for no limit.",
+    clipboardMaxAgeDays: "Max Age (days)",
+    clipboardMaxTotalMb: "Max Total Size (MB)",
+    clipboardKeepPinned: "Keep pinned records forever",
+    clipboardRetentionLastRun: "Last cleanup: {time}, {count} removed",
     clearClipboardData: "Clear Clipboard Data",
     clearClipboardDataDesc:
       "Delete all clipboard history records. This action cannot be undone.",
@@ -316,6 +327,49 @@ export default {
     updating: "Updating...",
   },
 
+  // Database encryption
+  encryption: {
+    title: "Database Encryption",
+    description:
+      "Encrypt snippet code and clipboard history with a passphrase. The passphrase cannot be recovered if you forget it.",
+    statusDisabled: "Not encrypted",
+    statusUnlocked: "Unlocked",
+    statusLocked: "Locked",
+    passphrase: "Passphrase",
+    confirmPassphrase: "Confirm passphrase",
+    passphraseMismatch: "The passphrases do not match",
+    remember: "Remember key in the system keychain",
+    rememberDesc:
+      "When remembered, the database is unlocked automatically at startup.",
+    enable: "Enable Encryption",
+    disable: "Disable Encryption",
+    lock: "Lock Now",
+    unlock: "Unlock",
+    saveRemember: "Save",
+    confirmEnable:
+      "Encrypt all snippets and clipboard history now? Without the passphrase the data cannot be read.",
+    confirmDisable:
+      "Decrypt all data and disable encryption? Data will be stored as plain text.",
+    enabledSuccess: "Encryption enabled",
+    disabledSuccess: "Encryption disabled",
+    unlockedSuccess: "Database unlocked",
+    lockedSuccess: "Database locked",
+    unlockTitle: "Database Locked",
+    unlockDesc:
+      "Your snippets and clipboard history are encrypted. Enter the passphrase to unlock them.",
+    plaintextBackupsTitle: "Unencrypted Backups",
+    plaintextBackups:
+      "{count} automatic backups were created before encryption was enabled and still contain plain text. Delete them now?",
+    backupsDeleted: "Deleted {count} unencrypted backups",
+  },
+
+  // Quick paste palette
+  palette: {
+    placeholder: "Search snippets and clipboard history...",
+    noResults: "No matching items",
+    hint: "↑↓ to select · Enter to copy · Esc to close",
+  },
+
   // Shortcuts
   shortcuts: {
     title: "Shortcuts",
@@ -120,6 +120,9 @@ export default {
     selectItem: "选择一个剪贴板项目",
     viewHistory: "查看剪贴板历史内容",
     placeholder: "剪贴板内容...",
+    pin: "置顶",
+    unpin: "取消置顶",
+    pinned: "已置顶",
   },
 
   // Toast 通知
@@ -172,6 +175,10 @@ export default {
     // 语言设置
     languageMode: "语言",
     languageDesc: "选择应用界面语言",
+    paletteShortcut: "快速粘贴快捷键",
+    paletteShortcutDesc:
+      "打开快速粘贴面板的全局快捷键，如 CommandOrControl+Shift+Space，留空表示不启用。",
+    paletteShortcutSaved: "快捷键已保存",
     chinese: "中文",
     english: "English",
     systemLanguage: "跟随系统",
@@ -233,7 +240,11 @@ export default {
     maxClipboardItems: "最大保留条数",
     maxClipboardItemsPlaceholder: "输入保留的剪贴板条数",
     maxClipboardItemsDesc:
-      "设置剪贴板历史记录的最大保留条数，超出限制的旧记录将被自动删除。",
+      "超出保留条数、天数或空间的旧记录会在后台定期删除，填 0 表示不限制。",
+    clipboardMaxAgeDays: "最长保留天数",
+    clipboardMaxTotalMb: "最大占用空间（MB）",
+    clipboardKeepPinned: "置顶的记录永久保留",
+    clipboardRetentionLastRun: "上次清理：{time}，删除 {count} 条",
     clearClipboardData: "清空剪贴板数据",
     clearClipboardDataDesc: "删除所有剪贴板历史记录，此操作不可恢复。",
     clearClipboard: "清空剪贴板",
@@ -315,6 +326,42 @@ export default {
     updateFailed: "更新失败，请稍后重试",
     restartFailed: "重启失败",
   },
+  encryption: {
+    title: "数据库加密",
+    description:
+      "使用口令加密代码片段和剪贴板历史。忘记口令后数据将无法恢复。",
+    statusDisabled: "未加密",
+    statusUnlocked: "已解锁",
+    statusLocked: "已锁定",
+    passphrase: "口令",
+    confirmPassphrase: "确认口令",
+    passphraseMismatch: "两次输入的口令不一致",
+    remember: "在系统钥匙串中记住密钥",
+    rememberDesc: "记住密钥后，启动时会自动解锁数据库。",
+    enable: "启用加密",
+    disable: "关闭加密",
+    lock: "立即锁定",
+    unlock: "解锁",
+    saveRemember: "保存",
+    confirmEnable:
+      "现在加密全部代码片段和剪贴板历史吗？没有口令将无法读取这些数据。",
+    confirmDisable: "解密全部数据并关闭加密吗？数据将以明文保存。",
+    enabledSuccess: "已启用加密",
+    disabledSuccess: "已关闭加密",
+    unlockedSuccess: "数据库已解锁",
+    lockedSuccess: "数据库已锁定",
+    unlockTitle: "数据库已锁定",
+    unlockDesc: "代码片段和剪贴板历史已加密，请输入口令解锁。",
+    plaintextBackupsTitle: "未加密的备份",
+    plaintextBackups:
+      "有 {count} 个自动备份创建于启用加密之前，仍包含明文数据。现在删除它们吗？",
+    backupsDeleted: "已删除 {count} 个未加密的备份",
+  },
+  palette: {
+    placeholder: "搜索代码片段和剪贴板历史...",
+    noResults: "没有匹配的内容",
+    hint: "↑↓ 选择 · Enter 复制 · Esc 关闭",
+  },
   shortcuts: {
     title: "快捷键",
     newSnippet: "新建代码片段",
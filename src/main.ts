@@ -1,13 +1,18 @@
 import { createApp } from "vue";
 import App from "./App.vue";
+import QuickPalette from "./components/QuickPalette.vue";
 import "./style.css";
 import "@fortawesome/fontawesome-free/css/all.css";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import i18n from "./i18n";
 
+// 快速粘贴面板与主窗口共用同一入口，通过 ?window=palette 区分
+const isPalette =
+  new URLSearchParams(window.location.search).get("window") === "palette";
+
 // 创建 Vue 应用
-const app = createApp(App);
+const app = createApp(isPalette ? QuickPalette : App);
 
 // 配置国际化
 app.use(i18n);
@@ -15,8 +20,9 @@ app.use(i18n);
 // 挂载应用
 app.mount("#app");
 
-// 监听窗口获得焦点事件，这可以帮助处理dock图标点击
+// 监听窗口获得焦点事件，这可以帮助处理dock图标点击（面板窗口不处理）
 window.addEventListener("focus", async () => {
+  if (isPalette) return;
   try {
     // 当窗口获得焦点时，确保窗口显示
     const isVisible = await invoke("is_window_visible");
@@ -30,6 +36,7 @@ window.addEventListener("focus", async () => {
 
 // 监听Tauri应用激活事件（如果有的话）
 (async () => {
+  if (isPalette) return;
   try {
     await listen("tauri://focus", () => {
       // 当应用获得焦点时显示窗口
@@ -1,6 +1,30 @@
 import Database from "@tauri-apps/plugin-sql";
 import { invoke } from "@tauri-apps/api/core";
-import type { CodeSnippet, ClipboardItem } from "../types";
+import type {
+  CodeSnippet,
+  ClipboardItem,
+  Collection,
+  CollectionNode,
+  DatabaseStats,
+  BackupInfo,
+  OptimizeReport,
+  IntegrityReport,
+  AutoBackupStatus,
+  RestoreReport,
+  EncryptionStatus,
+  SecretFinding,
+  SecretKind,
+  LanguageDetection,
+  CaptureRules,
+  ClipboardCaptureStatus,
+  ClipboardQuery,
+  ClipboardPage,
+  RetentionPolicy,
+  RetentionReport,
+  ClipboardRetentionStatus,
+  PaletteItem,
+  PaletteSource,
+} from "../types";
 
 // 数据库实例
 let db: Database | null = null;
@@ -59,6 +83,7 @@ function parseJsonSafely<T>(jsonStr: string, fallback: T): T {
 
 export interface CreateSnippetRequest {
   title: string;
+  // 为空或 "auto" 时由 Rust 端根据代码检测
   language: string;
   code: string;
   tags: readonly string[];
@@ -71,67 +96,128 @@ export interface UpdateSnippetRequest {
   tags?: readonly string[];
 }
 
+// 代码片段数据库行（tags 为 JSON 字符串，pinned 直接查询时为 0/1）
+interface RawSnippetRow {
+  id: number;
+  title: string;
+  language: string;
+  code: string;
+  tags: string;
+  created_at: string;
+  updated_at: string;
+  collection_id?: number | null;
+  pinned?: boolean | number;
+  use_count?: number;
+  last_used_at?: string | null;
+  secret_flags?: string | null;
+}
+
+function fromRawSnippetRow(row: RawSnippetRow): CodeSnippet {
+  return {
+    id: row.id,
+    title: row.title,
+    language: row.language,
+    code: row.code,
+    tags: parseJsonSafely<string[]>(row.tags, []),
+    created_at: row.created_at,
+    updated_at: row.updated_at,
+    collection_id: row.collection_id ?? null,
+    pinned: Boolean(row.pinned),
+    use_count: row.use_count ?? 0,
+    last_used_at: row.last_used_at ?? null,
+    secret_flags: parseJsonSafely<SecretKind[]>(row.secret_flags ?? "[]", []),
+  };
+}
+
 // 代码片段 API
 export const snippetApi = {
-  // 创建代码片段
+  // 创建代码片段（由 Rust 端校验后写入）
   async create(request: CreateSnippetRequest): Promise<CodeSnippet> {
     try {
-      const database = await getDatabase();
-      const timestamp = await getCurrentTimestamp();
-      const tagsJson = JSON.stringify(request.tags);
-
-      const result = await database.execute(
-        "INSERT INTO code_snippets (title, language, code, tags, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6)",
-        [
-          request.title,
-          request.language,
-          request.code,
-          tagsJson,
-          timestamp,
-          timestamp,
-        ]
-      );
-
-      return {
-        id: result.lastInsertId!,
-        title: request.title,
-        language: request.language,
-        code: request.code,
-        tags: request.tags,
-        created_at: timestamp,
-        updated_at: timestamp,
-      };
+      const row = await invoke<RawSnippetRow>("create_snippet", {
+        snippet: {
+          title: request.title,
+          language: request.language,
+          code: request.code,
+          tags: request.tags,
+        },
+      });
+      return fromRawSnippetRow(row);
     } catch (error) {
       console.error("Failed to create snippet:", error);
       throw error;
     }
   },
 
-  // 获取所有代码片段
+  // 批量删除代码片段（单个事务）
+  async bulkDelete(ids: number[]): Promise<number> {
+    try {
+      return await invoke<number>("bulk_delete_snippets", { ids });
+    } catch (error) {
+      console.error("Failed to bulk delete snippets:", error);
+      throw error;
+    }
+  },
+
+  // 批量添加标签
+  async bulkAddTags(ids: number[], tags: string[]): Promise<number> {
+    try {
+      return await invoke<number>("bulk_add_tags", { ids, tags });
+    } catch (error) {
+      console.error("Failed to bulk add tags:", error);
+      throw error;
+    }
+  },
+
+  // 批量移除标签
+  async bulkRemoveTags(ids: number[], tags: string[]): Promise<number> {
+    try {
+      return await invoke<number>("bulk_remove_tags", { ids, tags });
+    } catch (error) {
+      console.error("Failed to bulk remove tags:", error);
+      throw error;
+    }
+  },
+
+  // 批量修改字段（如语言）
+  async bulkUpdate(ids: number[], request: UpdateSnippetRequest): Promise<number> {
+    try {
+      return await invoke<number>("bulk_update_snippets", {
+        ids,
+        patch: request,
+      });
+    } catch (error) {
+      console.error("Failed to bulk update snippets:", error);
+      throw error;
+    }
+  },
+
+  // 记录一次使用（复制、插入）
+  async recordUse(id: number): Promise<void> {
+    try {
+      await invoke("record_snippet_use", { id });
+    } catch (error) {
+      console.error("Failed to record snippet use:", error);
+    }
+  },
+
+  // 设置或取消置顶
+  async setPinned(ids: number[], pinned: boolean): Promise<number> {
+    try {
+      return await invoke<number>("set_snippets_pinned", { ids, pinned });
+    } catch (error) {
+      console.error("Failed to update pinned state:", error);
+      throw error;
+    }
+  },
+
+  // 获取所有代码片段（经 Rust 端读取，启用加密时已解密）
   async getAll(): Promise<CodeSnippet[]> {
     try {
-      const database = await getDatabase();
-      const rows = await database.select<
-        Array<{
-          id: number;
-          title: string;
-          language: string;
-          code: string;
-          tags: string;
-          created_at: string;
-          updated_at: string;
-        }>
-      >("SELECT * FROM code_snippets ORDER BY created_at DESC");
-
-      return rows.map((row) => ({
-        id: row.id,
-        title: row.title,
-        language: row.language,
-        code: row.code,
-        tags: parseJsonSafely<string[]>(row.tags, []),
-        created_at: row.created_at,
-        updated_at: row.updated_at,
-      }));
+      const rows = await invoke<RawSnippetRow[]>("list_snippets", {
+        filter: { sort: "created" },
+      });
+      return rows.map(fromRawSnippetRow);
     } catch (error) {
       console.error("Failed to get all snippets:", error);
       throw error;
@@ -141,60 +227,26 @@ export const snippetApi = {
   // 根据 ID 获取代码片段
   async getById(id: number): Promise<CodeSnippet | null> {
     try {
-      const database = await getDatabase();
-      const rows = await database.select<
-        Array<{
-          id: number;
-          title: string;
-          language: string;
-          code: string;
-          tags: string;
-          created_at: string;
-          updated_at: string;
-        }>
-      >("SELECT * FROM code_snippets WHERE id = $1", [id]);
-
-      if (rows.length === 0) {
-        return null;
-      }
-
-      const row = rows[0];
-      return {
-        id: row.id,
-        title: row.title,
-        language: row.language,
-        code: row.code,
-        tags: parseJsonSafely(row.tags, []),
-        created_at: row.created_at,
-        updated_at: row.updated_at,
-      };
+      const row = await invoke<RawSnippetRow | null>("get_snippet", { id });
+      return row ? fromRawSnippetRow(row) : null;
     } catch (error) {
       console.error("Failed to get snippet by id:", error);
       throw error;
     }
   },
 
-  // 更新代码片段
+  // 更新代码片段（由 Rust 端校验后写入）
   async update(id: number, request: UpdateSnippetRequest): Promise<void> {
     try {
-      const database = await getDatabase();
-      const current = await this.getById(id);
-
-      if (!current) {
-        throw new Error("Snippet not found");
-      }
-
-      const title = request.title ?? current.title;
-      const language = request.language ?? current.language;
-      const code = request.code ?? current.code;
-      const tags = request.tags ?? current.tags;
-      const tagsJson = JSON.stringify(tags);
-      const timestamp = await getCurrentTimestamp();
-
-      await database.execute(
-        "UPDATE code_snippets SET title = $1, language = $2, code = $3, tags = $4, updated_at = $5 WHERE id = $6",
-        [title, language, code, tagsJson, timestamp, id]
-      );
+      await invoke("update_snippet", {
+        id,
+        patch: {
+          title: request.title,
+          language: request.language,
+          code: request.code,
+          tags: request.tags,
+        },
+      });
     } catch (error) {
       console.error("Failed to update snippet:", error);
       throw error;
@@ -204,48 +256,18 @@ export const snippetApi = {
   // 删除代码片段
   async delete(id: number): Promise<void> {
     try {
-      const database = await getDatabase();
-      await database.execute("DELETE FROM code_snippets WHERE id = $1", [id]);
+      await invoke<number>("bulk_delete_snippets", { ids: [id] });
     } catch (error) {
       console.error("Failed to delete snippet:", error);
       throw error;
     }
   },
 
-  // 搜索代码片段
+  // 搜索代码片段（标题、代码、标签）
   async search(query: string): Promise<CodeSnippet[]> {
     try {
-      const database = await getDatabase();
-      const searchTerm = `%${query.toLowerCase()}%`;
-
-      const rows = await database.select<
-        Array<{
-          id: number;
-          title: string;
-          language: string;
-          code: string;
-          tags: string;
-          created_at: string;
-          updated_at: string;
-        }>
-      >(
-        `SELECT * FROM code_snippets 
-         WHERE LOWER(title) LIKE $1 
-            OR LOWER(code) LIKE $2 
-            OR LOWER(tags) LIKE $3 
-         ORDER BY created_at DESC`,
-        [searchTerm, searchTerm, searchTerm]
-      );
-
-      return rows.map((row) => ({
-        id: row.id,
-        title: row.title,
-        language: row.language,
-        code: row.code,
-        tags: parseJsonSafely(row.tags, []),
-        created_at: row.created_at,
-        updated_at: row.updated_at,
-      }));
+      const rows = await invoke<RawSnippetRow[]>("search_snippets", { query });
+      return rows.map(fromRawSnippetRow);
     } catch (error) {
       console.error("Failed to search snippets:", error);
       throw error;
@@ -255,31 +277,10 @@ export const snippetApi = {
   // 根据语言获取代码片段
   async getByLanguage(language: string): Promise<CodeSnippet[]> {
     try {
-      const database = await getDatabase();
-      const rows = await database.select<
-        Array<{
-          id: number;
-          title: string;
-          language: string;
-          code: string;
-          tags: string;
-          created_at: string;
-          updated_at: string;
-        }>
-      >(
-        "SELECT * FROM code_snippets WHERE language = $1 ORDER BY created_at DESC",
-        [language]
-      );
-
-      return rows.map((row) => ({
-        id: row.id,
-        title: row.title,
-        language: row.language,
-        code: row.code,
-        tags: parseJsonSafely(row.tags, []),
-        created_at: row.created_at,
-        updated_at: row.updated_at,
-      }));
+      const rows = await invoke<RawSnippetRow[]>("list_snippets", {
+        filter: { language, sort: "created" },
+      });
+      return rows.map(fromRawSnippetRow);
     } catch (error) {
       console.error("Failed to get snippets by language:", error);
       throw error;
@@ -313,41 +314,95 @@ export const snippetApi = {
     }
   },
 
-  // 根据标签获取代码片段
+  // 根据标签获取代码片段（需包含全部指定标签）
   async getByTags(tags: string[]): Promise<CodeSnippet[]> {
     try {
-      const database = await getDatabase();
+      const rows = await invoke<RawSnippetRow[]>("list_snippets", {
+        filter: { tags, sort: "created" },
+      });
+      return rows.map(fromRawSnippetRow);
+    } catch (error) {
+      console.error("Failed to get snippets by tags:", error);
+      throw error;
+    }
+  },
+};
 
-      // 构建查询条件，要求包含所有指定的标签
-      const conditions = tags.map(() => "LOWER(tags) LIKE ?").join(" AND ");
-      const searchTerms = tags.map((tag) => `%"${tag.toLowerCase()}"%`);
+// ==================== 集合相关 API ====================
 
-      const rows = await database.select<
-        Array<{
-          id: number;
-          title: string;
-          language: string;
-          code: string;
-          tags: string;
-          created_at: string;
-          updated_at: string;
-        }>
-      >(
-        `SELECT * FROM code_snippets WHERE ${conditions} ORDER BY created_at DESC`,
-        searchTerms
-      );
+export const collectionApi = {
+  // 获取集合树
+  async getTree(): Promise<CollectionNode[]> {
+    try {
+      return await invoke<CollectionNode[]>("list_collections");
+    } catch (error) {
+      console.error("Failed to get collections:", error);
+      throw error;
+    }
+  },
 
-      return rows.map((row) => ({
-        id: row.id,
-        title: row.title,
-        language: row.language,
-        code: row.code,
-        tags: parseJsonSafely(row.tags, []),
-        created_at: row.created_at,
-        updated_at: row.updated_at,
-      }));
+  // 创建集合
+  async create(name: string, parentId: number | null = null): Promise<Collection> {
+    try {
+      return await invoke<Collection>("create_collection", { name, parentId });
     } catch (error) {
-      console.error("Failed to get snippets by tags:", error);
+      console.error("Failed to create collection:", error);
+      throw error;
+    }
+  },
+
+  // 重命名集合
+  async rename(id: number, name: string): Promise<Collection> {
+    try {
+      return await invoke<Collection>("rename_collection", { id, name });
+    } catch (error) {
+      console.error("Failed to rename collection:", error);
+      throw error;
+    }
+  },
+
+  // 移动集合（parentId 为 null 时移到顶层）
+  async move(id: number, parentId: number | null): Promise<Collection> {
+    try {
+      return await invoke<Collection>("move_collection", { id, parentId });
+    } catch (error) {
+      console.error("Failed to move collection:", error);
+      throw error;
+    }
+  },
+
+  // 删除集合及其子集合，其中的代码片段移到上级集合
+  async delete(id: number): Promise<number> {
+    try {
+      return await invoke<number>("delete_collection", { id });
+    } catch (error) {
+      console.error("Failed to delete collection:", error);
+      throw error;
+    }
+  },
+
+  // 将代码片段移入集合（collectionId 为 null 时移出）
+  async moveSnippets(ids: number[], collectionId: number | null): Promise<number> {
+    try {
+      return await invoke<number>("move_snippets_to_collection", {
+        ids,
+        collectionId,
+      });
+    } catch (error) {
+      console.error("Failed to move snippets to collection:", error);
+      throw error;
+    }
+  },
+
+  // 获取集合（含子集合）中的代码片段
+  async getSnippets(collectionId: number): Promise<CodeSnippet[]> {
+    try {
+      const rows = await invoke<RawSnippetRow[]>("list_snippets", {
+        filter: { collection_id: collectionId },
+      });
+      return rows.map(fromRawSnippetRow);
+    } catch (error) {
+      console.error("Failed to get collection snippets:", error);
       throw error;
     }
   },
@@ -357,47 +412,117 @@ export const snippetApi = {
 
 // 剪贴板 API
 export const clipboardApi = {
-  // 添加剪贴板项
-  async add(content: string): Promise<ClipboardItem> {
+  // 添加剪贴板项（由 Rust 端写入，启用加密时加密存储；旧数据由后台保留策略定期清理）
+  // 包含密钥且 clipboard_secret_action 为 skip 时不保存，返回 null
+  async add(content: string): Promise<ClipboardItem | null> {
     try {
-      const database = await getDatabase();
-      const timestamp = await getCurrentTimestamp();
+      return await invoke<ClipboardItem | null>("add_clipboard_item", {
+        content,
+      });
+    } catch (error) {
+      console.error("Failed to add clipboard item:", error);
+      throw error;
+    }
+  },
 
-      const result = await database.execute(
-        "INSERT INTO clipboard_items (content, created_at, updated_at) VALUES ($1, $2, $3)",
-        [content, timestamp, timestamp]
+  // 记录监听到的剪贴板内容，被记录规则排除或已暂停时返回 null
+  async capture(content: string): Promise<ClipboardItem | null> {
+    try {
+      return await invoke<ClipboardItem | null>("capture_clipboard_item", {
+        content,
+      });
+    } catch (error) {
+      console.error("Failed to capture clipboard item:", error);
+      throw error;
+    }
+  },
+
+  // 把剪贴板条目转换为代码片段：自动检测语言，未提供标题时取第一行有意义的文本，
+  // 沿用条目的创建时间；deleteItem 为 true 时转换后删除该条目
+  async promote(
+    id: number,
+    options: { title?: string; tags?: string[]; deleteItem?: boolean } = {}
+  ): Promise<CodeSnippet> {
+    try {
+      const row = await invoke<RawSnippetRow>("promote_clipboard_item", {
+        id,
+        title: options.title,
+        tags: options.tags,
+        deleteItem: options.deleteItem,
+      });
+      return fromRawSnippetRow(row);
+    } catch (error) {
+      console.error("Failed to promote clipboard item:", error);
+      throw error;
+    }
+  },
+
+  // 读取系统剪贴板（文本、HTML、图片或文件列表）并记录，内容未变化、已存在或被排除时返回 null
+  async captureSystem(): Promise<ClipboardItem | null> {
+    try {
+      return await invoke<ClipboardItem | null>("capture_system_clipboard");
+    } catch (error) {
+      console.error("Failed to capture system clipboard:", error);
+      throw error;
+    }
+  },
+
+  // 获取剪贴板记录规则与暂停状态
+  async getCaptureStatus(): Promise<ClipboardCaptureStatus> {
+    try {
+      return await invoke<ClipboardCaptureStatus>(
+        "get_clipboard_capture_status"
       );
+    } catch (error) {
+      console.error("Failed to get clipboard capture status:", error);
+      throw error;
+    }
+  },
 
-      // 从设置中读取最大保留条数，默认为 100
-      let maxItems = 100;
-      try {
-        const settingResult = await database.select<Array<{ value: string }>>(
-          "SELECT value FROM user_settings WHERE key = 'max_clipboard_items'"
-        );
-        if (settingResult.length > 0) {
-          maxItems = parseInt(settingResult[0].value) || 100;
-        }
-      } catch (error) {
-        console.warn(
-          "Failed to read max_clipboard_items setting, using default 100:",
-          error
-        );
-      }
-
-      // 限制剪贴板历史记录数量
-      await database.execute(
-        "DELETE FROM clipboard_items WHERE id NOT IN (SELECT id FROM clipboard_items ORDER BY created_at DESC LIMIT ?)",
-        [maxItems]
+  // 设置剪贴板记录规则
+  async setCaptureRules(rules: CaptureRules): Promise<ClipboardCaptureStatus> {
+    try {
+      return await invoke<ClipboardCaptureStatus>(
+        "set_clipboard_capture_rules",
+        { rules }
       );
+    } catch (error) {
+      console.error("Failed to set clipboard capture rules:", error);
+      throw error;
+    }
+  },
 
-      return {
-        id: result.lastInsertId!,
-        content,
-        created_at: timestamp,
-        updated_at: timestamp,
-      };
+  // 暂停记录剪贴板 minutes 分钟
+  async pauseCapture(minutes: number): Promise<ClipboardCaptureStatus> {
+    try {
+      return await invoke<ClipboardCaptureStatus>("pause_clipboard_capture", {
+        minutes,
+      });
     } catch (error) {
-      console.error("Failed to add clipboard item:", error);
+      console.error("Failed to pause clipboard capture:", error);
+      throw error;
+    }
+  },
+
+  // 恢复记录剪贴板
+  async resumeCapture(): Promise<ClipboardCaptureStatus> {
+    try {
+      return await invoke<ClipboardCaptureStatus>("resume_clipboard_capture");
+    } catch (error) {
+      console.error("Failed to resume clipboard capture:", error);
+      throw error;
+    }
+  },
+
+  // 开启或关闭剪贴板记录
+  async setCaptureEnabled(enabled: boolean): Promise<ClipboardCaptureStatus> {
+    try {
+      return await invoke<ClipboardCaptureStatus>(
+        "set_clipboard_capture_enabled",
+        { enabled }
+      );
+    } catch (error) {
+      console.error("Failed to set clipboard capture enabled:", error);
       throw error;
     }
   },
@@ -405,54 +530,27 @@ export const clipboardApi = {
   // 获取剪贴板历史
   async getHistory(limit: number = 100): Promise<ClipboardItem[]> {
     try {
-      const database = await getDatabase();
-      const rows = await database.select<
-        Array<{
-          id: number;
-          content: string;
-          created_at: string;
-          updated_at: string;
-        }>
-      >("SELECT * FROM clipboard_items ORDER BY created_at DESC LIMIT $1", [
-        limit,
-      ]);
-
-      return rows.map((row) => ({
-        id: row.id,
-        content: row.content,
-        created_at: row.created_at,
-        updated_at: row.updated_at,
-      }));
+      return await invoke<ClipboardItem[]>("get_clipboard_history", { limit });
     } catch (error) {
       console.error("Failed to get clipboard history:", error);
       throw error;
     }
   },
 
+  // 按关键词（全文索引）、时间范围、类型和语言分页搜索剪贴板历史
+  async search(query: ClipboardQuery = {}): Promise<ClipboardPage> {
+    try {
+      return await invoke<ClipboardPage>("search_clipboard", { query });
+    } catch (error) {
+      console.error("Failed to search clipboard history:", error);
+      throw error;
+    }
+  },
+
   // 根据 ID 获取剪贴板项
   async getById(id: number): Promise<ClipboardItem | null> {
     try {
-      const database = await getDatabase();
-      const rows = await database.select<
-        Array<{
-          id: number;
-          content: string;
-          created_at: string;
-          updated_at: string;
-        }>
-      >("SELECT * FROM clipboard_items WHERE id = $1", [id]);
-
-      if (rows.length === 0) {
-        return null;
-      }
-
-      const row = rows[0];
-      return {
-        id: row.id,
-        content: row.content,
-        created_at: row.created_at,
-        updated_at: row.updated_at,
-      };
+      return await invoke<ClipboardItem | null>("get_clipboard_item", { id });
     } catch (error) {
       console.error("Failed to get clipboard item by id:", error);
       throw error;
@@ -462,8 +560,7 @@ export const clipboardApi = {
   // 删除剪贴板项
   async delete(id: number): Promise<void> {
     try {
-      const database = await getDatabase();
-      await database.execute("DELETE FROM clipboard_items WHERE id = $1", [id]);
+      await invoke<number>("delete_clipboard_items", { ids: [id] });
     } catch (error) {
       console.error("Failed to delete clipboard item:", error);
       throw error;
@@ -473,8 +570,7 @@ export const clipboardApi = {
   // 清空剪贴板历史
   async clear(): Promise<void> {
     try {
-      const database = await getDatabase();
-      await database.execute("DELETE FROM clipboard_items");
+      await invoke<number>("clear_clipboard_history");
     } catch (error) {
       console.error("Failed to clear clipboard history:", error);
       throw error;
@@ -495,18 +591,314 @@ export const clipboardApi = {
     }
   },
 
-  // 清理超出限制的剪贴板数据
-  async cleanupExcessItems(maxItems: number): Promise<void> {
+  // 置顶或取消置顶剪贴板项，返回更新的数量
+  async setPinned(ids: number[], pinned: boolean): Promise<number> {
     try {
-      const database = await getDatabase();
+      return await invoke<number>("set_clipboard_items_pinned", {
+        ids,
+        pinned,
+      });
+    } catch (error) {
+      console.error("Failed to update clipboard pinned state:", error);
+      throw error;
+    }
+  },
 
-      // 删除超出限制的旧数据，保留最新的 maxItems 条记录
-      await database.execute(
-        "DELETE FROM clipboard_items WHERE id NOT IN (SELECT id FROM clipboard_items ORDER BY created_at DESC LIMIT ?)",
-        [maxItems]
-      );
+  // 获取剪贴板历史保留策略
+  async getRetention(): Promise<ClipboardRetentionStatus> {
+    try {
+      return await invoke<ClipboardRetentionStatus>("get_clipboard_retention");
+    } catch (error) {
+      console.error("Failed to get clipboard retention:", error);
+      throw error;
+    }
+  },
+
+  // 保存保留策略并立即按新策略清理
+  async setRetention(
+    policy: RetentionPolicy
+  ): Promise<ClipboardRetentionStatus> {
+    try {
+      return await invoke<ClipboardRetentionStatus>("set_clipboard_retention", {
+        policy,
+      });
+    } catch (error) {
+      console.error("Failed to set clipboard retention:", error);
+      throw error;
+    }
+  },
+
+  // 立即按当前策略清理剪贴板历史
+  async runRetention(): Promise<RetentionReport> {
+    try {
+      return await invoke<RetentionReport>("run_clipboard_retention");
+    } catch (error) {
+      console.error("Failed to run clipboard retention:", error);
+      throw error;
+    }
+  },
+};
+
+// ==================== 数据库统计 ====================
+
+export const statsApi = {
+  // 获取数据库统计信息
+  async get(): Promise<DatabaseStats> {
+    try {
+      return await invoke<DatabaseStats>("get_database_stats");
+    } catch (error) {
+      console.error("Failed to get database stats:", error);
+      throw error;
+    }
+  },
+};
+
+// ==================== 密钥检测 API ====================
+
+export const secretsApi = {
+  // 扫描文本中的密钥（AWS 密钥、GitHub 令牌、JWT、私钥、URL 中的密码等）
+  async scan(text: string): Promise<SecretFinding[]> {
+    try {
+      return await invoke<SecretFinding[]>("scan_secrets", { text });
+    } catch (error) {
+      console.error("Failed to scan secrets:", error);
+      throw error;
+    }
+  },
+};
+
+// ==================== 语言检测 API ====================
+
+export const languageApi = {
+  // 检测代码的编程语言，hint 为可选的文件名或扩展名
+  async detect(
+    content: string,
+    hint?: string
+  ): Promise<LanguageDetection> {
+    try {
+      return await invoke<LanguageDetection>("detect_language", {
+        content,
+        hint,
+      });
+    } catch (error) {
+      console.error("Failed to detect language:", error);
+      throw error;
+    }
+  },
+};
+
+// ==================== 界面语言 API ====================
+
+export const localeApi = {
+  // 保存界面语言并同步托盘菜单语言，返回实际使用的语言
+  async setLanguage(language: string): Promise<string> {
+    try {
+      return await invoke<string>("set_language", { language });
+    } catch (error) {
+      console.error("Failed to set language:", error);
+      throw error;
+    }
+  },
+};
+
+// ==================== 快速粘贴面板 API ====================
+
+export const paletteApi = {
+  // 在代码片段和剪贴板历史中搜索
+  async search(query: string, limit?: number): Promise<PaletteItem[]> {
+    try {
+      return await invoke<PaletteItem[]>("search_palette", { query, limit });
+    } catch (error) {
+      console.error("Failed to search palette:", error);
+      throw error;
+    }
+  },
+
+  // 把选中的结果写入系统剪贴板并隐藏面板
+  async select(source: PaletteSource, id: number): Promise<void> {
+    try {
+      await invoke("select_palette_item", { source, id });
+    } catch (error) {
+      console.error("Failed to select palette item:", error);
+      throw error;
+    }
+  },
+
+  async show(): Promise<void> {
+    await invoke("show_palette");
+  },
+
+  async hide(): Promise<void> {
+    await invoke("hide_palette");
+  },
+
+  // 获取全局快捷键（为空表示未启用）
+  async getShortcut(): Promise<string> {
+    try {
+      return await invoke<string>("get_palette_shortcut");
+    } catch (error) {
+      console.error("Failed to get palette shortcut:", error);
+      throw error;
+    }
+  },
+
+  // 设置全局快捷键，如 "CommandOrControl+Shift+Space"；为空时取消注册
+  async setShortcut(shortcut: string): Promise<string> {
+    try {
+      return await invoke<string>("set_palette_shortcut", { shortcut });
+    } catch (error) {
+      console.error("Failed to set palette shortcut:", error);
+      throw error;
+    }
+  },
+};
+
+// ==================== 数据库加密 API ====================
+
+export const encryptionApi = {
+  // 获取加密状态
+  async getStatus(): Promise<EncryptionStatus> {
+    try {
+      return await invoke<EncryptionStatus>("get_encryption_status");
+    } catch (error) {
+      console.error("Failed to get encryption status:", error);
+      throw error;
+    }
+  },
+
+  // 启用加密（remember 为 true 时把密钥保存到系统钥匙串）
+  async enable(passphrase: string, remember = false): Promise<EncryptionStatus> {
+    try {
+      return await invoke<EncryptionStatus>("enable_encryption", {
+        passphrase,
+        remember,
+      });
+    } catch (error) {
+      console.error("Failed to enable encryption:", error);
+      throw error;
+    }
+  },
+
+  // 关闭加密并解密全部数据
+  async disable(passphrase: string): Promise<EncryptionStatus> {
+    try {
+      return await invoke<EncryptionStatus>("disable_encryption", {
+        passphrase,
+      });
+    } catch (error) {
+      console.error("Failed to disable encryption:", error);
+      throw error;
+    }
+  },
+
+  // 解锁数据库
+  async unlock(passphrase: string, remember = false): Promise<EncryptionStatus> {
+    try {
+      return await invoke<EncryptionStatus>("unlock_database", {
+        passphrase,
+        remember,
+      });
+    } catch (error) {
+      console.error("Failed to unlock database:", error);
+      throw error;
+    }
+  },
+
+  // 锁定数据库
+  async lock(): Promise<EncryptionStatus> {
+    try {
+      return await invoke<EncryptionStatus>("lock_database");
     } catch (error) {
-      console.error("Failed to cleanup excess clipboard items:", error);
+      console.error("Failed to lock database:", error);
+      throw error;
+    }
+  },
+};
+
+// ==================== 数据库维护 API ====================
+
+export const maintenanceApi = {
+  // 在线备份数据库到指定路径
+  async backup(path: string): Promise<BackupInfo> {
+    try {
+      return await invoke<BackupInfo>("backup_database", { path });
+    } catch (error) {
+      console.error("Failed to back up database:", error);
+      throw error;
+    }
+  },
+
+  // 执行 VACUUM 和 ANALYZE
+  async optimize(): Promise<OptimizeReport> {
+    try {
+      return await invoke<OptimizeReport>("optimize_database");
+    } catch (error) {
+      console.error("Failed to optimize database:", error);
+      throw error;
+    }
+  },
+
+  // 检查数据库完整性
+  async checkIntegrity(): Promise<IntegrityReport> {
+    try {
+      return await invoke<IntegrityReport>("check_database_integrity");
+    } catch (error) {
+      console.error("Failed to check database integrity:", error);
+      throw error;
+    }
+  },
+
+  // 配置自动备份
+  async configureAutoBackup(options: {
+    enabled: boolean;
+    intervalHours?: number;
+    keep?: number;
+    dir?: string;
+  }): Promise<AutoBackupStatus> {
+    try {
+      return await invoke<AutoBackupStatus>("configure_auto_backup", options);
+    } catch (error) {
+      console.error("Failed to configure auto backup:", error);
+      throw error;
+    }
+  },
+
+  // 获取自动备份状态
+  async getAutoBackupStatus(): Promise<AutoBackupStatus> {
+    try {
+      return await invoke<AutoBackupStatus>("get_auto_backup_status");
+    } catch (error) {
+      console.error("Failed to get auto backup status:", error);
+      throw error;
+    }
+  },
+
+  // 从备份文件恢复数据库（恢复后需重新加载数据）
+  async restore(path: string): Promise<RestoreReport> {
+    try {
+      return await invoke<RestoreReport>("restore_backup", { path });
+    } catch (error) {
+      console.error("Failed to restore backup:", error);
+      throw error;
+    }
+  },
+
+  // 立即创建一个轮换备份
+  async backupNow(): Promise<BackupInfo> {
+    try {
+      return await invoke<BackupInfo>("create_backup_now");
+    } catch (error) {
+      console.error("Failed to create backup:", error);
+      throw error;
+    }
+  },
+
+  // 删除自动备份目录中的全部备份，返回删除的数量
+  async deleteAutoBackups(): Promise<number> {
+    try {
+      return await invoke<number>("delete_auto_backups");
+    } catch (error) {
+      console.error("Failed to delete auto backups:", error);
       throw error;
     }
   },
@@ -562,12 +954,30 @@ export const clipboardManagerApi = {
       throw error;
     }
   },
+
+  // 把剪贴板历史项按原类型（HTML、图片、文件列表）写回系统剪贴板
+  async restoreItem(itemId: number): Promise<void> {
+    try {
+      await invoke("set_clipboard_content", { itemId });
+    } catch (error) {
+      console.error("Failed to restore clipboard item:", error);
+      throw error;
+    }
+  },
 };
 
 // 导出主要 API
 export default {
   snippet: snippetApi,
+  collection: collectionApi,
   clipboard: clipboardApi,
+  stats: statsApi,
+  maintenance: maintenanceApi,
+  encryption: encryptionApi,
+  secrets: secretsApi,
+  language: languageApi,
+  locale: localeApi,
+  palette: paletteApi,
   direct: directDbApi,
   clipboardManager: clipboardManagerApi,
 };
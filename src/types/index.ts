@@ -6,13 +6,223 @@ export interface CodeSnippet {
   code: string;
   created_at?: string;
   updated_at?: string;
+  collection_id?: number | null;
+  pinned?: boolean;
+  use_count?: number;
+  last_used_at?: string | null;
+  // 检测到的密钥类型
+  secret_flags?: SecretKind[];
 }
 
+// 代码片段集合（可嵌套）
+export interface Collection {
+  id: number;
+  name: string;
+  parent_id: number | null;
+  sort_order: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface CollectionNode extends Collection {
+  snippet_count: number;
+  children: CollectionNode[];
+}
+
+// 数据库统计信息
+export interface NamedCount {
+  name: string;
+  count: number;
+}
+
+export interface DatabaseStats {
+  total_snippets: number;
+  total_clipboard_items: number;
+  languages: string[];
+  language_counts: NamedCount[];
+  tag_counts: NamedCount[];
+  db_size_bytes: number;
+  oldest_snippet_at: string | null;
+  newest_snippet_at: string | null;
+  oldest_clipboard_at: string | null;
+  newest_clipboard_at: string | null;
+  snippets_last_30_days: number;
+  clipboard_items_last_30_days: number;
+  daily_growth: { date: string; snippets: number; clipboard_items: number }[];
+  top_snippets: {
+    id: number;
+    title: string;
+    language: string;
+    pinned: boolean;
+    use_count: number;
+    last_used_at: string | null;
+  }[];
+}
+
+export interface BackupInfo {
+  path: string;
+  size_bytes: number;
+  created_at: string;
+}
+
+export interface OptimizeReport {
+  size_before_bytes: number;
+  size_after_bytes: number;
+  reclaimed_bytes: number;
+  duration_ms: number;
+}
+
+export interface IntegrityReport {
+  ok: boolean;
+  summary: string;
+  problems: string[];
+  foreign_key_problems: string[];
+  checked_at: string;
+  duration_ms: number;
+}
+
+export interface EncryptionStatus {
+  enabled: boolean;
+  unlocked: boolean;
+  remembered: boolean;
+}
+
+export type SecretKind =
+  | "aws_access_key"
+  | "aws_secret_key"
+  | "github_token"
+  | "slack_token"
+  | "jwt"
+  | "private_key"
+  | "url_password"
+  | "generic_secret";
+
+// 密钥检测结果（不包含密钥原文）
+export interface SecretFinding {
+  kind: SecretKind;
+  line: number;
+  preview: string;
+}
+
+// 语言检测结果（confidence 为 0 - 1）
+export interface LanguageDetection {
+  language: string;
+  confidence: number;
+}
+
+export interface RestoreReport {
+  restored_from: string;
+  backup_version: number;
+  schema_version: number;
+  migrated: boolean;
+  safety_copy: string;
+  restored_at: string;
+}
+
+export interface AutoBackupStatus {
+  config: {
+    enabled: boolean;
+    interval_hours: number;
+    keep: number;
+    dir: string;
+  };
+  running: boolean;
+  backups: BackupInfo[];
+}
+
+// 剪贴板内容类型
+export type ClipboardKind = "text" | "html" | "image" | "files";
+
 export interface ClipboardItem {
   id?: number;
+  // 文本内容；HTML 为纯文本形式，图片为尺寸说明，文件列表为每行一个路径
   content: string;
   created_at: string;
   updated_at: string;
+  kind?: ClipboardKind;
+  mime_type?: string;
+  // 图片缩略图（PNG data URL）
+  thumbnail?: string | null;
+  // 自动检测的编程语言（仅文本和 HTML 条目）
+  language?: string | null;
+  language_confidence?: number | null;
+  // 检测到的密钥类型（JSON 数组字符串），为空表示尚未扫描
+  secret_flags?: string | null;
+  // 置顶的条目排在最前，且不受保留策略清理
+  pinned?: boolean;
+}
+
+// 剪贴板历史查询条件（时间为 YYYY-MM-DD 或 YYYY-MM-DDTHH:MM:SS，两端都包含）
+export interface ClipboardQuery {
+  text?: string;
+  from?: string;
+  to?: string;
+  kinds?: ClipboardKind[];
+  language?: string;
+  // 只返回置顶（true）或未置顶（false）的条目
+  pinned?: boolean;
+  offset?: number;
+  limit?: number;
+}
+
+// 剪贴板历史查询结果（total 为满足条件的总条数）
+export interface ClipboardPage {
+  items: ClipboardItem[];
+  total: number;
+  offset: number;
+  limit: number;
+}
+
+// 剪贴板记录规则（长度为 0 表示不限制）
+export interface CaptureRules {
+  exclude_patterns: string[];
+  min_length: number;
+  max_length: number;
+  excluded_apps: string[];
+  quiet_start?: string | null; // HH:MM
+  quiet_end?: string | null; // HH:MM
+}
+
+export interface ClipboardCaptureStatus {
+  enabled: boolean;
+  paused_until: string | null;
+  rules: CaptureRules;
+}
+
+// 剪贴板历史保留策略（为 0 表示不限制）
+export interface RetentionPolicy {
+  max_items: number;
+  max_age_days: number;
+  max_total_mb: number;
+  keep_pinned: boolean;
+}
+
+export interface RetentionReport {
+  deleted_by_age: number;
+  deleted_by_count: number;
+  deleted_by_size: number;
+  remaining: number;
+  ran_at: string;
+}
+
+export interface ClipboardRetentionStatus {
+  policy: RetentionPolicy;
+  last_report: RetentionReport | null;
+}
+
+// 快速粘贴面板中的一条结果
+export type PaletteSource = "snippet" | "clipboard";
+
+export interface PaletteItem {
+  source: PaletteSource;
+  id: number;
+  // 代码片段标题，剪贴板条目为内容的第一行
+  title: string;
+  preview: string;
+  language: string | null;
+  // 剪贴板内容类型，代码片段为 null
+  kind: ClipboardKind | null;
+  pinned: boolean;
 }
 
 export interface ContextMenuState {